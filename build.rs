@@ -0,0 +1,56 @@
+//! Build-time facts for `raven --version --verbose`.
+//!
+//! Bug reports need the exact compiler build, so this records the git
+//! commit, the rustc that built the compiler, and the target triple as
+//! `RAVEN_BUILD_*` environment variables for `env!`. Each falls back to
+//! `unknown` when it cannot be read, for example when building from a
+//! source archive with no `.git` directory, so the build never fails on
+//! them.
+
+use std::process::Command;
+
+fn main() {
+    let commit = run("git", &["describe", "--always", "--tags"]);
+    let rustc = std::env::var("RUSTC").unwrap_or_else(|_| "rustc".to_string());
+    let rustc_version = run(&rustc, &["--version"]);
+    let target = std::env::var("TARGET").unwrap_or_default();
+
+    println!("cargo:rustc-env=RAVEN_BUILD_COMMIT={}", or_unknown(commit));
+    println!(
+        "cargo:rustc-env=RAVEN_BUILD_RUSTC={}",
+        or_unknown(rustc_version)
+    );
+    println!(
+        "cargo:rustc-env=RAVEN_BUILD_TARGET={}",
+        or_unknown(Some(target))
+    );
+
+    // Re-run when the checked-out commit moves (`HEAD` switches branch, or
+    // the branch it names gets a new commit), not on every source edit.
+    println!("cargo:rerun-if-changed=build.rs");
+    if let Ok(head) = std::fs::read_to_string(".git/HEAD") {
+        println!("cargo:rerun-if-changed=.git/HEAD");
+        if let Some(branch) = head.trim().strip_prefix("ref: ") {
+            let branch_ref = format!(".git/{}", branch);
+            if std::path::Path::new(&branch_ref).exists() {
+                println!("cargo:rerun-if-changed={}", branch_ref);
+            }
+        }
+    }
+}
+
+/// The trimmed stdout of `program args`, or `None` when it cannot run or
+/// exits unsuccessfully.
+fn run(program: &str, args: &[&str]) -> Option<String> {
+    let out = Command::new(program).args(args).output().ok()?;
+    if !out.status.success() {
+        return None;
+    }
+    Some(String::from_utf8(out.stdout).ok()?.trim().to_string())
+}
+
+fn or_unknown(value: Option<String>) -> String {
+    value
+        .filter(|v| !v.is_empty())
+        .unwrap_or_else(|| "unknown".to_string())
+}
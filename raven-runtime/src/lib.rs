@@ -17,11 +17,14 @@
 pub mod ffi_test;
 pub mod gc;
 pub mod object;
+pub mod profile;
 pub mod reflect;
 pub mod roots;
 pub mod sched;
+pub mod stack;
 pub mod stw;
 pub mod tls;
+pub mod trace;
 
 pub use gc::{
     raven_defer_enter_frame, raven_defer_push, raven_defer_run_frame, raven_gc_alloc,
@@ -33,13 +36,17 @@ pub use object::{
     raven_closure_captures, raven_closure_fn_ptr, raven_closure_new, raven_float_to_string,
     raven_int_to_string, raven_list_elements, raven_list_len, raven_list_new, raven_list_push,
     raven_map_bucket_count, raven_map_buckets, raven_map_new, raven_set_bucket_count,
-    raven_set_buckets, raven_set_new, raven_string_byte_at, raven_string_bytes, raven_string_cmp,
-    raven_string_concat, raven_string_eq, raven_string_from_byte, raven_string_from_bytes,
-    raven_string_len, raven_string_new, raven_string_substring, raven_struct_fields,
+    raven_set_buckets, raven_set_new, raven_string_byte_at, raven_string_bytes,
+    raven_string_char_at, raven_string_cmp, raven_string_concat, raven_string_eq,
+    raven_string_eq_ignore_case, raven_string_from_byte, raven_string_from_bytes, raven_string_len,
+    raven_string_new, raven_string_repeat, raven_string_substring, raven_struct_fields,
     raven_struct_new, Box as RavenBox, Closure as RavenClosure, List as RavenList, Map as RavenMap,
     MapEntry, ObjectHeader, Set as RavenSet, SetEntry, String as RavenString, OBJECT_ALIGN,
     TAG_BOX, TAG_CLOSURE, TAG_LIST, TAG_MAP, TAG_SET, TAG_STRING, TAG_STRUCT,
 };
+pub use profile::{
+    raven_profile_enter, raven_profile_exit, raven_profile_register, raven_profile_report,
+};
 pub use reflect::{
     raven_any_field_names, raven_any_get_field, raven_any_new, raven_any_payload,
     raven_any_set_field, raven_any_type_id, raven_any_type_name, raven_type_register,
@@ -48,6 +55,8 @@ pub use sched::{
     raven_channel_new, raven_channel_new_buffered, raven_channel_recv, raven_channel_send,
     raven_go_spawn, raven_go_yield,
 };
+pub use stack::raven_stack_guard_install;
+pub use trace::{raven_panic_at, raven_trace_register};
 
 use std::alloc::{self, Layout};
 use std::cell::RefCell;
@@ -434,6 +443,13 @@ pub extern "C" fn raven_dealloc(ptr: *mut u8, size: usize, align: usize) {
 /// `msg_len` may be zero, in which case `msg_ptr` is not dereferenced.
 #[no_mangle]
 pub extern "C" fn raven_panic(msg_ptr: *const u8, msg_len: usize) -> ! {
+    panic_exit(msg_ptr, msg_len, &[])
+}
+
+/// Write the `raven panic:` line, then a `call stack:` line when `calls`
+/// is non-empty, and exit with status 101. Shared by `raven_panic` and
+/// `trace::raven_panic_at`.
+fn panic_exit(msg_ptr: *const u8, msg_len: usize, calls: &[String]) -> ! {
     let msg = if msg_len == 0 || msg_ptr.is_null() {
         ""
     } else {
@@ -445,6 +461,9 @@ pub extern "C" fn raven_panic(msg_ptr: *const u8, msg_len: usize) -> ! {
     let mut handle = stderr.lock();
     // Best-effort write; we are about to exit either way.
     let _ = writeln!(handle, "raven panic: {msg}");
+    if !calls.is_empty() {
+        let _ = writeln!(handle, "call stack: {}", calls.join(" -> "));
+    }
     let _ = handle.flush();
     process::exit(101);
 }
@@ -550,6 +569,47 @@ pub extern "C" fn raven_env_has(name: *const object::String) -> bool {
     env_name(name).is_some_and(|n| std::env::var_os(n).is_some())
 }
 
+/// Set an environment variable for this process and the children it
+/// spawns. Returns `false`, leaving the environment unchanged, when `name`
+/// is empty or holds `=` or a NUL byte, or `value` holds a NUL byte, since
+/// `std::env::set_var` panics on those.
+///
+/// # Safety
+///
+/// `name` and `value` must be valid `raven_string_from_bytes`-built
+/// `String`s.
+#[no_mangle]
+pub extern "C" fn raven_env_set(name: *const object::String, value: *const object::String) -> bool {
+    let (Some(name), Some(value)) = (env_name(name), env_name(value)) else {
+        return false;
+    };
+    if name.is_empty() || name.contains(['=', '\0']) || value.contains('\0') {
+        return false;
+    }
+    std::env::set_var(name, value);
+    true
+}
+
+/// Every environment variable as `KEY=VALUE` text, joined by a NUL byte,
+/// in one read of the environment. A NUL cannot occur in a name or value,
+/// so it separates entries without risk of splitting one. Variables whose
+/// name or value is not valid UTF-8 are skipped. An empty environment
+/// yields an empty `String`.
+#[no_mangle]
+pub extern "C" fn raven_env_vars() -> *mut object::String {
+    let value = std::env::vars_os()
+        .filter_map(|(k, v)| {
+            Some(format!(
+                "{}={}",
+                k.into_string().ok()?,
+                v.into_string().ok()?
+            ))
+        })
+        .collect::<Vec<_>>()
+        .join("\0");
+    object::raven_string_from_bytes(value.as_ptr(), value.len())
+}
+
 /// Number of process arguments, including the program path at index 0.
 #[no_mangle]
 pub extern "C" fn raven_env_arg_count() -> i64 {
@@ -569,9 +629,11 @@ pub extern "C" fn raven_env_arg_at(index: i64) -> *mut object::String {
     object::raven_string_from_bytes(value.as_ptr(), value.len())
 }
 
-/// Terminate the process with `code`. Does not return.
+/// Terminate the process with `code`. Does not return. A profiled build
+/// prints its profile first, since `main` never returns to do it.
 #[no_mangle]
 pub extern "C" fn raven_env_exit(code: i64) -> ! {
+    profile::report();
     process::exit(code as i32);
 }
 
@@ -1178,6 +1240,16 @@ pub extern "C" fn raven_time_now_millis() -> i64 {
     chrono::Utc::now().timestamp_millis()
 }
 
+/// Milliseconds on a monotonic clock since the first call in this process.
+/// Unlike the wall clock it never steps backwards, so the difference of two
+/// readings is a true elapsed time.
+#[no_mangle]
+pub extern "C" fn raven_time_monotonic_millis() -> i64 {
+    static START: OnceLock<std::time::Instant> = OnceLock::new();
+    let start = START.get_or_init(std::time::Instant::now);
+    start.elapsed().as_millis() as i64
+}
+
 /// The UTC datetime for a Unix timestamp in seconds, or the epoch when the
 /// timestamp is out of chrono's representable range.
 fn time_from_ts(ts: i64) -> chrono::DateTime<chrono::Utc> {
@@ -2754,6 +2826,15 @@ mod tests {
         assert_eq!(drained[0], 1);
     }
 
+    #[test]
+    fn monotonic_millis_never_decreases_and_counts_a_sleep() {
+        let a = raven_time_monotonic_millis();
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        let b = raven_time_monotonic_millis();
+        assert!(a >= 0);
+        assert!(b - a >= 20, "slept 20ms but the clock moved {}ms", b - a);
+    }
+
     #[test]
     fn random_entropy_never_repeats_within_a_process() {
         // The call counter must make successive seeds distinct even when
@@ -2868,6 +2949,25 @@ mod tests {
         object::raven_string_from_bytes(s.as_ptr(), s.len())
     }
 
+    #[test]
+    fn env_set_refuses_names_set_var_would_panic_on() {
+        assert!(raven_env_set(
+            rv_string("RAVEN_RT_ENV_SET"),
+            rv_string("on")
+        ));
+        assert_eq!(std::env::var("RAVEN_RT_ENV_SET").as_deref(), Ok("on"));
+        assert!(!raven_env_set(rv_string(""), rv_string("x")));
+        assert!(!raven_env_set(rv_string("A=B"), rv_string("x")));
+        assert!(!raven_env_set(rv_string("A\0B"), rv_string("x")));
+        assert!(!raven_env_set(
+            rv_string("RAVEN_RT_ENV_SET"),
+            rv_string("x\0y")
+        ));
+        assert_eq!(std::env::var("RAVEN_RT_ENV_SET").as_deref(), Ok("on"));
+        let listed = env_name(raven_env_vars()).expect("UTF-8 listing");
+        assert!(listed.split('\0').any(|e| e == "RAVEN_RT_ENV_SET=on"));
+    }
+
     #[test]
     fn parked_accept_does_not_hold_the_registry_lock() {
         // A connection that has not yet arrived must not serialize other
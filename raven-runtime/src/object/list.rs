@@ -208,6 +208,47 @@ pub extern "C" fn raven_list_get(l: *const List, index: u32, out: *mut u8) -> u3
     1
 }
 
+/// Allocate a new list holding the same elements as `l`, in one copy of
+/// the element buffer. The new list has the same element shape and GC
+/// pointer flag, `len == cap == raven_list_len(l)`, and shares nothing
+/// with `l`, so pushing to or assigning through either leaves the other
+/// alone. The elements themselves are copied bytewise: a GC pointer
+/// element points at the same object from both lists.
+///
+/// Returns null when `l` is null or on allocation failure.
+#[no_mangle]
+pub extern "C" fn raven_list_copy(l: *const List) -> *mut List {
+    if l.is_null() {
+        return ptr::null_mut();
+    }
+    // SAFETY: caller passes a pointer obtained from a constructor.
+    let (len, elem_size, elem_align, gc_ptrs) = unsafe {
+        (
+            (*l).header.len,
+            (*l).element_size,
+            (*l).element_align,
+            (*l).elements_are_gc_ptrs,
+        )
+    };
+    let copy = raven_list_new(elem_size, elem_align, len, gc_ptrs);
+    if copy.is_null() {
+        return ptr::null_mut();
+    }
+    // SAFETY: both buffers hold at least `len * elem_size` bytes, the
+    // source's initialised, and they are distinct allocations.
+    unsafe {
+        if len > 0 && elem_size > 0 {
+            ptr::copy_nonoverlapping(
+                (*l).elements,
+                (*copy).elements,
+                (len as usize) * (elem_size as usize),
+            );
+        }
+        (*copy).header.len = len;
+    }
+    copy
+}
+
 /// Size of the in-memory `List` object.
 pub(crate) const fn size_of_list() -> usize {
     std::mem::size_of::<List>()
@@ -451,6 +492,34 @@ mod tests {
         unsafe { drop_list_for_test(l) };
     }
 
+    #[test]
+    fn copy_is_independent_of_the_source() {
+        let l = raven_list_new(8, 8, 0, 1);
+        for v in [4u64, 5, 6] {
+            raven_list_push(l, &v as *const u64 as *const u8);
+        }
+        let c = raven_list_copy(l);
+        assert!(!c.is_null());
+        assert_eq!(raven_list_len(c), 3);
+        // SAFETY: c came from the constructor.
+        unsafe {
+            assert_eq!((*c).header.cap, 3);
+            assert_eq!((*c).elements_are_gc_ptrs, 1);
+        }
+        // Changing the source after the copy leaves the copy alone.
+        let extra = 7u64;
+        raven_list_push(l, &extra as *const u64 as *const u8);
+        let mut out: u64 = 0;
+        assert_eq!(raven_list_pop(c, &mut out as *mut u64 as *mut u8), 1);
+        assert_eq!(out, 6);
+        assert_eq!(raven_list_len(l), 4);
+        assert!(raven_list_copy(std::ptr::null()).is_null());
+        unsafe {
+            drop_list_for_test(c);
+            drop_list_for_test(l);
+        }
+    }
+
     #[test]
     fn null_accessors_are_safe() {
         assert_eq!(raven_list_len(std::ptr::null()), 0);
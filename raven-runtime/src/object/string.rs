@@ -245,6 +245,110 @@ pub extern "C" fn raven_string_cmp(a: *const String, b: *const String) -> i64 {
     }
 }
 
+/// Compare two strings by content, ignoring ASCII case. Returns `1` when
+/// they hold the same bytes after folding `A`-`Z` to `a`-`z`, `0`
+/// otherwise. Other bytes, including non-ASCII UTF-8, must match exactly,
+/// matching the ASCII-only case mapping of `std/string`. Either pointer may
+/// be null and is treated as the empty string.
+///
+/// Backs the `==~`/`!=~` operators on `String`.
+#[no_mangle]
+pub extern "C" fn raven_string_eq_ignore_case(a: *const String, b: *const String) -> i8 {
+    if a == b {
+        return 1;
+    }
+    let a_len = raven_string_len(a) as usize;
+    let b_len = raven_string_len(b) as usize;
+    if a_len != b_len {
+        return 0;
+    }
+    if a_len == 0 {
+        return 1;
+    }
+    let a_bytes = raven_string_bytes(a);
+    let b_bytes = raven_string_bytes(b);
+    if a_bytes.is_null() || b_bytes.is_null() {
+        return 0;
+    }
+    // SAFETY: both buffers hold `a_len == b_len` valid bytes.
+    let (a_slice, b_slice) = unsafe {
+        (
+            std::slice::from_raw_parts(a_bytes, a_len),
+            std::slice::from_raw_parts(b_bytes, b_len),
+        )
+    };
+    a_slice.eq_ignore_ascii_case(b_slice) as i8
+}
+
+/// Repeat `s` `count` times into a freshly allocated string. A `count` of
+/// zero or less yields the empty string. Panics when the result would
+/// exceed the `u32` length limit: a null here would read back as an empty
+/// string, so an overflowing repeat would otherwise pass silently.
+///
+/// Backs `String * Int` (and `Int * String`).
+#[no_mangle]
+pub extern "C" fn raven_string_repeat(s: *const String, count: i64) -> *mut String {
+    let len = raven_string_len(s) as usize;
+    let count = usize::try_from(count).unwrap_or(0);
+    let total_u32 = match len.checked_mul(count).map(u32::try_from) {
+        Some(Ok(v)) => v,
+        _ => {
+            let msg = format!(
+                "string repeat too long: {} bytes * {} exceeds the {} byte limit",
+                len,
+                count,
+                u32::MAX
+            );
+            crate::raven_panic(msg.as_ptr(), msg.len())
+        }
+    };
+    let out = raven_string_new(total_u32);
+    if out.is_null() {
+        return ptr::null_mut();
+    }
+    if total_u32 > 0 {
+        // SAFETY: out has `len * count` bytes of capacity, and each copy
+        // writes `len` bytes from s's buffer into its own slot.
+        unsafe {
+            let src = raven_string_bytes(s);
+            let dst = (*out).bytes;
+            for i in 0..count {
+                ptr::copy_nonoverlapping(src, dst.add(i * len), len);
+            }
+            (*out).header.len = total_u32;
+        }
+    }
+    out
+}
+
+/// Return the `i`th character (Unicode scalar) of `s`, counting characters
+/// rather than bytes, so `"café"[3]` is `'é'`. Invalid UTF-8 decodes as
+/// `U+FFFD`, one per maximal invalid sequence. Panics with "string index
+/// out of bounds" when `i` is negative or not below the character count.
+///
+/// Backs `s[i]` on a `String`.
+#[no_mangle]
+pub extern "C" fn raven_string_char_at(s: *const String, i: i64) -> u32 {
+    let len = raven_string_len(s) as usize;
+    let bytes = raven_string_bytes(s);
+    let slice = if len == 0 || bytes.is_null() {
+        &[][..]
+    } else {
+        // SAFETY: the buffer holds `len` valid bytes.
+        unsafe { std::slice::from_raw_parts(bytes, len) }
+    };
+    let ch = usize::try_from(i)
+        .ok()
+        .and_then(|i| std::string::String::from_utf8_lossy(slice).chars().nth(i));
+    match ch {
+        Some(c) => c as u32,
+        None => {
+            let msg = "string index out of bounds";
+            crate::raven_panic(msg.as_ptr(), msg.len())
+        }
+    }
+}
+
 /// Allocate a fresh `String` holding the half-open byte range
 /// `[start, end)` of `s`. The bounds are signed and clamped to
 /// `0..=len` (a negative bound clamps to 0), and a `start` past `end`
@@ -648,6 +752,64 @@ mod tests {
         }
     }
 
+    #[test]
+    fn string_eq_ignore_case_folds_ascii_only() {
+        let hello = raven_string_from_bytes(b"Hello".as_ptr(), 5);
+        let lower = raven_string_from_bytes(b"hello".as_ptr(), 5);
+        let help = raven_string_from_bytes(b"help!".as_ptr(), 5);
+        let e_acute = raven_string_from_bytes("\u{e9}".as_ptr(), 2);
+        let e_acute_upper = raven_string_from_bytes("\u{c9}".as_ptr(), 2);
+        assert_eq!(raven_string_eq_ignore_case(hello, lower), 1);
+        assert_eq!(raven_string_eq_ignore_case(hello, help), 0);
+        // Non-ASCII bytes are compared exactly.
+        assert_eq!(raven_string_eq_ignore_case(e_acute, e_acute_upper), 0);
+        assert_eq!(
+            raven_string_eq_ignore_case(std::ptr::null(), std::ptr::null()),
+            1
+        );
+        unsafe {
+            drop_string_for_test(hello);
+            drop_string_for_test(lower);
+            drop_string_for_test(help);
+            drop_string_for_test(e_acute);
+            drop_string_for_test(e_acute_upper);
+        }
+    }
+
+    #[test]
+    fn string_repeat_concatenates_copies() {
+        let ab = raven_string_from_bytes(b"ab".as_ptr(), 2);
+        let three = raven_string_repeat(ab, 3);
+        assert_eq!(read(three), "ababab");
+        // Zero and negative counts yield the empty string.
+        let none = raven_string_repeat(ab, 0);
+        assert_eq!(read(none), "");
+        let negative = raven_string_repeat(ab, -2);
+        assert_eq!(read(negative), "");
+        unsafe {
+            drop_string_for_test(ab);
+            drop_string_for_test(three);
+            drop_string_for_test(none);
+            drop_string_for_test(negative);
+        }
+    }
+
+    #[test]
+    fn char_at_counts_characters_not_bytes() {
+        let cafe = "café";
+        let s = raven_string_from_bytes(cafe.as_ptr(), cafe.len());
+        assert_eq!(raven_string_char_at(s, 0), 'c' as u32);
+        assert_eq!(raven_string_char_at(s, 3), 'é' as u32);
+        // Invalid UTF-8 reads as the replacement character.
+        let bad = raven_string_from_bytes(b"a\xffb".as_ptr(), 3);
+        assert_eq!(raven_string_char_at(bad, 1), 0xFFFD);
+        assert_eq!(raven_string_char_at(bad, 2), 'b' as u32);
+        unsafe {
+            drop_string_for_test(s);
+            drop_string_for_test(bad);
+        }
+    }
+
     #[test]
     fn substring_extracts_clamped_range() {
         let s = raven_string_from_bytes(b"hello".as_ptr(), 5);
@@ -0,0 +1,263 @@
+//! Call counts and timings for `raven build --profile`.
+//!
+//! A profiled build calls [`raven_profile_enter`] at the top of every Raven
+//! function and [`raven_profile_exit`] before each of its returns, passing
+//! the function's index in the table the entry shim registers with
+//! [`raven_profile_register`] (the same table panic call stacks read). When
+//! `main` returns, or the program calls `exit`, [`report`] prints one row
+//! per called function to stderr, slowest first. An ordinary build emits
+//! none of these calls and never registers a table, so it pays nothing.
+//!
+//! A function's time is the wall-clock time during which a goroutine had at
+//! least one call to it open, summed over goroutines. A recursive call
+//! overlaps the outer call in the same goroutine and adds no time of its own.
+//! The time includes the function's callees.
+//!
+//! Each OS thread counts into its own table, which only that thread writes,
+//! so a call takes no lock; [`report`] merges the tables. Goroutines move
+//! between worker threads, so the calls a goroutine has open travel with it
+//! instead: the scheduler swaps them in and out of the thread around each
+//! resume with [`swap_open`].
+
+use std::cell::{OnceCell, RefCell};
+use std::io::Write;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+/// Running totals for one function on one thread.
+#[derive(Default)]
+struct Counter {
+    calls: AtomicU64,
+    nanos: AtomicU64,
+}
+
+/// The open calls to one function in the running goroutine.
+#[derive(Clone, Copy, Default)]
+pub(crate) struct OpenCalls {
+    depth: u32,
+    /// When `depth` last rose from zero.
+    since: Option<Instant>,
+}
+
+/// Every Raven function's display name in table order. Set once by the entry
+/// shim of a profiled build.
+static NAMES: OnceLock<Vec<&'static str>> = OnceLock::new();
+
+/// Every thread's table, for [`report`] to merge. A thread adds its own the
+/// first time it enters a profiled function.
+static TABLES: Mutex<Vec<Arc<[Counter]>>> = Mutex::new(Vec::new());
+
+/// Set by the first [`report`], so an `exit` after `main` returned (or a
+/// second `exit`) does not print the table twice.
+static REPORTED: AtomicBool = AtomicBool::new(false);
+
+thread_local! {
+    /// This thread's table, indexed by function id.
+    static TABLE: OnceCell<Arc<[Counter]>> = const { OnceCell::new() };
+
+    /// The open calls of the goroutine this thread is running, indexed by
+    /// function id and grown on demand.
+    static OPEN: RefCell<Vec<OpenCalls>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Register the function table for a profiled build.
+///
+/// `table` has the layout of `trace::raven_trace_register`: `count` entries
+/// of four pointer-sized words, of which the third and fourth are the
+/// address and byte length of the function's UTF-8 display name. A
+/// function's index in the table is the id the enter and exit hooks pass.
+/// Only the first registration takes effect.
+///
+/// # Safety
+///
+/// `table` must point to `count * 4` readable words, and every name must
+/// be static UTF-8 data that lives for the rest of the program.
+#[no_mangle]
+pub extern "C" fn raven_profile_register(table: *const usize, count: usize) {
+    if table.is_null() {
+        return;
+    }
+    // SAFETY: the caller guarantees `count * 4` readable words.
+    let words = unsafe { std::slice::from_raw_parts(table, count * 4) };
+    let names = words
+        .chunks_exact(4)
+        .map(|entry| {
+            // SAFETY: the caller guarantees each name is static UTF-8.
+            let bytes = unsafe { std::slice::from_raw_parts(entry[2] as *const u8, entry[3]) };
+            std::str::from_utf8(bytes).unwrap_or("<invalid utf-8>")
+        })
+        .collect();
+    let _ = NAMES.set(names);
+}
+
+/// Run `f` on this thread's counter for function `id`, creating the table on
+/// the thread's first call. A no-op before registration or for an id past the
+/// table.
+fn with_counter(id: u32, f: impl FnOnce(&Counter)) {
+    let Some(names) = NAMES.get() else {
+        return;
+    };
+    TABLE.with(|t| {
+        let table = t.get_or_init(|| {
+            let table: Arc<[Counter]> = names.iter().map(|_| Counter::default()).collect();
+            TABLES
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .push(table.clone());
+            table
+        });
+        if let Some(c) = table.get(id as usize) {
+            f(c);
+        }
+    });
+}
+
+/// Add `n` to a counter only its own thread writes.
+fn bump(counter: &AtomicU64, n: u64) {
+    counter.store(counter.load(Ordering::Relaxed) + n, Ordering::Relaxed);
+}
+
+/// Count a call to the function with index `id`.
+#[no_mangle]
+pub extern "C" fn raven_profile_enter(id: u32) {
+    if NAMES.get().is_none() {
+        return;
+    }
+    with_counter(id, |c| bump(&c.calls, 1));
+    OPEN.with(|open| {
+        let mut open = open.borrow_mut();
+        let i = id as usize;
+        if open.len() <= i {
+            open.resize(i + 1, OpenCalls::default());
+        }
+        if open[i].depth == 0 {
+            open[i].since = Some(Instant::now());
+        }
+        open[i].depth += 1;
+    });
+}
+
+/// Close a call to the function with index `id`.
+#[no_mangle]
+pub extern "C" fn raven_profile_exit(id: u32) {
+    if NAMES.get().is_none() {
+        return;
+    }
+    let elapsed = OPEN.with(|open| {
+        let mut open = open.borrow_mut();
+        let calls = open.get_mut(id as usize)?;
+        calls.depth = calls.depth.saturating_sub(1);
+        if calls.depth > 0 {
+            return None;
+        }
+        calls.since.take().map(|since| since.elapsed())
+    });
+    if let Some(elapsed) = elapsed {
+        with_counter(id, |c| bump(&c.nanos, elapsed.as_nanos() as u64));
+    }
+}
+
+/// Exchange the open calls on this thread with `open`. The scheduler calls
+/// it with a goroutine's saved calls before resuming the goroutine and again
+/// after it suspends, so the calls follow the goroutine to whichever worker
+/// runs it next. Does nothing in a build that is not profiled.
+pub(crate) fn swap_open(open: &mut Vec<OpenCalls>) {
+    if NAMES.get().is_none() {
+        return;
+    }
+    OPEN.with(|o| std::mem::swap(&mut *o.borrow_mut(), open));
+}
+
+/// Print the profile when `main` returns. Emitted by the entry shim of a
+/// profiled build.
+#[no_mangle]
+pub extern "C" fn raven_profile_report() {
+    report();
+}
+
+/// Print the profile table to stderr, once. A no-op unless a profiled build
+/// registered its table. Calls the reporting goroutine still has open
+/// (`main` itself, when the program exits from inside it) are timed up to
+/// now.
+pub fn report() {
+    let Some(names) = NAMES.get() else {
+        return;
+    };
+    if REPORTED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    let mut rows: Vec<(&str, u64, Duration)> = names
+        .iter()
+        .map(|name| (*name, 0, Duration::ZERO))
+        .collect();
+    for table in TABLES.lock().unwrap_or_else(|e| e.into_inner()).iter() {
+        for (row, c) in rows.iter_mut().zip(table.iter()) {
+            row.1 += c.calls.load(Ordering::Relaxed);
+            row.2 += Duration::from_nanos(c.nanos.load(Ordering::Relaxed));
+        }
+    }
+    let now = Instant::now();
+    OPEN.with(|open| {
+        for (row, calls) in rows.iter_mut().zip(open.borrow().iter()) {
+            if let Some(since) = calls.since {
+                row.2 += now - since;
+            }
+        }
+    });
+    rows.retain(|r| r.1 > 0);
+    if rows.is_empty() {
+        return;
+    }
+    rows.sort_by(|a, b| b.2.cmp(&a.2).then(b.1.cmp(&a.1)).then(a.0.cmp(b.0)));
+    let stderr = std::io::stderr();
+    let mut out = stderr.lock();
+    let _ = write!(out, "{}", render(&rows));
+    let _ = out.flush();
+}
+
+/// The table for `rows` of (name, calls, total time), one line each under
+/// a header, with the name column as wide as the longest name.
+fn render(rows: &[(&str, u64, Duration)]) -> String {
+    let width = rows
+        .iter()
+        .map(|r| r.0.chars().count())
+        .chain(["function".len()])
+        .max()
+        .unwrap_or(0);
+    let mut out = format!(
+        "{:<width$}  {:>10}  {:>12}  {:>12}\n",
+        "function", "calls", "total ms", "avg ms"
+    );
+    for (name, calls, total) in rows {
+        let ms = total.as_secs_f64() * 1000.0;
+        out.push_str(&format!(
+            "{:<width$}  {:>10}  {:>12.3}  {:>12.3}\n",
+            name,
+            calls,
+            ms,
+            ms / *calls as f64
+        ));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_aligns_columns_under_the_header() {
+        let table = render(&[
+            ("fib", 177, Duration::from_millis(12)),
+            ("Counter.bump", 4, Duration::from_micros(500)),
+        ]);
+        let lines: Vec<&str> = table.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("function    "), "{table}");
+        assert!(lines[1].starts_with("fib         "), "{table}");
+        assert!(lines[1].ends_with("12.000         0.068"), "{table}");
+        assert!(lines[2].contains("Counter.bump"), "{table}");
+        assert!(lines.iter().all(|l| l.len() == lines[0].len()), "{table}");
+    }
+}
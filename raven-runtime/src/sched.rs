@@ -16,6 +16,7 @@ use crate::gc::{
     set_extra_roots_hook, take_root_chain, RootSlot, SavedRoots,
 };
 use crate::object::Closure;
+use crate::profile::{swap_open, OpenCalls};
 use corosensei::{Coroutine, CoroutineResult, Yielder};
 use std::cell::Cell;
 use std::collections::{HashMap, HashSet, VecDeque};
@@ -66,6 +67,10 @@ struct Goroutine {
     /// slot stays stale, crashing the next suspend. Null until the first run, and
     /// for goroutine 0 (main), which suspends via a condvar, not a yielder.
     yielder: *const GoYielder,
+    /// The profiled calls this goroutine has open while it is parked, swapped
+    /// onto the worker that resumes it. Always empty in a build that is not
+    /// profiled, and for goroutine 0 (main), whose calls stay on its thread.
+    profile_open: Vec<OpenCalls>,
 }
 
 /// A channel: a bounded queue of pointer-width value slots plus the wait
@@ -254,6 +259,7 @@ impl Scheduler {
                 roots: (Vec::new(), Vec::new(), Vec::new()),
                 spawn_root: std::ptr::null_mut(),
                 yielder: std::ptr::null(),
+                profile_open: Vec::new(),
             },
         );
         Scheduler {
@@ -359,6 +365,7 @@ pub extern "C" fn raven_go_spawn(closure: *mut Closure) {
                 // while the goroutine runs.
                 spawn_root: closure as *mut u8,
                 yielder: std::ptr::null(),
+                profile_open: Vec::new(),
             },
         );
         sched.ready.push_back(id);
@@ -479,9 +486,13 @@ fn run_goroutine(id: usize) {
             .unwrap_or_default()
     });
     install_root_chain(saved);
-    let (mut coro, yielder) = with_sched(|sched| {
+    let (mut coro, yielder, mut open) = with_sched(|sched| {
         let g = sched.goroutines.get_mut(&id).expect("live goroutine");
-        (g.coro.take().expect("coroutine handle"), g.yielder)
+        (
+            g.coro.take().expect("coroutine handle"),
+            g.yielder,
+            std::mem::take(&mut g.profile_open),
+        )
     });
     // Republish the yielder into this worker's per-thread slot before resuming,
     // so a goroutine that suspended on another worker finds its slot correct on
@@ -490,7 +501,9 @@ fn run_goroutine(id: usize) {
     if !yielder.is_null() {
         CURRENT_YIELDER.with(|y| y.set(yielder));
     }
+    swap_open(&mut open);
     let result = coro.resume(());
+    swap_open(&mut open);
     // Save this goroutine's live roots and clear `running` before leaving the
     // running state, so once the collector may proceed the saved chain is
     // authoritative again.
@@ -499,6 +512,7 @@ fn run_goroutine(id: usize) {
         if let Some(g) = sched.goroutines.get_mut(&id) {
             g.coro = Some(coro);
             g.roots = live;
+            g.profile_open = open;
         }
         sched.running.remove(&id);
         match result {
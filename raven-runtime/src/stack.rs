@@ -0,0 +1,130 @@
+//! Stack overflow reporting for compiled Raven programs.
+//!
+//! Unbounded recursion runs the main thread off the end of its stack and into
+//! the guard region below it, which the OS reports as `SIGSEGV` (`SIGBUS` on
+//! macOS). A compiled program does not go through Rust's std startup, so
+//! nothing turns that into a message and the process dies with a bare
+//! "Segmentation fault". The entry shim calls [`raven_stack_guard_install`]
+//! first, which records the main stack's guard range and installs a handler
+//! on an alternate signal stack (the overflowed stack has no room left to run
+//! one). A fault inside the range prints a `raven panic:` line and exits with
+//! the panic status, 101. Any other fault restores the default action and
+//! returns, so the faulting access re-executes and kills the process exactly
+//! as before.
+//!
+//! Only the main thread's stack is watched. An overflow on a goroutine stack
+//! still surfaces as a plain segfault.
+
+/// Bytes below the lowest mapped stack address still treated as an overflow.
+/// Linux keeps an unmapped gap of 256 pages under a growing stack, and a large
+/// frame can step well into it before touching memory.
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+const GUARD_SPAN: usize = 1 << 20;
+
+/// Size of the alternate signal stack the handler runs on.
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+const ALT_STACK_SIZE: usize = 64 * 1024;
+
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+mod imp {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::{ALT_STACK_SIZE, GUARD_SPAN};
+
+    /// The `[start, end)` address range a fault must land in to count as a
+    /// main-stack overflow. Both zero until the guard is installed.
+    static GUARD_START: AtomicUsize = AtomicUsize::new(0);
+    static GUARD_END: AtomicUsize = AtomicUsize::new(0);
+
+    const MESSAGE: &[u8] =
+        b"raven panic: stack overflow: recursion went deeper than the stack allows\n";
+
+    /// Lowest address of the calling thread's stack.
+    #[cfg(target_os = "linux")]
+    unsafe fn stack_low() -> Option<usize> {
+        let mut attr: libc::pthread_attr_t = unsafe { std::mem::zeroed() };
+        if unsafe { libc::pthread_getattr_np(libc::pthread_self(), &mut attr) } != 0 {
+            return None;
+        }
+        let mut addr: *mut libc::c_void = std::ptr::null_mut();
+        let mut size: libc::size_t = 0;
+        let rc = unsafe { libc::pthread_attr_getstack(&attr, &mut addr, &mut size) };
+        unsafe { libc::pthread_attr_destroy(&mut attr) };
+        (rc == 0).then_some(addr as usize)
+    }
+
+    /// Lowest address of the calling thread's stack.
+    #[cfg(target_os = "macos")]
+    unsafe fn stack_low() -> Option<usize> {
+        let me = unsafe { libc::pthread_self() };
+        let top = unsafe { libc::pthread_get_stackaddr_np(me) } as usize;
+        let size = unsafe { libc::pthread_get_stacksize_np(me) };
+        top.checked_sub(size)
+    }
+
+    #[cfg(target_os = "linux")]
+    unsafe fn fault_addr(info: *const libc::siginfo_t) -> usize {
+        unsafe { (*info).si_addr() as usize }
+    }
+
+    #[cfg(target_os = "macos")]
+    unsafe fn fault_addr(info: *const libc::siginfo_t) -> usize {
+        unsafe { (*info).si_addr as usize }
+    }
+
+    extern "C" fn on_fault(sig: libc::c_int, info: *mut libc::siginfo_t, _ctx: *mut libc::c_void) {
+        // Only async-signal-safe calls from here on: no allocation, no locks.
+        let addr = unsafe { fault_addr(info) };
+        let start = GUARD_START.load(Ordering::Relaxed);
+        let end = GUARD_END.load(Ordering::Relaxed);
+        if start <= addr && addr < end {
+            unsafe {
+                libc::write(2, MESSAGE.as_ptr().cast(), MESSAGE.len());
+                libc::_exit(101);
+            }
+        }
+        // Not an overflow: fall back to the default action and let the
+        // access fault again.
+        unsafe {
+            libc::signal(sig, libc::SIG_DFL);
+        }
+    }
+
+    pub(super) fn install() {
+        let Some(low) = (unsafe { stack_low() }) else {
+            return;
+        };
+        let page = unsafe { libc::sysconf(libc::_SC_PAGESIZE) }.max(4096) as usize;
+        GUARD_START.store(low.saturating_sub(GUARD_SPAN), Ordering::Relaxed);
+        GUARD_END.store(low + page, Ordering::Relaxed);
+
+        // The alternate stack lives for the rest of the process.
+        let alt = Box::leak(vec![0u8; ALT_STACK_SIZE].into_boxed_slice());
+        let ss = libc::stack_t {
+            ss_sp: alt.as_mut_ptr().cast(),
+            ss_flags: 0,
+            ss_size: ALT_STACK_SIZE,
+        };
+        unsafe {
+            if libc::sigaltstack(&ss, std::ptr::null_mut()) != 0 {
+                return;
+            }
+            let mut action: libc::sigaction = std::mem::zeroed();
+            action.sa_sigaction = on_fault as *const () as usize;
+            action.sa_flags = libc::SA_SIGINFO | libc::SA_ONSTACK;
+            libc::sigemptyset(&mut action.sa_mask);
+            libc::sigaction(libc::SIGSEGV, &action, std::ptr::null_mut());
+            libc::sigaction(libc::SIGBUS, &action, std::ptr::null_mut());
+        }
+    }
+}
+
+/// Install the main-thread stack overflow handler. The back end emits a call
+/// at the very start of the entry shim, on the main thread. A no-op on
+/// platforms without support (Windows already reports a stack overflow by
+/// its own exception code).
+#[no_mangle]
+pub extern "C" fn raven_stack_guard_install() {
+    #[cfg(any(target_os = "linux", target_os = "macos"))]
+    imp::install();
+}
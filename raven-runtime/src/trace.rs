@@ -0,0 +1,208 @@
+//! Call stacks for Raven panics.
+//!
+//! A panic reports the chain of Raven calls that led to it, as
+//! `call stack: main -> process -> validate`. Keeping a shadow stack of
+//! names would cost a pair of runtime calls on every Raven call, so the
+//! stack is instead recovered from the machine stack at panic time. The
+//! back end compiles every function with frame pointers, so each Raven
+//! frame starts with the caller's frame pointer and, one word above it,
+//! the return address into the caller. The entry shim registers a table of
+//! every Raven function's code range and display name with
+//! [`raven_trace_register`]; a panic emitted by the back end calls
+//! [`raven_panic_at`] with its own function's address and frame pointer,
+//! and the runtime walks the chain, naming each return address from the
+//! table.
+//!
+//! The walk stops at the first return address outside every registered
+//! function. That is the entry shim for the main thread and the scheduler
+//! for a goroutine, and also any runtime frame a Raven callback was called
+//! from (a comparator handed to a runtime sort), since a Rust frame is not
+//! guaranteed to keep a frame pointer. Panics raised by the runtime itself
+//! go through `raven_panic` and print no call stack.
+
+use std::sync::OnceLock;
+
+/// At most this many innermost entries are printed, after collapsing
+/// recursion; a deeper stack prints a leading `...` in place of the outer
+/// calls.
+const MAX_FRAMES: usize = 64;
+
+/// Frames walked before giving up on a chain, a guard against a corrupt
+/// stack that still looks like Raven frames.
+const MAX_WALK: usize = 1 << 20;
+
+/// One registered function: the `[start, end)` range of its machine code
+/// and the name a call stack shows for it.
+struct TracedFn {
+    start: usize,
+    end: usize,
+    name: &'static str,
+}
+
+/// Every Raven function, sorted by start address. Set once by the entry
+/// shim before any Raven code runs.
+static FUNCTIONS: OnceLock<Vec<TracedFn>> = OnceLock::new();
+
+/// Register the program's function table for panic call stacks.
+///
+/// `table` points to `count` entries of four pointer-sized words each:
+/// the function's start address, its code size in bytes, and the address
+/// and byte length of its UTF-8 display name. The back end emits one call
+/// from the entry shim. Only the first registration takes effect.
+///
+/// # Safety
+///
+/// `table` must point to `count * 4` readable words, and every name must
+/// be static UTF-8 data that lives for the rest of the program.
+#[no_mangle]
+pub extern "C" fn raven_trace_register(table: *const usize, count: usize) {
+    if table.is_null() {
+        return;
+    }
+    // SAFETY: the caller guarantees `count * 4` readable words.
+    let words = unsafe { std::slice::from_raw_parts(table, count * 4) };
+    let mut functions: Vec<TracedFn> = words
+        .chunks_exact(4)
+        .map(|entry| {
+            // SAFETY: the caller guarantees each name is static UTF-8.
+            let bytes = unsafe { std::slice::from_raw_parts(entry[2] as *const u8, entry[3]) };
+            TracedFn {
+                start: entry[0],
+                end: entry[0] + entry[1],
+                name: std::str::from_utf8(bytes).unwrap_or("<invalid utf-8>"),
+            }
+        })
+        .collect();
+    functions.sort_by_key(|f| f.start);
+    let _ = FUNCTIONS.set(functions);
+}
+
+/// The registered function whose code contains `addr`.
+fn lookup(addr: usize) -> Option<&'static TracedFn> {
+    let functions = FUNCTIONS.get()?;
+    let i = functions.partition_point(|f| f.start <= addr);
+    let f = functions.get(i.checked_sub(1)?)?;
+    (addr < f.end).then_some(f)
+}
+
+/// The call stack leading to a panic in the function at `func`, whose
+/// frame pointer is `fp`, outermost call first. A run of calls to the same
+/// function (recursion) collapses into one `name (xN)` entry. Empty when
+/// no table is registered or `func` is not a registered function.
+fn call_stack(func: usize, mut fp: usize) -> Vec<String> {
+    let Some(current) = lookup(func) else {
+        return Vec::new();
+    };
+    let mut names = vec![current.name];
+    let word = std::mem::size_of::<usize>();
+    while fp != 0 && fp.is_multiple_of(word) && names.len() < MAX_WALK {
+        // SAFETY: `fp` is the frame pointer of a registered Raven function,
+        // and every Raven function keeps a frame pointer, so the frame
+        // record holds the caller's frame pointer and the return address.
+        let (caller_fp, ret) = unsafe {
+            let record = fp as *const usize;
+            (*record, *record.add(1))
+        };
+        // A return address outside every Raven function ends the chain
+        // before its frame pointer (which may not be one) is followed.
+        let Some(caller) = lookup(ret) else {
+            break;
+        };
+        names.push(caller.name);
+        // The stack grows down, so a caller's frame is always above its
+        // callee's. Anything else is a corrupt chain.
+        if caller_fp <= fp {
+            break;
+        }
+        fp = caller_fp;
+    }
+    let mut out: Vec<String> = Vec::new();
+    let mut i = 0;
+    while i < names.len() && out.len() < MAX_FRAMES {
+        let run = names[i..].iter().take_while(|n| **n == names[i]).count();
+        if run == 1 {
+            out.push(names[i].to_string());
+        } else {
+            out.push(format!("{} (x{})", names[i], run));
+        }
+        i += run;
+    }
+    if i < names.len() {
+        out.push("...".to_string());
+    }
+    out.reverse();
+    out
+}
+
+/// Report a Raven panic raised in compiled code, with its call stack.
+///
+/// Behaves like `raven_panic`, then prints a `call stack:` line naming
+/// the Raven calls that were open. `func` is the address of the panicking
+/// function and `fp` its frame pointer.
+///
+/// # Safety
+///
+/// `msg_ptr` must point to `msg_len` initialized bytes of valid UTF-8.
+/// `fp` must be the frame pointer of the function at `func`, with every
+/// Raven frame above it keeping a frame pointer.
+#[no_mangle]
+pub extern "C" fn raven_panic_at(
+    msg_ptr: *const u8,
+    msg_len: usize,
+    func: *const u8,
+    fp: *const u8,
+) -> ! {
+    let names = call_stack(func as usize, fp as usize);
+    crate::panic_exit(msg_ptr, msg_len, &names)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A hand-built frame chain: three Raven frames, innermost first, then
+    /// a return address into unregistered code that ends the walk.
+    #[test]
+    fn walks_frame_records_and_names_each_caller() {
+        static NAMES: [&str; 3] = ["main", "process", "check"];
+        let table: Vec<usize> = (0..3)
+            .flat_map(|i| {
+                let name = NAMES[i];
+                [0x1000 * (i + 1), 0x100, name.as_ptr() as usize, name.len()]
+            })
+            .collect();
+        raven_trace_register(table.as_ptr(), 3);
+
+        // Frame records of [caller fp, return address], innermost at the
+        // lowest address. `check` was called from inside `process`, which
+        // was called from inside `main`, which was called from outside any
+        // Raven function.
+        let mut stack = [0usize; 6];
+        let base = stack.as_mut_ptr();
+        // SAFETY: every write is in bounds of `stack`, which outlives the walk.
+        let names = unsafe {
+            *base = base.add(2) as usize;
+            *base.add(1) = 0x2010;
+            *base.add(2) = base.add(4) as usize;
+            *base.add(3) = 0x1010;
+            *base.add(5) = 0x9000;
+            call_stack(0x3004, base as usize)
+        };
+        assert_eq!(names, ["main", "process", "check"]);
+        assert!(call_stack(0x9000, 0).is_empty());
+
+        // `process` calling itself twice collapses into one entry.
+        let mut stack = [0usize; 6];
+        let base = stack.as_mut_ptr();
+        // SAFETY: every write is in bounds of `stack`, which outlives the walk.
+        let names = unsafe {
+            *base = base.add(2) as usize;
+            *base.add(1) = 0x2010;
+            *base.add(2) = base.add(4) as usize;
+            *base.add(3) = 0x2020;
+            *base.add(5) = 0x1010;
+            call_stack(0x2004, base as usize)
+        };
+        assert_eq!(names, ["main", "process (x3)"]);
+    }
+}
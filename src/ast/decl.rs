@@ -63,6 +63,12 @@ pub struct Function {
     pub params: Vec<Param>,
     pub ret: Option<Type>,
     pub body: FunctionBody,
+    /// Lint names silenced by a preceding `@allow(...)` attribute
+    /// (`dead_code` or `infinite_loop`). Empty when no attribute is present,
+    /// and always empty for trait members. A local function the
+    /// parser hoists out of a body takes the list of the function it was
+    /// declared in.
+    pub allows: Vec<String>,
     pub span: Span,
 }
 
@@ -80,6 +80,9 @@ pub enum ExprKind {
     /// parser produces this and the resolver rejects it until tuples
     /// land; see `docs/v2/specs/parser.md`.
     Tuple(Vec<Expr>),
+    /// `()` the unit value, of type `Unit`. The value a bare `return` and a
+    /// function with no return type produce, written out.
+    Unit,
     /// `(expr)` parenthesized expression, retained so spans cover the
     /// parens for error reporting.
     Paren(Box<Expr>),
@@ -137,8 +140,13 @@ pub enum ExprKind {
     },
     /// `loop { ... }`.
     Loop(Block),
-    /// `while cond { ... }`.
-    While { cond: Box<Expr>, body: Block },
+    /// `while cond { ... } else { ... }`. The `else` block runs only when
+    /// the condition is false on first test, so the body never ran.
+    While {
+        cond: Box<Expr>,
+        body: Block,
+        else_block: Option<Block>,
+    },
     /// `for pat in iter { ... }`.
     For {
         pattern: Pattern,
@@ -177,8 +185,13 @@ pub enum UnaryOp {
     Neg,
     /// `!x` logical not.
     Not,
+    /// `~x` bitwise complement of an `Int`.
+    BitNot,
     /// `&x` reference. Semantics deferred to the type checker.
     Ref,
+    /// `typeof x`: the name of the operand's static type as a `String`,
+    /// rendered the same way as `type_name<T>()`.
+    TypeOf,
 }
 
 /// A macro invocation, kept as raw argument tokens for the formatter.
@@ -209,6 +222,10 @@ pub enum BinaryOp {
     Mod,
     Eq,
     Ne,
+    /// `==~`: `String` equality ignoring ASCII case.
+    EqIgnoreCase,
+    /// `!=~`: the negation of `==~`.
+    NeIgnoreCase,
     Lt,
     Le,
     Gt,
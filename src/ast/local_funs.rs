@@ -0,0 +1,647 @@
+//! Hoisting local functions out of a function body.
+//!
+//! A local `fun` is a closure bound where it is declared, so it sees the
+//! locals above it but nothing below it, itself included. Most helpers
+//! capture nothing, though, and for those the order is only in the way:
+//! two of them cannot call each other, and neither can call itself. The
+//! parser hoists such a function to a top-level item instead (named by
+//! [`local_fun_name`]) and points its block at the new name, so the whole
+//! block sees it, recursion included, and a loop body that declares one no
+//! longer builds a closure on every pass.
+//!
+//! A local function is hoisted when all of these hold:
+//!
+//! * It reads no name the enclosing function binds (a parameter, `let`,
+//!   pattern binding, or local function), except other functions hoisted
+//!   out of the same block. Any such name would be a capture.
+//! * It uses neither `self` nor `Self`, nor a type parameter of the
+//!   enclosing function.
+//! * Its name is bound nowhere else in the enclosing function, so no use of
+//!   the name can mean anything but this function.
+//! * It declares its return type, or has a block body that returns nothing:
+//!   a top-level function without one returns `Unit`, while a closure
+//!   infers it.
+//!
+//! The check is syntactic and errs toward keeping the closure: a global the
+//! body reads that shares a name with some local of the enclosing function
+//! also keeps it. Any other local function stays a closure, with its
+//! declaration-order scoping.
+
+use std::collections::{HashMap, HashSet};
+
+use super::local_types::Renamer;
+use super::{
+    local_type_name, Block, Decl, DeclKind, ElseBranch, Expr, ExprKind, Function, FunctionBody,
+    GenericParam, LambdaBody, LambdaParam, Param, Pattern, PatternKind, Stmt, StmtKind,
+    StrFragment, Type, TypeKind, TypePath,
+};
+
+/// The top-level name of local function `name` hoisted out of `func` at
+/// `line`. It follows [`local_type_name`], so
+/// [`source_type_name`](super::source_type_name) reads the declared name back.
+pub fn local_fun_name(name: &str, func: &str, line: u32) -> String {
+    local_type_name(name, func, line)
+}
+
+/// Hoist the local functions of function `func` that capture nothing (see
+/// the module docs) out of `body`, returning them as top-level items.
+pub fn hoist_local_funs(
+    func: &str,
+    params: &[Param],
+    generics: &[GenericParam],
+    body: &mut FunctionBody,
+) -> Vec<Decl> {
+    let mut scan = Scan::default();
+    scan.values.extend(params.iter().map(|p| p.name.clone()));
+    scan.binds.extend(params.iter().map(|p| p.name.clone()));
+    match body {
+        FunctionBody::Block(b) => scan.block(b),
+        FunctionBody::Expr(e) => scan.expr(e),
+        FunctionBody::None => {}
+    }
+    let mut bound = HashMap::new();
+    for name in scan.binds {
+        *bound.entry(name).or_insert(0usize) += 1;
+    }
+    let mut h = Hoister {
+        func,
+        bound,
+        generics: generics.iter().map(|g| g.name.clone()).collect(),
+        hoisted: Vec::new(),
+    };
+    match body {
+        FunctionBody::Block(b) => h.block(b),
+        FunctionBody::Expr(e) => h.expr(e),
+        FunctionBody::None => {}
+    }
+    h.hoisted
+}
+
+struct Hoister<'a> {
+    func: &'a str,
+    /// How many times the enclosing function binds each value name.
+    bound: HashMap<String, usize>,
+    /// The enclosing function's type parameters.
+    generics: HashSet<String>,
+    hoisted: Vec<Decl>,
+}
+
+impl Hoister<'_> {
+    /// Hoist what `b` declares, then visit what is left of it and the
+    /// bodies of what was hoisted, outermost first so an inner function
+    /// that calls an outer one sees it already hoisted.
+    fn block(&mut self, b: &mut Block) {
+        let mut hoisted = self.hoist(b);
+        for s in &mut b.stmts {
+            self.stmt(s);
+        }
+        if let Some(e) = &mut b.trailing {
+            self.expr(e);
+        }
+        for decl in &mut hoisted {
+            if let DeclKind::Function(f) = &mut decl.kind {
+                match &mut f.body {
+                    FunctionBody::Block(b) => self.block(b),
+                    FunctionBody::Expr(e) => self.expr(e),
+                    FunctionBody::None => {}
+                }
+            }
+        }
+        self.hoisted.append(&mut hoisted);
+    }
+
+    /// Move the local functions of `b` that can be hoisted out of it, and
+    /// point the rest of `b` and their own bodies at their new names.
+    fn hoist(&self, b: &mut Block) -> Vec<Decl> {
+        let mut candidates: HashMap<String, Outside> = HashMap::new();
+        for s in &b.stmts {
+            let StmtKind::Fun { name, func } = &s.kind else {
+                continue;
+            };
+            let ExprKind::Lambda {
+                params, ret, body, ..
+            } = &func.kind
+            else {
+                continue;
+            };
+            if self.bound.get(name) != Some(&1) || params.iter().any(|p| p.ty.is_none()) {
+                continue;
+            }
+            let outside = Outside::of(params, body);
+            let unit_body = matches!(body, LambdaBody::Block(b) if b.trailing.is_none());
+            if (ret.is_some() || (unit_body && !outside.returns_value))
+                && !outside.self_
+                && outside.types.is_disjoint(&self.generics)
+                && outside.names.is_disjoint(&self.generics)
+            {
+                candidates.insert(name.clone(), outside);
+            }
+        }
+        // Drop any candidate that reads a local other than a fellow
+        // candidate, until none does.
+        loop {
+            let keep: HashSet<String> = candidates
+                .iter()
+                .filter(|(_, o)| {
+                    o.names
+                        .iter()
+                        .all(|n| candidates.contains_key(n) || !self.bound.contains_key(n))
+                })
+                .map(|(n, _)| n.clone())
+                .collect();
+            if keep.len() == candidates.len() {
+                break;
+            }
+            candidates.retain(|n, _| keep.contains(n));
+        }
+        if candidates.is_empty() {
+            return Vec::new();
+        }
+
+        let mut rename = HashMap::new();
+        let mut hoisted = Vec::new();
+        let mut kept = Vec::new();
+        for s in std::mem::take(&mut b.stmts) {
+            match s.kind {
+                StmtKind::Fun { name, func } if candidates.contains_key(&name) => {
+                    let lifted = local_fun_name(&name, self.func, s.span.line);
+                    rename.insert(name, lifted.clone());
+                    hoisted.push(to_function(lifted, func, s.span));
+                }
+                kind => kept.push(Stmt { kind, ..s }),
+            }
+        }
+        b.stmts = kept;
+        Renamer::new(&rename, false).block(b);
+        for f in &mut hoisted {
+            let mut r = Renamer::new(&rename, false);
+            r.values.extend(f.params.iter().map(|p| p.name.clone()));
+            match &mut f.body {
+                FunctionBody::Block(b) => r.block(b),
+                FunctionBody::Expr(e) => r.expr(e),
+                FunctionBody::None => {}
+            }
+        }
+        hoisted
+            .into_iter()
+            .map(|f| Decl {
+                span: f.span.clone(),
+                kind: DeclKind::Function(f),
+            })
+            .collect()
+    }
+
+    fn stmt(&mut self, s: &mut Stmt) {
+        match &mut s.kind {
+            StmtKind::Let { init, .. } => {
+                if let Some(e) = init {
+                    self.expr(e);
+                }
+            }
+            StmtKind::Return(e) | StmtKind::Break(e) => {
+                if let Some(e) = e {
+                    self.expr(e);
+                }
+            }
+            StmtKind::Fun { func: e, .. }
+            | StmtKind::Defer(e)
+            | StmtKind::Spawn(e)
+            | StmtKind::Expr(e) => self.expr(e),
+            StmtKind::Assign { target, value, .. } => {
+                self.expr(target);
+                self.expr(value);
+            }
+            StmtKind::Continue | StmtKind::TypeDecl(_) => {}
+        }
+    }
+
+    fn expr(&mut self, e: &mut Expr) {
+        match &mut e.kind {
+            ExprKind::Block(b) | ExprKind::Loop(b) => self.block(b),
+            ExprKind::If {
+                cond,
+                then_branch,
+                else_branch,
+            } => {
+                self.expr(cond);
+                self.block(then_branch);
+                match else_branch.as_deref_mut() {
+                    Some(ElseBranch::If(e)) => self.expr(e),
+                    Some(ElseBranch::Block(b)) => self.block(b),
+                    None => {}
+                }
+            }
+            ExprKind::While {
+                cond,
+                body,
+                else_block,
+            } => {
+                self.expr(cond);
+                self.block(body);
+                if let Some(b) = else_block {
+                    self.block(b);
+                }
+            }
+            ExprKind::For { iter, body, .. } => {
+                self.expr(iter);
+                self.block(body);
+            }
+            ExprKind::Match { scrutinee, arms } => {
+                self.expr(scrutinee);
+                for arm in arms {
+                    if let Some(g) = &mut arm.guard {
+                        self.expr(g);
+                    }
+                    self.expr(&mut arm.body);
+                }
+            }
+            ExprKind::Lambda { body, .. } => match body {
+                LambdaBody::Block(b) => self.block(b),
+                LambdaBody::Expr(e) => self.expr(e),
+            },
+            ExprKind::StructLit { fields, .. } => {
+                fields.iter_mut().for_each(|f| self.expr(&mut f.value));
+            }
+            ExprKind::InterpolatedString(fragments) => {
+                for f in fragments {
+                    if let StrFragment::Expr(e) = f {
+                        self.expr(e);
+                    }
+                }
+            }
+            ExprKind::Array(items) | ExprKind::Tuple(items) | ExprKind::SetLit(items) => {
+                items.iter_mut().for_each(|e| self.expr(e));
+            }
+            ExprKind::MapLit(pairs) => {
+                for (k, v) in pairs {
+                    self.expr(k);
+                    self.expr(v);
+                }
+            }
+            ExprKind::Paren(inner)
+            | ExprKind::Try(inner)
+            | ExprKind::Unary { operand: inner, .. }
+            | ExprKind::Field {
+                receiver: inner, ..
+            } => self.expr(inner),
+            ExprKind::Binary { lhs, rhs, .. } => {
+                self.expr(lhs);
+                self.expr(rhs);
+            }
+            ExprKind::Range { start, end, .. } => {
+                self.expr(start);
+                self.expr(end);
+            }
+            ExprKind::Index { receiver, index } => {
+                self.expr(receiver);
+                self.expr(index);
+            }
+            ExprKind::Call { callee, args } => {
+                self.expr(callee);
+                args.iter_mut().for_each(|a| self.expr(a));
+            }
+            ExprKind::MethodCall { receiver, args, .. } => {
+                self.expr(receiver);
+                args.iter_mut().for_each(|a| self.expr(a));
+            }
+            ExprKind::Int(_)
+            | ExprKind::Float(_)
+            | ExprKind::Bool(_)
+            | ExprKind::Str(_)
+            | ExprKind::BlockStr(_)
+            | ExprKind::Char(_)
+            | ExprKind::CStr(_)
+            | ExprKind::SelfLower
+            | ExprKind::SelfUpper
+            | ExprKind::Ident { .. }
+            | ExprKind::MacroCall(_)
+            | ExprKind::Unit => {}
+        }
+    }
+}
+
+/// The top-level function a hoisted `fun name(params) -> ret body` becomes.
+fn to_function(name: String, func: Expr, span: crate::span::Span) -> Function {
+    let ExprKind::Lambda {
+        params, ret, body, ..
+    } = func.kind
+    else {
+        unreachable!("a local function is a lambda")
+    };
+    Function {
+        name,
+        generics: Vec::new(),
+        params: params
+            .into_iter()
+            .filter_map(|p| {
+                Some(Param {
+                    name: p.name,
+                    ty: p.ty?,
+                    span: p.span,
+                })
+            })
+            .collect(),
+        ret,
+        body: match body {
+            LambdaBody::Block(b) => FunctionBody::Block(b),
+            LambdaBody::Expr(e) => FunctionBody::Expr(*e),
+        },
+        allows: Vec::new(),
+        span,
+    }
+}
+
+/// What a local function's body reads from outside itself.
+#[derive(Default)]
+struct Outside {
+    /// Bare names read but not bound inside.
+    names: HashSet<String>,
+    /// Head names of the types mentioned.
+    types: HashSet<String>,
+    /// Whether `self` or `Self` appears.
+    self_: bool,
+    /// Whether the body itself (not a lambda inside it) leaves with a value:
+    /// `return v` or `?`.
+    returns_value: bool,
+}
+
+impl Outside {
+    fn of(params: &[LambdaParam], body: &LambdaBody) -> Self {
+        let mut scan = Scan::default();
+        for p in params {
+            if let Some(t) = &p.ty {
+                scan.ty(t);
+            }
+            scan.values.push(p.name.clone());
+        }
+        match body {
+            LambdaBody::Block(b) => scan.block(b),
+            LambdaBody::Expr(e) => scan.expr(e),
+        }
+        scan.out
+    }
+}
+
+/// A read-only walk recording what [`Outside`] holds, plus every value name
+/// the walked code binds.
+#[derive(Default)]
+struct Scan {
+    out: Outside,
+    /// Value names bound in the scopes being walked, innermost last.
+    values: Vec<String>,
+    /// Every value name bound anywhere in the walk, once per binding.
+    binds: Vec<String>,
+    /// How many lambdas deep the walk is.
+    lambdas: usize,
+}
+
+impl Scan {
+    fn bind(&mut self, name: &str) {
+        self.values.push(name.to_string());
+        self.binds.push(name.to_string());
+    }
+
+    fn ty(&mut self, ty: &Type) {
+        match &ty.kind {
+            TypeKind::Path(p) | TypeKind::Dyn(p) => self.path(p),
+            TypeKind::Optional(inner) => self.ty(inner),
+            TypeKind::Function { params, ret } => {
+                params.iter().for_each(|p| self.ty(p));
+                self.ty(ret);
+            }
+            TypeKind::Unit => {}
+        }
+    }
+
+    fn path(&mut self, p: &TypePath) {
+        if let Some(head) = p.segments.first() {
+            if head.name == "Self" {
+                self.out.self_ = true;
+            }
+            self.out.types.insert(head.name.clone());
+        }
+        for seg in &p.segments {
+            seg.generics.iter().for_each(|g| self.ty(g));
+        }
+    }
+
+    fn block(&mut self, b: &Block) {
+        let scope = self.values.len();
+        for s in &b.stmts {
+            self.stmt(s);
+        }
+        if let Some(e) = &b.trailing {
+            self.expr(e);
+        }
+        self.values.truncate(scope);
+    }
+
+    fn stmt(&mut self, s: &Stmt) {
+        match &s.kind {
+            StmtKind::Let { name, ty, init, .. } => {
+                if let Some(t) = ty {
+                    self.ty(t);
+                }
+                if let Some(e) = init {
+                    self.expr(e);
+                }
+                self.bind(name);
+            }
+            StmtKind::Fun { name, func } => {
+                self.expr(func);
+                self.bind(name);
+            }
+            StmtKind::Return(e) => {
+                if let Some(e) = e {
+                    self.out.returns_value |= self.lambdas == 0;
+                    self.expr(e);
+                }
+            }
+            StmtKind::Break(e) => {
+                if let Some(e) = e {
+                    self.expr(e);
+                }
+            }
+            StmtKind::Defer(e) | StmtKind::Spawn(e) | StmtKind::Expr(e) => self.expr(e),
+            StmtKind::Assign { target, value, .. } => {
+                self.expr(target);
+                self.expr(value);
+            }
+            StmtKind::Continue | StmtKind::TypeDecl(_) => {}
+        }
+    }
+
+    fn expr(&mut self, e: &Expr) {
+        match &e.kind {
+            ExprKind::Ident { name, generics } => {
+                if !self.values.contains(name) {
+                    self.out.names.insert(name.clone());
+                }
+                generics.iter().for_each(|g| self.ty(g));
+            }
+            ExprKind::SelfLower | ExprKind::SelfUpper => self.out.self_ = true,
+            ExprKind::StructLit {
+                name,
+                generics,
+                fields,
+            } => {
+                self.out.types.insert(name.clone());
+                generics.iter().for_each(|g| self.ty(g));
+                fields.iter().for_each(|f| self.expr(&f.value));
+            }
+            ExprKind::Try(inner) => {
+                self.out.returns_value |= self.lambdas == 0;
+                self.expr(inner);
+            }
+            ExprKind::Block(b) | ExprKind::Loop(b) => self.block(b),
+            ExprKind::If {
+                cond,
+                then_branch,
+                else_branch,
+            } => {
+                self.expr(cond);
+                self.block(then_branch);
+                match else_branch.as_deref() {
+                    Some(ElseBranch::If(e)) => self.expr(e),
+                    Some(ElseBranch::Block(b)) => self.block(b),
+                    None => {}
+                }
+            }
+            ExprKind::While {
+                cond,
+                body,
+                else_block,
+            } => {
+                self.expr(cond);
+                self.block(body);
+                if let Some(b) = else_block {
+                    self.block(b);
+                }
+            }
+            ExprKind::For {
+                pattern,
+                iter,
+                body,
+            } => {
+                self.expr(iter);
+                let scope = self.values.len();
+                self.pattern(pattern);
+                self.block(body);
+                self.values.truncate(scope);
+            }
+            ExprKind::Match { scrutinee, arms } => {
+                self.expr(scrutinee);
+                for arm in arms {
+                    let scope = self.values.len();
+                    self.pattern(&arm.pattern);
+                    if let Some(g) = &arm.guard {
+                        self.expr(g);
+                    }
+                    self.expr(&arm.body);
+                    self.values.truncate(scope);
+                }
+            }
+            ExprKind::Lambda {
+                params, ret, body, ..
+            } => {
+                for p in params {
+                    if let Some(t) = &p.ty {
+                        self.ty(t);
+                    }
+                }
+                if let Some(t) = ret {
+                    self.ty(t);
+                }
+                let scope = self.values.len();
+                for p in params {
+                    self.bind(&p.name);
+                }
+                self.lambdas += 1;
+                match body {
+                    LambdaBody::Block(b) => self.block(b),
+                    LambdaBody::Expr(e) => self.expr(e),
+                }
+                self.lambdas -= 1;
+                self.values.truncate(scope);
+            }
+            ExprKind::InterpolatedString(fragments) => {
+                for f in fragments {
+                    if let StrFragment::Expr(e) = f {
+                        self.expr(e);
+                    }
+                }
+            }
+            ExprKind::Array(items) | ExprKind::Tuple(items) | ExprKind::SetLit(items) => {
+                items.iter().for_each(|e| self.expr(e));
+            }
+            ExprKind::MapLit(pairs) => {
+                for (k, v) in pairs {
+                    self.expr(k);
+                    self.expr(v);
+                }
+            }
+            ExprKind::Paren(inner)
+            | ExprKind::Unary { operand: inner, .. }
+            | ExprKind::Field {
+                receiver: inner, ..
+            } => self.expr(inner),
+            ExprKind::Binary { lhs, rhs, .. } => {
+                self.expr(lhs);
+                self.expr(rhs);
+            }
+            ExprKind::Range { start, end, .. } => {
+                self.expr(start);
+                self.expr(end);
+            }
+            ExprKind::Index { receiver, index } => {
+                self.expr(receiver);
+                self.expr(index);
+            }
+            ExprKind::Call { callee, args } => {
+                self.expr(callee);
+                args.iter().for_each(|a| self.expr(a));
+            }
+            ExprKind::MethodCall {
+                receiver,
+                generics,
+                args,
+                ..
+            } => {
+                self.expr(receiver);
+                generics.iter().for_each(|g| self.ty(g));
+                args.iter().for_each(|a| self.expr(a));
+            }
+            ExprKind::Int(_)
+            | ExprKind::Float(_)
+            | ExprKind::Bool(_)
+            | ExprKind::Str(_)
+            | ExprKind::BlockStr(_)
+            | ExprKind::Char(_)
+            | ExprKind::CStr(_)
+            | ExprKind::MacroCall(_)
+            | ExprKind::Unit => {}
+        }
+    }
+
+    /// Bind the names `pat` binds: a bare lowercase name, or a struct field
+    /// written without a pattern.
+    fn pattern(&mut self, pat: &Pattern) {
+        match &pat.kind {
+            PatternKind::Ident(name) => {
+                if name.starts_with(|c: char| c.is_lowercase()) {
+                    self.bind(name);
+                }
+            }
+            PatternKind::Struct { fields, .. } => {
+                for f in fields {
+                    match &f.pattern {
+                        Some(p) => self.pattern(p),
+                        None => self.bind(&f.name),
+                    }
+                }
+            }
+            PatternKind::Tuple { elements, .. } => elements.iter().for_each(|p| self.pattern(p)),
+            PatternKind::Wildcard | PatternKind::Literal(_) | PatternKind::Range { .. } => {}
+        }
+    }
+}
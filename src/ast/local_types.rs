@@ -0,0 +1,355 @@
+//! Names for the `struct` and `enum` declarations inside a function body.
+//!
+//! The parser lifts a local type out of its function to a top-level item
+//! under a name scoped to that function ([`local_type_name`]), then points
+//! the function at the new name ([`rename_local_types`]). Only places that
+//! name a type change: annotations, struct literal and struct pattern
+//! names, and a bare name a value is reached through (`E.A`, `P.new()`).
+//! A parameter, `let`, local `fun`, or pattern binding that shares the
+//! type's name keeps its name, and so do the uses it shadows.
+
+use std::collections::HashMap;
+
+use super::{
+    Block, Decl, DeclKind, ElseBranch, Expr, ExprKind, FunctionBody, LambdaBody, Param, Pattern,
+    PatternKind, Stmt, StmtKind, StrFragment, Type, TypeKind, TypePath, VariantPayload,
+};
+
+/// Joins a local type's own name to the function that declares it, in the
+/// top-level name the parser gives the type when it lifts it out of the body.
+pub const LOCAL_TYPE_MARKER: &str = "__in_";
+
+/// The top-level name of a `struct` or `enum` called `name` declared inside
+/// function `func` at `line`. Each function gets its own, so two functions
+/// can declare the same local type name and no other function can reach it.
+pub fn local_type_name(name: &str, func: &str, line: u32) -> String {
+    format!("{name}{LOCAL_TYPE_MARKER}{func}_{line}")
+}
+
+/// The name a type was declared with: a lifted local type's own name, and
+/// any other type name unchanged. Diagnostics and derived `to_string` show
+/// this rather than the lifted name.
+pub fn source_type_name(name: &str) -> &str {
+    local_type_owner(name).map_or(name, |(n, _)| n)
+}
+
+/// The declared name of a lifted local type and the function declaring it,
+/// or `None` for any other type name.
+pub fn local_type_owner(name: &str) -> Option<(&str, &str)> {
+    let (base, rest) = name.split_once(LOCAL_TYPE_MARKER)?;
+    let (func, _line) = rest.rsplit_once('_')?;
+    Some((base, func))
+}
+
+/// Point the local types lifted out of one function, and that function's
+/// parameters and body, at the names in `rename` (declared name to lifted
+/// name).
+pub fn rename_local_types(
+    types: &mut [Decl],
+    params: &[Param],
+    body: &mut FunctionBody,
+    rename: &HashMap<String, String>,
+) {
+    let mut r = Renamer::new(rename, true);
+    for decl in types {
+        r.decl(decl);
+    }
+    r.values.extend(params.iter().map(|p| p.name.clone()));
+    match body {
+        FunctionBody::Block(b) => r.block(b),
+        FunctionBody::Expr(e) => r.expr(e),
+        FunctionBody::None => {}
+    }
+}
+
+/// Renames the bare names in `rename` that a walked tree reads, and with
+/// `types` set, the type names too. [`super::local_funs`] uses it without
+/// `types` to point a block at the functions hoisted out of it.
+pub(super) struct Renamer<'a> {
+    rename: &'a HashMap<String, String>,
+    types: bool,
+    /// Value names bound in the scopes being walked, innermost last. A bare
+    /// name bound here reads the value, so it is not renamed.
+    pub(super) values: Vec<String>,
+}
+
+impl<'a> Renamer<'a> {
+    pub(super) fn new(rename: &'a HashMap<String, String>, types: bool) -> Self {
+        Renamer {
+            rename,
+            types,
+            values: Vec::new(),
+        }
+    }
+
+    fn name(&self, name: &mut String) {
+        if let Some(lifted) = self.rename.get(name) {
+            *name = lifted.clone();
+        }
+    }
+
+    fn type_name(&self, name: &mut String) {
+        if self.types {
+            self.name(name);
+        }
+    }
+
+    fn decl(&self, decl: &mut Decl) {
+        match &mut decl.kind {
+            DeclKind::Struct(s) => {
+                self.name(&mut s.name);
+                for f in &mut s.fields {
+                    self.ty(&mut f.ty);
+                }
+            }
+            DeclKind::Enum(e) => {
+                self.name(&mut e.name);
+                for v in &mut e.variants {
+                    match &mut v.payload {
+                        VariantPayload::Tuple(tys) => tys.iter_mut().for_each(|t| self.ty(t)),
+                        VariantPayload::Struct(fields) => {
+                            fields.iter_mut().for_each(|f| self.ty(&mut f.ty))
+                        }
+                        VariantPayload::Unit => {}
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn ty(&self, ty: &mut Type) {
+        match &mut ty.kind {
+            TypeKind::Path(p) | TypeKind::Dyn(p) => self.path(p),
+            TypeKind::Optional(inner) => self.ty(inner),
+            TypeKind::Function { params, ret } => {
+                params.iter_mut().for_each(|p| self.ty(p));
+                self.ty(ret);
+            }
+            TypeKind::Unit => {}
+        }
+    }
+
+    fn path(&self, p: &mut TypePath) {
+        if let Some(head) = p.segments.first_mut() {
+            self.type_name(&mut head.name);
+        }
+        for seg in &mut p.segments {
+            seg.generics.iter_mut().for_each(|g| self.ty(g));
+        }
+    }
+
+    pub(super) fn block(&mut self, b: &mut Block) {
+        let scope = self.values.len();
+        for s in &mut b.stmts {
+            self.stmt(s);
+        }
+        if let Some(e) = &mut b.trailing {
+            self.expr(e);
+        }
+        self.values.truncate(scope);
+    }
+
+    fn stmt(&mut self, s: &mut Stmt) {
+        match &mut s.kind {
+            StmtKind::Let { name, ty, init, .. } => {
+                if let Some(t) = ty {
+                    self.ty(t);
+                }
+                if let Some(e) = init {
+                    self.expr(e);
+                }
+                self.values.push(name.clone());
+            }
+            // A local function is in scope in its own body, so it can recurse.
+            StmtKind::Fun { name, func } => {
+                self.values.push(name.clone());
+                self.expr(func);
+            }
+            StmtKind::Return(e) | StmtKind::Break(e) => {
+                if let Some(e) = e {
+                    self.expr(e);
+                }
+            }
+            StmtKind::Defer(e) | StmtKind::Spawn(e) | StmtKind::Expr(e) => self.expr(e),
+            StmtKind::Assign { target, value, .. } => {
+                self.expr(target);
+                self.expr(value);
+            }
+            StmtKind::Continue | StmtKind::TypeDecl(_) => {}
+        }
+    }
+
+    pub(super) fn expr(&mut self, e: &mut Expr) {
+        match &mut e.kind {
+            ExprKind::Ident { name, generics } => {
+                if !self.values.contains(name) {
+                    self.name(name);
+                }
+                generics.iter_mut().for_each(|g| self.ty(g));
+            }
+            ExprKind::StructLit {
+                name,
+                generics,
+                fields,
+            } => {
+                self.type_name(name);
+                generics.iter_mut().for_each(|g| self.ty(g));
+                for f in fields {
+                    self.expr(&mut f.value);
+                }
+            }
+            ExprKind::InterpolatedString(fragments) => {
+                for f in fragments {
+                    if let StrFragment::Expr(e) = f {
+                        self.expr(e);
+                    }
+                }
+            }
+            ExprKind::Array(items) | ExprKind::Tuple(items) | ExprKind::SetLit(items) => {
+                items.iter_mut().for_each(|e| self.expr(e));
+            }
+            ExprKind::MapLit(pairs) => {
+                for (k, v) in pairs {
+                    self.expr(k);
+                    self.expr(v);
+                }
+            }
+            ExprKind::Paren(inner)
+            | ExprKind::Try(inner)
+            | ExprKind::Unary { operand: inner, .. }
+            | ExprKind::Field {
+                receiver: inner, ..
+            } => self.expr(inner),
+            ExprKind::Binary { lhs, rhs, .. } => {
+                self.expr(lhs);
+                self.expr(rhs);
+            }
+            ExprKind::Range { start, end, .. } => {
+                self.expr(start);
+                self.expr(end);
+            }
+            ExprKind::Index { receiver, index } => {
+                self.expr(receiver);
+                self.expr(index);
+            }
+            ExprKind::Call { callee, args } => {
+                self.expr(callee);
+                args.iter_mut().for_each(|a| self.expr(a));
+            }
+            ExprKind::MethodCall {
+                receiver,
+                generics,
+                args,
+                ..
+            } => {
+                self.expr(receiver);
+                generics.iter_mut().for_each(|g| self.ty(g));
+                args.iter_mut().for_each(|a| self.expr(a));
+            }
+            ExprKind::Block(b) | ExprKind::Loop(b) => self.block(b),
+            ExprKind::If {
+                cond,
+                then_branch,
+                else_branch,
+            } => {
+                self.expr(cond);
+                self.block(then_branch);
+                match else_branch.as_deref_mut() {
+                    Some(ElseBranch::If(e)) => self.expr(e),
+                    Some(ElseBranch::Block(b)) => self.block(b),
+                    None => {}
+                }
+            }
+            ExprKind::While {
+                cond,
+                body,
+                else_block,
+            } => {
+                self.expr(cond);
+                self.block(body);
+                if let Some(b) = else_block {
+                    self.block(b);
+                }
+            }
+            ExprKind::For {
+                pattern,
+                iter,
+                body,
+            } => {
+                self.expr(iter);
+                let scope = self.values.len();
+                self.pattern(pattern);
+                self.block(body);
+                self.values.truncate(scope);
+            }
+            ExprKind::Match { scrutinee, arms } => {
+                self.expr(scrutinee);
+                for arm in arms {
+                    let scope = self.values.len();
+                    self.pattern(&mut arm.pattern);
+                    if let Some(g) = &mut arm.guard {
+                        self.expr(g);
+                    }
+                    self.expr(&mut arm.body);
+                    self.values.truncate(scope);
+                }
+            }
+            ExprKind::Lambda {
+                params, ret, body, ..
+            } => {
+                for p in params.iter_mut() {
+                    if let Some(t) = &mut p.ty {
+                        self.ty(t);
+                    }
+                }
+                if let Some(t) = ret {
+                    self.ty(t);
+                }
+                let scope = self.values.len();
+                self.values.extend(params.iter().map(|p| p.name.clone()));
+                match body {
+                    LambdaBody::Block(b) => self.block(b),
+                    LambdaBody::Expr(e) => self.expr(e),
+                }
+                self.values.truncate(scope);
+            }
+            ExprKind::Int(_)
+            | ExprKind::Float(_)
+            | ExprKind::Bool(_)
+            | ExprKind::Str(_)
+            | ExprKind::BlockStr(_)
+            | ExprKind::Char(_)
+            | ExprKind::CStr(_)
+            | ExprKind::SelfLower
+            | ExprKind::SelfUpper
+            | ExprKind::MacroCall(_)
+            | ExprKind::Unit => {}
+        }
+    }
+
+    /// Rename the struct names `pat` mentions and bind the names it binds.
+    /// A bare lowercase name is a binding; a PascalCase one is a variant.
+    fn pattern(&mut self, pat: &mut Pattern) {
+        match &mut pat.kind {
+            PatternKind::Ident(name) => {
+                if name.starts_with(|c: char| c.is_lowercase()) {
+                    self.values.push(name.clone());
+                }
+            }
+            PatternKind::Struct { name, fields } => {
+                self.type_name(name);
+                for f in fields {
+                    match &mut f.pattern {
+                        Some(p) => self.pattern(p),
+                        None => self.values.push(f.name.clone()),
+                    }
+                }
+            }
+            PatternKind::Tuple { elements, .. } => {
+                elements.iter_mut().for_each(|p| self.pattern(p));
+            }
+            PatternKind::Wildcard | PatternKind::Literal(_) | PatternKind::Range { .. } => {}
+        }
+    }
+}
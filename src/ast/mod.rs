@@ -7,6 +7,8 @@
 //! * `expr`: value expressions
 //! * `stmt`: statements (assignments, lets, control flow effects)
 //! * `decl`: top level items (functions, structs, traits, etc.)
+//! * `local_types`: the names of types declared inside a function body
+//! * `local_funs`: hoisting local functions that capture nothing
 //!
 //! Every node carries a `Span` so downstream passes can render errors
 //! anchored at the offending source range. The lexer's `Span` type is
@@ -17,6 +19,8 @@
 
 pub mod decl;
 pub mod expr;
+pub mod local_funs;
+pub mod local_types;
 pub mod pattern;
 pub mod pretty;
 pub mod stmt;
@@ -24,6 +28,8 @@ pub mod ty;
 
 pub use decl::*;
 pub use expr::*;
+pub use local_funs::*;
+pub use local_types::*;
 pub use pattern::*;
 pub use pretty::pretty_file;
 pub use stmt::*;
@@ -292,6 +292,12 @@ fn pretty_stmt(buf: &mut String, stmt: &Stmt, depth: usize) {
             indent(buf, depth);
             buf.push_str(")\n");
         }
+        StmtKind::Fun { name, func } => {
+            writeln!(buf, "(fun-stmt {}", quote(name)).unwrap();
+            pretty_expr(buf, func, depth + 1);
+            indent(buf, depth);
+            buf.push_str(")\n");
+        }
         StmtKind::Return(e) => {
             buf.push_str("(return\n");
             if let Some(e) = e {
@@ -311,6 +317,12 @@ fn pretty_stmt(buf: &mut String, stmt: &Stmt, depth: usize) {
         StmtKind::Continue => {
             buf.push_str("(continue)\n");
         }
+        StmtKind::TypeDecl(decl) => {
+            buf.push_str("(type-stmt\n");
+            pretty_decl(buf, decl, depth + 1);
+            indent(buf, depth);
+            buf.push_str(")\n");
+        }
         StmtKind::Defer(e) => {
             buf.push_str("(defer\n");
             pretty_expr(buf, e, depth + 1);
@@ -369,6 +381,7 @@ fn pretty_expr(buf: &mut String, expr: &Expr, depth: usize) {
         ExprKind::CStr(s) => writeln!(buf, "(cstr {})", quote(s)).unwrap(),
         ExprKind::SelfLower => writeln!(buf, "(self)").unwrap(),
         ExprKind::SelfUpper => writeln!(buf, "(Self)").unwrap(),
+        ExprKind::Unit => writeln!(buf, "(unit)").unwrap(),
         ExprKind::Ident { name, generics } => {
             write!(buf, "(ident {}", quote(name)).unwrap();
             if !generics.is_empty() {
@@ -588,10 +601,17 @@ fn pretty_expr(buf: &mut String, expr: &Expr, depth: usize) {
             indent(buf, depth);
             buf.push_str(")\n");
         }
-        ExprKind::While { cond, body } => {
+        ExprKind::While {
+            cond,
+            body,
+            else_block,
+        } => {
             buf.push_str("(while\n");
             pretty_expr(buf, cond, depth + 1);
             pretty_block(buf, body, depth + 1, "body");
+            if let Some(e) = else_block {
+                pretty_block(buf, e, depth + 1, "else");
+            }
             indent(buf, depth);
             buf.push_str(")\n");
         }
@@ -760,7 +780,9 @@ fn unary_op(op: UnaryOp) -> &'static str {
     match op {
         UnaryOp::Neg => "neg",
         UnaryOp::Not => "not",
+        UnaryOp::BitNot => "bitnot",
         UnaryOp::Ref => "ref",
+        UnaryOp::TypeOf => "typeof",
     }
 }
 
@@ -773,6 +795,8 @@ fn binary_op(op: BinaryOp) -> &'static str {
         BinaryOp::Mod => "%",
         BinaryOp::Eq => "==",
         BinaryOp::Ne => "!=",
+        BinaryOp::EqIgnoreCase => "==~",
+        BinaryOp::NeIgnoreCase => "!=~",
         BinaryOp::Lt => "<",
         BinaryOp::Le => "<=",
         BinaryOp::Gt => ">",
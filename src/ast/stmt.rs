@@ -7,6 +7,7 @@
 
 use crate::span::Span;
 
+use super::decl::Decl;
 use super::expr::Expr;
 use super::ty::Type;
 
@@ -32,6 +33,12 @@ pub enum StmtKind {
         init: Option<Expr>,
         mutable: bool,
     },
+    /// `fun name(params) -> T { body }` inside a function body. A local
+    /// function: `func` is always an `ExprKind::Lambda`, and the statement
+    /// binds `name` to it for the rest of the block exactly as an immutable
+    /// `const name = fun(params) -> T { body }` would. Kept as its own node so
+    /// the formatter reprints the declaration form.
+    Fun { name: String, func: Expr },
     /// `return expr?`.
     Return(Option<Expr>),
     /// `break expr?` (carries a value when inside a `loop`).
@@ -53,6 +60,11 @@ pub enum StmtKind {
     /// A bare expression evaluated for its side effects (or returned as
     /// the trailing value of a block).
     Expr(Expr),
+    /// A `struct` or `enum` declared inside a function body. Only
+    /// [`crate::parser::parse_keeping_local_types`] (the formatter) produces
+    /// this; [`crate::parser::parse`] lifts the declaration to a top-level
+    /// item, so the compile pipeline never sees one.
+    TypeDecl(Box<Decl>),
 }
 
 /// Compound assignment operators. The plain `=` form is also represented
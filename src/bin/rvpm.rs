@@ -899,14 +899,20 @@ fn cmd_workspace(args: &[String]) -> Result<Vec<String>, String> {
     Ok(lines)
 }
 
-/// Generate Markdown API docs from the package sources into `target/doc`.
+/// Generate API docs from the package sources into `target/doc`: one
+/// Markdown file, or an HTML site with `--html`.
 fn cmd_doc(args: &[String]) -> Result<Vec<String>, String> {
     if args.iter().any(|a| a == "--help" || a == "-h") {
         return Ok(vec![doc_usage()]);
     }
-    reject_extra_args(args, "doc", &["--help", "-h"], 0)?;
+    reject_extra_args(args, "doc", &["--help", "-h", "--html"], 0)?;
+    let format = if args.iter().any(|a| a == "--html") {
+        raven::doc::DocFormat::Html
+    } else {
+        raven::doc::DocFormat::Markdown
+    };
     let cwd = std::env::current_dir().map_err(|e| e.to_string())?;
-    let report = raven::doc::generate(&cwd).map_err(|e| e.to_string())?;
+    let report = raven::doc::generate_with(&cwd, format).map_err(|e| e.to_string())?;
     Ok(report.outcome_lines)
 }
 
@@ -1142,7 +1148,7 @@ fn workspace_usage() -> String {
 }
 
 fn doc_usage() -> String {
-    "Usage: rvpm doc".to_string()
+    "Usage: rvpm doc [--html]".to_string()
 }
 
 fn add_usage() -> String {
@@ -1173,7 +1179,7 @@ fn print_usage() {
     println!("  dist           Package the built application (tar, zip, deb, rpm, msi, inno)");
     println!("  run [args]     Build the application then run it, forwarding args");
     println!("  test           Run fun test_*() tests in *_test.rv files");
-    println!("  doc            Generate Markdown API docs into target/doc");
+    println!("  doc            Generate Markdown API docs into target/doc (--html for HTML)");
     println!("  fmt [paths]    Format .rv files in place (--check to verify only)");
     println!("  fetch <pkg>    Fetch 'github.com/<user>/<repo>@<version>' into the shared cache");
     println!("  lock           Generate or validate rv.lock for the current package");
@@ -128,6 +128,19 @@ pub struct ModuleCx {
     /// `main` shim pushes each as a permanent GC root before running the
     /// global initializers, so a heap value stored into one stays reachable.
     global_roots: Vec<DataId>,
+    /// Every Raven function with its code size in bytes (zero until it is
+    /// defined) and its call-stack display name, in declaration order. The
+    /// `main` shim registers them as the table a panic's call stack is read
+    /// from.
+    traced: Vec<(FuncId, u32, String)>,
+    /// Each function's index in `traced`, fixed when it is declared. The
+    /// `--profile` hooks pass it as the function's id.
+    trace_index: HashMap<FuncId, u32>,
+    /// The function whose body is being lowered, so a panic site can pass
+    /// its own address to the runtime.
+    current: Option<FuncId>,
+    /// Whether to emit the `--profile` call-count and timing hooks.
+    profile: bool,
 }
 
 /// The exported `main` shim plus the Raven `main` it dispatches to.
@@ -168,6 +181,10 @@ impl ModuleCx {
             repr_c_structs: HashMap::new(),
             globals: HashMap::new(),
             global_roots: Vec::new(),
+            traced: Vec::new(),
+            trace_index: HashMap::new(),
+            current: None,
+            profile: false,
         }
     }
 
@@ -205,6 +222,22 @@ impl ModuleCx {
         self.repr_c_structs = layouts;
     }
 
+    /// Emit the `--profile` hooks: a call count and timing per function and
+    /// a report when `main` returns.
+    pub fn set_profile(&mut self, profile: bool) {
+        self.profile = profile;
+    }
+
+    /// The id the profile hooks pass for the function being lowered, its
+    /// index in the function table. `None` when the build is not profiled.
+    pub fn profile_id(&self) -> Option<u32> {
+        if !self.profile {
+            return None;
+        }
+        self.current
+            .and_then(|id| self.trace_index.get(&id).copied())
+    }
+
     /// The C layout of the `@repr(C)` struct named by `mangle`, if any.
     pub fn repr_c_layout(&self, mangle: &str) -> Option<&ReprCLayout> {
         self.repr_c_structs.get(mangle)
@@ -408,6 +441,12 @@ impl ModuleCx {
         self.runtime.get(symbol).copied()
     }
 
+    /// The function whose body is being lowered. Set for the duration of
+    /// each MIR function's lowering.
+    pub fn current_function(&self) -> Option<FuncId> {
+        self.current
+    }
+
     /// Width of an integer wide enough to hold a pointer on the host
     /// target.
     pub fn pointer_type(&self) -> cranelift_codegen::ir::Type {
@@ -515,6 +554,29 @@ impl ModuleCx {
         sig = self.make_sig(&[], &[]);
         self.declare_runtime(intrinsics::RUNTIME_GC_LEAVE_FRAME, &sig)?;
 
+        // raven_panic_at(msg ptr, len: usize, func: ptr, fp: ptr) -> !
+        sig = self.make_sig(&[ptr, ptr, ptr, ptr], &[]);
+        self.declare_runtime(intrinsics::RUNTIME_PANIC_AT, &sig)?;
+
+        // raven_trace_register(table: ptr, count: usize)
+        sig = self.make_sig(&[ptr, ptr], &[]);
+        self.declare_runtime(intrinsics::RUNTIME_TRACE_REGISTER, &sig)?;
+
+        // The `--profile` hooks, declared only for a profiled build so an
+        // ordinary object references none of them.
+        if self.profile {
+            // raven_profile_register(table: ptr, count: usize)
+            sig = self.make_sig(&[ptr, ptr], &[]);
+            self.declare_runtime(intrinsics::RUNTIME_PROFILE_REGISTER, &sig)?;
+            // raven_profile_enter(id: u32) / raven_profile_exit(id: u32)
+            sig = self.make_sig(&[i32t], &[]);
+            self.declare_runtime(intrinsics::RUNTIME_PROFILE_ENTER, &sig)?;
+            self.declare_runtime(intrinsics::RUNTIME_PROFILE_EXIT, &sig)?;
+            // raven_profile_report()
+            sig = self.make_sig(&[], &[]);
+            self.declare_runtime(intrinsics::RUNTIME_PROFILE_REPORT, &sig)?;
+        }
+
         // raven_gc_push_root(slot: ptr)
         sig = self.make_sig(&[ptr], &[]);
         self.declare_runtime(intrinsics::RUNTIME_GC_PUSH_ROOT, &sig)?;
@@ -523,6 +585,10 @@ impl ModuleCx {
         sig = self.make_sig(&[ptr], &[]);
         self.declare_runtime(intrinsics::RUNTIME_GC_POP_ROOTS, &sig)?;
 
+        // raven_stack_guard_install()
+        sig = self.make_sig(&[], &[]);
+        self.declare_runtime(intrinsics::RUNTIME_STACK_GUARD_INSTALL, &sig)?;
+
         // raven_gc_enter_running() / raven_gc_exit_running() / raven_gc_safepoint()
         sig = self.make_sig(&[], &[]);
         self.declare_runtime(intrinsics::RUNTIME_GC_ENTER_RUNNING, &sig)?;
@@ -570,6 +636,10 @@ impl ModuleCx {
         sig = self.make_sig(&[ptr, i32t, ptr], &[i32t]);
         self.declare_runtime(intrinsics::RUNTIME_LIST_GET, &sig)?;
 
+        // raven_list_copy(List ptr) -> List ptr
+        sig = self.make_sig(&[ptr], &[ptr]);
+        self.declare_runtime(intrinsics::RUNTIME_LIST_COPY, &sig)?;
+
         // raven_panic(msg ptr, len: usize) -> ! (no Cranelift return)
         sig = self.make_sig(&[ptr, ptr], &[]);
         self.declare_runtime(intrinsics::RUNTIME_PANIC, &sig)?;
@@ -628,6 +698,18 @@ impl ModuleCx {
         sig = self.make_sig(&[ptr, ptr], &[i64t]);
         self.declare_runtime(intrinsics::RUNTIME_STRING_CMP, &sig)?;
 
+        // raven_string_eq_ignore_case(String ptr, String ptr) -> i8 (Bool)
+        sig = self.make_sig(&[ptr, ptr], &[types::I8]);
+        self.declare_runtime(intrinsics::RUNTIME_STRING_EQ_IGNORE_CASE, &sig)?;
+
+        // raven_string_repeat(String ptr, count: i64) -> String ptr
+        sig = self.make_sig(&[ptr, i64t], &[ptr]);
+        self.declare_runtime(intrinsics::RUNTIME_STRING_REPEAT, &sig)?;
+
+        // raven_string_char_at(String ptr, index: i64) -> u32
+        sig = self.make_sig(&[ptr, i64t], &[i32t]);
+        self.declare_runtime(intrinsics::RUNTIME_STRING_CHAR_AT, &sig)?;
+
         // raven_int_to_string(i64) -> String ptr
         sig = self.make_sig(&[i64t], &[ptr]);
         self.declare_runtime(intrinsics::RUNTIME_INT_TO_STRING, &sig)?;
@@ -895,6 +977,8 @@ impl ModuleCx {
             };
             let id = self.module.declare_function(&name, linkage, &sig)?;
             self.functions.insert(func.name.clone(), id);
+            self.trace_index.insert(id, self.traced.len() as u32);
+            self.traced.push((id, 0, super::function::trace_name(func)));
             let param_tys: Vec<MirType> = func
                 .params
                 .iter()
@@ -933,9 +1017,9 @@ impl ModuleCx {
         Ok(())
     }
 
-    /// Emit the body of the `int main(void)` shim: register every type's GC
-    /// descriptor and reflection metadata, then call the Raven `main`,
-    /// discard its result, and return `0`.
+    /// Emit the body of the `int main(void)` shim: install the stack overflow
+    /// handler, register every type's GC descriptor and reflection metadata,
+    /// then call the Raven `main`, discard its result, and return `0`.
     fn define_main_shim(&mut self, program: &MirProgram) -> Result<(), CodegenError> {
         let MainEntry { shim, raven_main } = self
             .main_entry
@@ -956,6 +1040,8 @@ impl ModuleCx {
             registrations.push(self.intern_reflect_metadata(key, info)?);
         }
 
+        let trace_table = self.define_trace_table()?;
+
         let mut ctx = Context::new();
         ctx.func.signature = {
             let mut sig = Signature::new(self.module.target_config().default_call_conv);
@@ -1011,6 +1097,20 @@ impl ModuleCx {
         let push_root_ref = self
             .runtime_id(intrinsics::RUNTIME_GC_PUSH_ROOT)
             .map(|id| self.module.declare_func_in_func(id, &mut ctx.func));
+        let stack_guard_ref = self
+            .runtime_id(intrinsics::RUNTIME_STACK_GUARD_INSTALL)
+            .map(|id| self.module.declare_func_in_func(id, &mut ctx.func));
+        let trace_register_ref = self
+            .runtime_id(intrinsics::RUNTIME_TRACE_REGISTER)
+            .map(|id| self.module.declare_func_in_func(id, &mut ctx.func));
+        let trace_table_gv = self.module.declare_data_in_func(trace_table, &mut ctx.func);
+        let trace_count = self.traced.len();
+        let profile_register_ref = self
+            .runtime_id(intrinsics::RUNTIME_PROFILE_REGISTER)
+            .map(|id| self.module.declare_func_in_func(id, &mut ctx.func));
+        let profile_report_ref = self
+            .runtime_id(intrinsics::RUNTIME_PROFILE_REPORT)
+            .map(|id| self.module.declare_func_in_func(id, &mut ctx.func));
         // The main thread runs compiled Raven for the whole program, so it is
         // in the collector's "running" set from entry to exit.
         let enter_running_ref = self
@@ -1035,7 +1135,25 @@ impl ModuleCx {
             let block = builder.create_block();
             builder.switch_to_block(block);
             builder.seal_block(block);
-            // Enter the running set first: the main thread runs compiled Raven,
+            // Watch the main stack before any Raven code runs, so an overflow
+            // anywhere in the program reports as a panic.
+            if let Some(guard) = stack_guard_ref {
+                builder.ins().call(guard, &[]);
+            }
+            // Register the function table next, so a panic anywhere in the
+            // program, global initializers included, prints its call stack.
+            if let Some(reg) = trace_register_ref {
+                let table = builder.ins().symbol_value(ptr, trace_table_gv);
+                let count = builder.ins().iconst(ptr, trace_count as i64);
+                builder.ins().call(reg, &[table, count]);
+            }
+            // A profiled build reads the same table for its report names.
+            if let Some(reg) = profile_register_ref {
+                let table = builder.ins().symbol_value(ptr, trace_table_gv);
+                let count = builder.ins().iconst(ptr, trace_count as i64);
+                builder.ins().call(reg, &[table, count]);
+            }
+            // Enter the running set next: the main thread runs compiled Raven,
             // so a parallel collection must wait for it to reach a safepoint.
             if let Some(enter) = enter_running_ref {
                 builder.ins().call(enter, &[]);
@@ -1104,6 +1222,9 @@ impl ModuleCx {
             // The Raven `main` returns unit, so there is no result value
             // to forward; the call is emitted purely for its effects.
             builder.ins().call(callee, &[]);
+            if let Some(report) = profile_report_ref {
+                builder.ins().call(report, &[]);
+            }
             // Leave the running set before exiting.
             if let Some(exit) = exit_running_ref {
                 builder.ins().call(exit, &[]);
@@ -1119,6 +1240,49 @@ impl ModuleCx {
         Ok(())
     }
 
+    /// Emit the read-only table of every defined Raven function that panic
+    /// call stacks are read from: per function, four pointer-sized words
+    /// holding its address, its code size, and the address and length of
+    /// its display name. The layout matches `raven_trace_register`.
+    fn define_trace_table(&mut self) -> Result<DataId, CodegenError> {
+        let ptr_bytes = self.pointer_type().bytes() as usize;
+        let little = self.module.isa().endianness() == cranelift_codegen::ir::Endianness::Little;
+        let word = |v: u64| -> Vec<u8> {
+            match (ptr_bytes, little) {
+                (8, true) => v.to_le_bytes().to_vec(),
+                (8, false) => v.to_be_bytes().to_vec(),
+                (_, true) => (v as u32).to_le_bytes().to_vec(),
+                (_, false) => (v as u32).to_be_bytes().to_vec(),
+            }
+        };
+        let traced = std::mem::take(&mut self.traced);
+        let mut contents = Vec::with_capacity(traced.len() * 4 * ptr_bytes);
+        let mut names = Vec::with_capacity(traced.len());
+        for (_, size, name) in &traced {
+            // The address words are relocations filled in below.
+            contents.extend(word(0));
+            contents.extend(word(*size as u64));
+            contents.extend(word(0));
+            contents.extend(word(name.len() as u64));
+            names.push(self.intern_string(name.as_bytes())?);
+        }
+        let id = self
+            .module
+            .declare_data("__raven_trace_table", Linkage::Local, false, false)?;
+        let mut desc = DataDescription::new();
+        desc.define(contents.into_boxed_slice());
+        for (i, ((func_id, _, _), name_id)) in traced.iter().zip(&names).enumerate() {
+            let entry = (i * 4 * ptr_bytes) as u32;
+            let func_ref = self.module.declare_func_in_data(*func_id, &mut desc);
+            desc.write_function_addr(entry, func_ref);
+            let name_gv = self.module.declare_data_in_data(*name_id, &mut desc);
+            desc.write_data_addr(entry + 2 * ptr_bytes as u32, name_gv, 0);
+        }
+        self.module.define_data(id, &desc)?;
+        self.traced = traced;
+        Ok(id)
+    }
+
     fn define_one(&mut self, func: &MirFunction) -> Result<(), CodegenError> {
         let func_id = self
             .functions
@@ -1129,14 +1293,22 @@ impl ModuleCx {
         let mut ctx = Context::new();
         ctx.func.signature = sig;
 
+        self.current = Some(func_id);
         {
             let mut lowering = FunctionLowering::new(self, &mut ctx.func, func);
             lowering.lower()?;
         }
+        self.current = None;
 
         self.module
             .define_function(func_id, &mut ctx)
             .map_err(|e| CodegenError::Codegen(format!("define {}: {}", func.name, e)))?;
+        let size = ctx
+            .compiled_code()
+            .map(|code| code.code_info().total_size)
+            .unwrap_or(0);
+        let index = self.trace_index[&func_id] as usize;
+        self.traced[index].1 = size;
         Ok(())
     }
 
@@ -184,6 +184,7 @@ impl<'cx, 'func> FunctionLowering<'cx, 'func> {
                 if self.func.has_defer {
                     enter_defer_frame(self.cx, &mut builder);
                 }
+                profile_hook(self.cx, &mut builder, intrinsics::RUNTIME_PROFILE_ENTER);
             }
             // A loop header polls a GC safepoint at its top, so a thread in a
             // long non-allocating loop still reaches a safepoint and can be
@@ -1090,6 +1091,7 @@ fn emit_unop(builder: &mut FunctionBuilder<'_>, op: MirUnOp, v: Value) -> Value
             let one = builder.ins().iconst(types::I8, 1);
             builder.ins().bxor(v, one)
         }
+        MirUnOp::BitNot => builder.ins().bnot(v),
         MirUnOp::Ref => {
             // The address operator is not lowerable in the MVP.
             // Return the value unchanged so the function still compiles
@@ -1502,7 +1504,8 @@ fn lower_index_access(
 /// heap object (mutating it in place, so every alias observes the new
 /// element); `pop`/`get` copy an element into a scratch slot and panic
 /// when the list is empty or the index is out of range, matching the
-/// element-returning method signatures the type checker assigns.
+/// element-returning method signatures the type checker assigns. The
+/// `for` loop snapshot's copy is one `raven_list_copy` call.
 fn lower_list_method(
     cx: &mut ModuleCx,
     builder: &mut FunctionBuilder<'_>,
@@ -1592,6 +1595,14 @@ fn lower_list_method(
             let raw = builder.ins().stack_load(ptr, scratch, 0);
             Ok(Some(raw))
         }
+        ListMethodOp::Copy => {
+            let copy_id = cx
+                .runtime_id(intrinsics::RUNTIME_LIST_COPY)
+                .expect("list copy declared at module init");
+            let copy_ref = cx.module().declare_func_in_func(copy_id, builder.func);
+            let inst = builder.ins().call(copy_ref, &[list]);
+            Ok(Some(builder.inst_results(inst)[0]))
+        }
     }
 }
 
@@ -1735,6 +1746,30 @@ fn emit_panic(cx: &mut ModuleCx, builder: &mut FunctionBuilder<'_>, message: &st
     let local_id = cx.module().declare_data_in_func(id, builder.func);
     let msg_ptr = builder.ins().symbol_value(ptr, local_id);
     let msg_len = builder.ins().iconst(ptr, bytes.len() as i64);
+    call_panic(cx, builder, msg_ptr, msg_len);
+}
+
+/// Call the runtime panic with a message already in registers. Inside a
+/// Raven function this is `raven_panic_at`, passing the function's own
+/// address and frame pointer so the runtime can print the call stack.
+fn call_panic(
+    cx: &mut ModuleCx,
+    builder: &mut FunctionBuilder<'_>,
+    msg_ptr: Value,
+    msg_len: Value,
+) {
+    let ptr = cx.pointer_type();
+    if let Some(current) = cx.current_function() {
+        let panic_id = cx
+            .runtime_id(intrinsics::RUNTIME_PANIC_AT)
+            .expect("panic_at declared at module init");
+        let panic_ref = cx.module().declare_func_in_func(panic_id, builder.func);
+        let self_ref = cx.module().declare_func_in_func(current, builder.func);
+        let func = builder.ins().func_addr(ptr, self_ref);
+        let fp = builder.ins().get_frame_pointer(ptr);
+        builder.ins().call(panic_ref, &[msg_ptr, msg_len, func, fp]);
+        return;
+    }
     let panic_id = cx
         .runtime_id(intrinsics::RUNTIME_PANIC)
         .expect("panic declared at module init");
@@ -2738,14 +2773,11 @@ fn lower_intrinsic(
                 )));
             }
             let (ptr_val, len_val) = lower_string_arg(cx, builder, &args[0], slots)?;
-            let func_id = cx
-                .runtime_id(intrinsics::RUNTIME_PANIC)
-                .expect("panic declared at module init");
-            let local_ref = cx.module().declare_func_in_func(func_id, builder.func);
-            // raven_panic terminates the process; the call is treated as a
-            // normal returning void call so lowering of any trailing code in
-            // the block stays well formed (that code is dead at runtime).
-            builder.ins().call(local_ref, &[ptr_val, len_val]);
+            // The runtime panic terminates the process; the call is treated
+            // as a normal returning void call so lowering of any trailing
+            // code in the block stays well formed (that code is dead at
+            // runtime).
+            call_panic(cx, builder, ptr_val, len_val);
             Ok(None)
         }
         intrinsics::IO_READ_LINE => {
@@ -2881,6 +2913,21 @@ fn lower_intrinsic(
             let inst = builder.ins().call(local_ref, &[b_i32]);
             Ok(builder.inst_results(inst).first().copied())
         }
+        intrinsics::CHAR_FROM_CODE => {
+            // A `Char` is its 32-bit scalar value, so the native `Int` only
+            // narrows to i32; no runtime call is needed.
+            if args.len() != 1 {
+                return Err(CodegenError::Unsupported(format!(
+                    "__char_from_code intrinsic expects 1 arg, got {}",
+                    args.len()
+                )));
+            }
+            let c = require_value(
+                lower_operand(cx, builder, &args[0], slots)?,
+                "__char_from_code argument",
+            )?;
+            Ok(Some(builder.ins().ireduce(types::I32, c)))
+        }
         intrinsics::STR_CONCAT_FN => {
             // `raven_string_concat(String ptr, String ptr) -> String`.
             if args.len() != 2 {
@@ -3096,6 +3143,7 @@ fn lower_terminator(
                 }
             }
             leave_root_frame(cx, builder, root_frame);
+            profile_hook(cx, builder, intrinsics::RUNTIME_PROFILE_EXIT);
             match v {
                 Some(value) => {
                     builder.ins().return_(&[value]);
@@ -3115,6 +3163,20 @@ fn lower_terminator(
     }
 }
 
+/// Emit a `--profile` enter or exit hook for the function being lowered. A
+/// no-op in an ordinary build.
+fn profile_hook(cx: &mut ModuleCx, builder: &mut FunctionBuilder<'_>, symbol: &str) {
+    let Some(id) = cx.profile_id() else {
+        return;
+    };
+    let hook = cx
+        .runtime_id(symbol)
+        .expect("profile hooks declared at module init");
+    let hook_ref = cx.module().declare_func_in_func(hook, builder.func);
+    let id = builder.ins().iconst(types::I32, id as i64);
+    builder.ins().call(hook_ref, &[id]);
+}
+
 /// Emit the matching `raven_gc_leave_frame` for a function that entered
 /// a root frame. A no-op when the function has no GC pointer locals.
 fn leave_root_frame(
@@ -3132,6 +3194,43 @@ fn leave_root_frame(
     builder.ins().call(leave_ref, &[]);
 }
 
+/// The name a function goes by in a panic's call stack: its source name,
+/// prefixed with the implementing type for a method (`Counter.bump`). A
+/// hoisted local function shows as declared, and a lifted lambda body has
+/// no source name and shows as `<closure>`.
+pub(crate) fn trace_name(func: &MirFunction) -> String {
+    if func.origin.contains("$closure$") {
+        return "<closure>".to_string();
+    }
+    if crate::ast::local_type_owner(&func.origin).is_some() {
+        return crate::ast::source_type_name(&func.origin).to_string();
+    }
+    // A method symbol is `<type mangle>$<method>`, optionally followed by
+    // `$<type>` suffixes for method-level generics. The type mangle doubles
+    // any `_` in the type's own name and joins type arguments with a single
+    // `_`, so the source type name ends at the first lone `_`.
+    let method = format!("${}", func.origin);
+    let Some(at) = func.name.find(&method) else {
+        return func.origin.clone();
+    };
+    let rest = &func.name[at + method.len()..];
+    if at == 0 || !(rest.is_empty() || rest.starts_with('$')) {
+        return func.origin.clone();
+    }
+    let mut ty = String::new();
+    let mut chars = func.name[..at].chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '_' {
+            if chars.peek() != Some(&'_') {
+                break;
+            }
+            chars.next();
+        }
+        ty.push(c);
+    }
+    format!("{}.{}", ty, func.origin)
+}
+
 /// Open the per-call defer frame at function entry.
 fn enter_defer_frame(cx: &mut ModuleCx, builder: &mut FunctionBuilder<'_>) {
     let enter = cx
@@ -46,6 +46,10 @@ pub const STR_FROM_BYTE: &str = "__str_from_byte";
 /// `__str_concat(a: String, b: String) -> String` concatenates two
 /// strings into a fresh `String`.
 pub const STR_CONCAT_FN: &str = "__str_concat";
+/// `__char_from_code(c: Int) -> Char` is the `Char` whose scalar value is
+/// `c`. The caller (`String.chars()` in `std/string`) passes only valid
+/// Unicode scalar values; no check is made.
+pub const CHAR_FROM_CODE: &str = "__char_from_code";
 
 /// Internal defer intrinsic. MIR lowering of a `defer expr` builds a
 /// thunk closure capturing what `expr` needs, then emits
@@ -105,6 +109,18 @@ pub const RUNTIME_STRING_EQ: &str = "raven_string_eq";
 /// returning a negative/zero/positive `i64`. Backs `< <= > >=` on `String`.
 pub const RUNTIME_STRING_CMP: &str = "raven_string_cmp";
 
+/// Runtime C symbol comparing two `String` values ignoring ASCII case.
+/// Backs the `==~`/`!=~` operators.
+pub const RUNTIME_STRING_EQ_IGNORE_CASE: &str = "raven_string_eq_ignore_case";
+
+/// Runtime C symbol repeating a `String` an `Int` number of times. Backs
+/// `String * Int`.
+pub const RUNTIME_STRING_REPEAT: &str = "raven_string_repeat";
+
+/// Runtime C symbol reading the `i`th character of a `String`, counting
+/// characters rather than bytes. Backs `s[i]`.
+pub const RUNTIME_STRING_CHAR_AT: &str = "raven_string_char_at";
+
 /// Map a MIR string-runtime intrinsic mangled name to the runtime C
 /// symbol it lowers to, or `None` when `mangled` is not one of them.
 /// These intrinsics share one call shape: each operand lowers to an
@@ -115,6 +131,9 @@ pub fn string_runtime_symbol(mangled: &str) -> Option<&'static str> {
         mir_intr::STR_CONCAT => RUNTIME_STRING_CONCAT,
         mir_intr::STR_EQ => RUNTIME_STRING_EQ,
         mir_intr::STR_CMP => RUNTIME_STRING_CMP,
+        mir_intr::STR_EQ_IGNORE_CASE => RUNTIME_STRING_EQ_IGNORE_CASE,
+        mir_intr::STR_REPEAT => RUNTIME_STRING_REPEAT,
+        mir_intr::STR_CHAR_AT => RUNTIME_STRING_CHAR_AT,
         mir_intr::INT_TO_STRING => RUNTIME_INT_TO_STRING,
         mir_intr::BOOL_TO_STRING => RUNTIME_BOOL_TO_STRING,
         mir_intr::FLOAT_TO_STRING => RUNTIME_FLOAT_TO_STRING,
@@ -153,6 +172,11 @@ pub const RUNTIME_GC_PUSH_ROOT: &str = "raven_gc_push_root";
 /// Runtime C symbol popping the last `n` single root slots.
 pub const RUNTIME_GC_POP_ROOTS: &str = "raven_gc_pop_roots";
 
+/// Runtime C symbol installing the main-thread stack overflow handler, so
+/// unbounded recursion reports a panic instead of a bare segfault. Emitted
+/// first in the entry shim.
+pub const RUNTIME_STACK_GUARD_INSTALL: &str = "raven_stack_guard_install";
+
 /// Runtime C symbol marking the OS thread as running compiled Raven, so the
 /// parallel collector waits for it to reach a safepoint. Emitted at program
 /// entry (and by the scheduler around a dispatched goroutine).
@@ -204,11 +228,36 @@ pub const RUNTIME_LIST_POP: &str = "raven_list_pop";
 /// `index` into `out_ptr`, returning `1` on success and `0` when the
 /// index is out of range.
 pub const RUNTIME_LIST_GET: &str = "raven_list_get";
+/// `raven_list_copy(List) -> List` allocates a new list holding the same
+/// elements, in one copy of the element buffer.
+pub const RUNTIME_LIST_COPY: &str = "raven_list_copy";
 
 /// Runtime C symbol reporting a fatal panic and terminating the process.
 /// Used by the out-of-bounds index check.
 pub const RUNTIME_PANIC: &str = "raven_panic";
 
+/// Runtime C symbol for a panic raised in compiled code. It takes the
+/// panicking function's address and frame pointer besides the message, so
+/// the runtime can print the Raven call stack that led to the panic.
+pub const RUNTIME_PANIC_AT: &str = "raven_panic_at";
+
+/// Runtime C symbol registering the table of every Raven function's code
+/// range and display name that panic call stacks are read from. Emitted
+/// once in the entry shim.
+pub const RUNTIME_TRACE_REGISTER: &str = "raven_trace_register";
+
+/// Runtime C symbols for `raven build --profile`. The entry shim registers
+/// the function table (the one panic call stacks read) with
+/// `raven_profile_register` and prints the report with
+/// `raven_profile_report` when `main` returns; every Raven function calls
+/// `raven_profile_enter(id)` on entry and `raven_profile_exit(id)` before
+/// each return, `id` being its index in the table. Only a profiled build
+/// emits them.
+pub const RUNTIME_PROFILE_REGISTER: &str = "raven_profile_register";
+pub const RUNTIME_PROFILE_ENTER: &str = "raven_profile_enter";
+pub const RUNTIME_PROFILE_EXIT: &str = "raven_profile_exit";
+pub const RUNTIME_PROFILE_REPORT: &str = "raven_profile_report";
+
 /// Runtime C symbol allocating a closure object.
 pub const RUNTIME_CLOSURE_NEW: &str = "raven_closure_new";
 
@@ -258,6 +307,7 @@ pub fn is_intrinsic(mangled: &str) -> bool {
             | STR_SUBSTRING
             | STR_FROM_BYTE
             | STR_CONCAT_FN
+            | CHAR_FROM_CODE
             | DEFER_PUSH_FN
             | GO_SPAWN_FN
     )
@@ -83,6 +83,11 @@ pub fn host_isa() -> Result<Arc<dyn TargetIsa>, CodegenError> {
     flag_builder
         .set("is_pic", "true")
         .map_err(|e| CodegenError::Target(e.to_string()))?;
+    // Every frame keeps a frame pointer, so a panic can walk the chain of
+    // Raven frames to print its call stack (see `raven_runtime::trace`).
+    flag_builder
+        .set("preserve_frame_pointers", "true")
+        .map_err(|e| CodegenError::Target(e.to_string()))?;
     let flags = settings::Flags::new(flag_builder);
     let isa_builder = cranelift_native::builder()
         .map_err(|s| CodegenError::Target(format!("native ISA: {}", s)))?;
@@ -91,14 +96,32 @@ pub fn host_isa() -> Result<Arc<dyn TargetIsa>, CodegenError> {
         .map_err(|e| CodegenError::Target(e.to_string()))
 }
 
+/// Switches that change what the back end emits. The default is an
+/// ordinary build.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CodegenOptions {
+    /// Instrument every Raven function with call-count and timing hooks and
+    /// print a profile to stderr when the program ends (`raven build
+    /// --profile`; see `raven_runtime::profile`).
+    pub profile: bool,
+}
+
 /// Compile a [`MirProgram`] into a relocatable object file.
 ///
 /// Returns the raw object bytes, ready to be written to disk and
 /// linked. Use [`compile_to_object`] when the caller already has an
 /// ISA; this convenience routine builds the host ISA first.
 pub fn compile_program(program: &MirProgram) -> Result<Vec<u8>, CodegenError> {
+    compile_program_with(program, &CodegenOptions::default())
+}
+
+/// Compile a [`MirProgram`] like [`compile_program`], with `options`.
+pub fn compile_program_with(
+    program: &MirProgram,
+    options: &CodegenOptions,
+) -> Result<Vec<u8>, CodegenError> {
     let isa = host_isa()?;
-    compile_to_object(program, isa)
+    compile_to_object(program, isa, options)
 }
 
 /// Compile a [`MirProgram`] into a relocatable object file using the
@@ -107,6 +130,7 @@ pub fn compile_program(program: &MirProgram) -> Result<Vec<u8>, CodegenError> {
 pub fn compile_to_object(
     program: &MirProgram,
     isa: Arc<dyn TargetIsa>,
+    options: &CodegenOptions,
 ) -> Result<Vec<u8>, CodegenError> {
     let builder = ObjectBuilder::new(
         isa,
@@ -118,6 +142,7 @@ pub fn compile_to_object(
 
     let mut cx = ModuleCx::new(module);
     cx.set_repr_c_structs(program.repr_c_structs.clone());
+    cx.set_profile(options.profile);
     cx.declare_runtime_imports()?;
     cx.declare_externs(program)?;
     cx.declare_functions(program)?;
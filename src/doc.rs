@@ -1,12 +1,15 @@
-//! Generate Markdown API documentation from a package's source.
+//! Generate API documentation from a package's source.
 //!
 //! `rvpm doc` parses each `.rv` source file in a package, collects its
 //! top-level items (`fun`, `struct`, `enum`, `trait`, `const`) together with
 //! the `//` comment block written directly above each, and writes a single
-//! Markdown file under `target/doc/`. There is no separate doc-comment syntax
-//! in Raven, so any contiguous run of `//` lines immediately above an item is
-//! taken as its documentation.
+//! Markdown file under `target/doc/`. With `--html` it writes a browsable
+//! site instead: an `index.html` and one page per source file. There is no
+//! separate doc-comment syntax in Raven, so any contiguous run of `//` lines
+//! immediately above an item is taken as its documentation; `///` lines, the
+//! doc-comment marker of other languages, are read the same way.
 
+use std::collections::HashSet;
 use std::fmt;
 use std::path::{Path, PathBuf};
 
@@ -22,6 +25,16 @@ pub struct DocReport {
     pub outcome_lines: Vec<String>,
 }
 
+/// The output format of a `doc` run.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DocFormat {
+    /// One `target/doc/<package>.md` file.
+    #[default]
+    Markdown,
+    /// `target/doc/<package>/index.html` and one page per source file.
+    Html,
+}
+
 /// An error produced while generating documentation.
 #[derive(Debug)]
 pub enum DocError {
@@ -52,71 +65,238 @@ struct DocItem {
     doc: String,
 }
 
+/// The documented items of one source file, under its package-relative path.
+struct DocFile {
+    rel: String,
+    items: Vec<DocItem>,
+}
+
 /// Generate `target/doc/<package>.md` for the package rooted at `project_dir`.
 pub fn generate(project_dir: &Path) -> Result<DocReport, DocError> {
-    let name = package_name(project_dir);
-    let files = discover_source_files(project_dir)?;
+    generate_with(project_dir, DocFormat::Markdown)
+}
 
-    let mut body = String::new();
-    let mut item_count = 0usize;
-    let mut documented_files = 0usize;
-    for file in &files {
-        let source = std::fs::read_to_string(file).map_err(|e| DocError::Io {
+/// Generate documentation in `format` for the package rooted at `project_dir`.
+pub fn generate_with(project_dir: &Path, format: DocFormat) -> Result<DocReport, DocError> {
+    let name = package_name(project_dir);
+    let mut files = Vec::new();
+    for file in discover_source_files(project_dir)? {
+        let source = std::fs::read_to_string(&file).map_err(|e| DocError::Io {
             path: file.clone(),
             message: e.to_string(),
         })?;
-        let items = documented_items(&source, file)?;
+        let items = documented_items(&source, &file)?;
         if items.is_empty() {
             continue;
         }
-        documented_files += 1;
-        let rel = file.strip_prefix(project_dir).unwrap_or(file);
-        body.push_str(&format!("## {}\n\n", rel_display(rel)));
-        for it in items {
-            item_count += 1;
-            body.push_str(&format!("### {}\n\n", it.name));
-            body.push_str(&format!("```rust\n{}\n```\n\n", it.signature));
-            if !it.doc.is_empty() {
-                body.push_str(&it.doc);
-                body.push_str("\n\n");
-            }
-        }
-    }
-
-    let mut md = format!("# {} API\n\n", name);
-    if item_count == 0 {
-        md.push_str("No documented top-level items were found.\n");
-    } else {
-        md.push_str(&format!(
-            "{} item(s) across {} file(s).\n\n",
-            item_count, documented_files
-        ));
-        md.push_str(&body);
+        let rel = file.strip_prefix(project_dir).unwrap_or(&file);
+        files.push(DocFile {
+            rel: rel_display(rel),
+            items,
+        });
     }
+    let item_count = files.iter().map(|f| f.items.len()).sum::<usize>();
 
     let out_dir = project_dir.join("target").join("doc");
-    std::fs::create_dir_all(&out_dir).map_err(|e| DocError::Io {
-        path: out_dir.clone(),
-        message: e.to_string(),
-    })?;
-    let output = out_dir.join(format!("{}.md", name));
-    std::fs::write(&output, &md).map_err(|e| DocError::Io {
-        path: output.clone(),
-        message: e.to_string(),
-    })?;
+    let (output, pages) = match format {
+        DocFormat::Markdown => (
+            out_dir.join(format!("{}.md", name)),
+            vec![(format!("{}.md", name), render_markdown(&name, &files))],
+        ),
+        DocFormat::Html => (
+            out_dir.join(&name).join("index.html"),
+            render_html(&name, &files)
+                .into_iter()
+                .map(|(page, html)| (format!("{}/{}", name, page), html))
+                .collect(),
+        ),
+    };
+    for (page, text) in &pages {
+        let path = out_dir.join(page);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| DocError::Io {
+                path: parent.to_path_buf(),
+                message: e.to_string(),
+            })?;
+        }
+        std::fs::write(&path, text).map_err(|e| DocError::Io {
+            path: path.clone(),
+            message: e.to_string(),
+        })?;
+    }
 
     Ok(DocReport {
         outcome_lines: vec![format!(
             "Wrote {} ({} item(s) from {} file(s))",
             output.display(),
             item_count,
-            documented_files
+            files.len()
         )],
         output,
         item_count,
     })
 }
 
+/// The whole package as one Markdown document.
+fn render_markdown(name: &str, files: &[DocFile]) -> String {
+    let item_count = files.iter().map(|f| f.items.len()).sum::<usize>();
+    let mut md = format!("# {} API\n\n", name);
+    if item_count == 0 {
+        md.push_str("No documented top-level items were found.\n");
+        return md;
+    }
+    md.push_str(&format!(
+        "{} item(s) across {} file(s).\n\n",
+        item_count,
+        files.len()
+    ));
+    for file in files {
+        md.push_str(&format!("## {}\n\n", file.rel));
+        for it in &file.items {
+            md.push_str(&format!("### {}\n\n", it.name));
+            md.push_str(&format!("```rust\n{}\n```\n\n", it.signature));
+            if !it.doc.is_empty() {
+                md.push_str(&it.doc);
+                md.push_str("\n\n");
+            }
+        }
+    }
+    md
+}
+
+const HTML_STYLE: &str =
+    "body{font-family:sans-serif;max-width:52rem;margin:2rem auto;padding:0 1rem;line-height:1.5}\
+pre{background:#f4f4f4;padding:.75rem;overflow-x:auto}\
+code{font-family:monospace}\
+section{border-top:1px solid #ddd;margin-top:1.5rem}";
+
+/// The HTML site as (page file name, contents) pairs: `index.html` listing
+/// every documented file, then one page per file with a table of contents
+/// and a section for each item, anchored at the item's name.
+fn render_html(name: &str, files: &[DocFile]) -> Vec<(String, String)> {
+    let page_names = html_page_names(files);
+    let mut pages = Vec::new();
+    let mut index = String::new();
+    if files.is_empty() {
+        index.push_str("<p>No documented top-level items were found.</p>\n");
+    } else {
+        index.push_str("<ul>\n");
+        for (file, page) in files.iter().zip(&page_names) {
+            index.push_str(&format!(
+                "<li><a href=\"{}\">{}</a> ({} item(s))</li>\n",
+                page,
+                escape_html(&file.rel),
+                file.items.len()
+            ));
+        }
+        index.push_str("</ul>\n");
+    }
+    pages.push((
+        "index.html".to_string(),
+        html_page(&format!("{} API", name), "", &index),
+    ));
+
+    for (file, page) in files.iter().zip(page_names) {
+        let mut body = String::from("<ul>\n");
+        for it in &file.items {
+            body.push_str(&format!(
+                "<li><a href=\"#{0}\"><code>{0}</code></a></li>\n",
+                escape_html(&it.name)
+            ));
+        }
+        body.push_str("</ul>\n");
+        for it in &file.items {
+            body.push_str(&format!(
+                "<section id=\"{0}\">\n<h2>{0}</h2>\n<pre><code>{1}</code></pre>\n",
+                escape_html(&it.name),
+                escape_html(&it.signature)
+            ));
+            body.push_str(&doc_html(&it.doc));
+            body.push_str("</section>\n");
+        }
+        pages.push((
+            page,
+            html_page(
+                &file.rel,
+                &format!(
+                    "<p><a href=\"index.html\">{} API</a></p>\n",
+                    escape_html(name)
+                ),
+                &body,
+            ),
+        ));
+    }
+    pages
+}
+
+/// A complete HTML document titled `title`, with `nav` above the heading.
+fn html_page(title: &str, nav: &str, body: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<title>{0}</title>\n<style>{1}</style>\n</head>\n<body>\n{2}<h1>{0}</h1>\n{3}</body>\n</html>\n",
+        escape_html(title),
+        HTML_STYLE,
+        nav,
+        body
+    )
+}
+
+/// The page file for a source file: its relative path with each `/` turned
+/// into `.` and `.rv` into `.html`, so every page sits beside `index.html`
+/// (`src/util/text.rv` becomes `src.util.text.html`).
+fn html_page_name(rel: &str) -> String {
+    let stem = rel.strip_suffix(".rv").unwrap_or(rel);
+    format!("{}.html", stem.replace('/', "."))
+}
+
+/// The page file for each of `files`, in order. A name already taken, by
+/// `index.html` or by an earlier file (`a/b.rv` and `a.b.rv` both give
+/// `a.b.html`), gets the first free `-2`, `-3`, ... suffix instead.
+fn html_page_names(files: &[DocFile]) -> Vec<String> {
+    let mut taken: HashSet<String> = HashSet::from(["index.html".to_string()]);
+    files
+        .iter()
+        .map(|file| {
+            let name = html_page_name(&file.rel);
+            let stem = name.strip_suffix(".html").unwrap_or(&name);
+            let unique = std::iter::once(name.clone())
+                .chain((2..).map(|n| format!("{stem}-{n}.html")))
+                .find(|candidate| !taken.contains(candidate))
+                .expect("an unbounded suffix range always has a free name");
+            taken.insert(unique.clone());
+            unique
+        })
+        .collect()
+}
+
+/// A doc comment as HTML paragraphs: a blank comment line separates
+/// paragraphs, and a backtick-quoted span becomes `<code>`.
+fn doc_html(doc: &str) -> String {
+    let mut out = String::new();
+    for para in doc.split("\n\n") {
+        let text = para.trim();
+        if text.is_empty() {
+            continue;
+        }
+        let mut html = String::new();
+        for (i, part) in escape_html(text).split('`').enumerate() {
+            if i % 2 == 1 {
+                html.push_str(&format!("<code>{}</code>", part));
+            } else {
+                html.push_str(part);
+            }
+        }
+        out.push_str(&format!("<p>{}</p>\n", html));
+    }
+    out
+}
+
+fn escape_html(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
 /// The package name from `rv.toml`, falling back to the directory name.
 fn package_name(project_dir: &Path) -> String {
     if let Ok(text) = std::fs::read_to_string(project_dir.join("rv.toml")) {
@@ -272,11 +452,21 @@ fn skip_non_code(bytes: &[u8], i: usize) -> Option<usize> {
             Some(j)
         }
         b'/' if bytes.get(i + 1) == Some(&b'*') => {
+            // Block comments nest, matching the lexer.
             let mut j = i + 2;
-            while j + 1 < bytes.len() && !(bytes[j] == b'*' && bytes[j + 1] == b'/') {
-                j += 1;
+            let mut depth = 1u32;
+            while j < bytes.len() && depth > 0 {
+                if bytes[j] == b'/' && bytes.get(j + 1) == Some(&b'*') {
+                    depth += 1;
+                    j += 2;
+                } else if bytes[j] == b'*' && bytes.get(j + 1) == Some(&b'/') {
+                    depth -= 1;
+                    j += 2;
+                } else {
+                    j += 1;
+                }
             }
-            Some((j + 2).min(bytes.len()))
+            Some(j.min(bytes.len()))
         }
         b'"' | b'\'' => {
             let quote = bytes[i];
@@ -294,7 +484,7 @@ fn skip_non_code(bytes: &[u8], i: usize) -> Option<usize> {
 }
 
 /// The contiguous `//` comment block immediately above the item starting at
-/// `item_line` (1-based), with `//` markers stripped. Attribute lines (`@...`)
+/// `item_line` (1-based), with `//` (or `///`) markers stripped. Attribute lines (`@...`)
 /// between the comment and the item are skipped.
 fn doc_comment_above(lines: &[&str], item_line: u32) -> String {
     if item_line < 2 {
@@ -310,6 +500,7 @@ fn doc_comment_above(lines: &[&str], item_line: u32) -> String {
         if line.starts_with('@') {
             // An attribute decorates the item; keep scanning above it.
         } else if let Some(rest) = line.strip_prefix("//") {
+            let rest = rest.strip_prefix('/').unwrap_or(rest);
             collected.push(rest.trim().to_string());
         } else {
             break;
@@ -323,7 +514,7 @@ fn doc_comment_above(lines: &[&str], item_line: u32) -> String {
     collected.join("\n")
 }
 
-/// Render a relative path with forward slashes for stable Markdown output.
+/// Render a relative path with forward slashes for stable output and page names.
 fn rel_display(rel: &Path) -> String {
     rel.components()
         .map(|c| c.as_os_str().to_string_lossy())
@@ -364,4 +555,77 @@ mod tests {
         assert_eq!(items[0].signature, "fun double(x: Int) -> Int");
         assert!(items[0].doc.is_empty());
     }
+
+    #[test]
+    fn triple_slash_comments_lose_their_marker() {
+        let src = "/// Double `x`.\n///\n/// Never overflows a small input.\nfun double(x: Int) -> Int = x * 2\n";
+        let items = documented_items(src, Path::new("a.rv")).unwrap();
+        assert_eq!(
+            items[0].doc,
+            "Double `x`.\n\nNever overflows a small input."
+        );
+        assert_eq!(
+            doc_html(&items[0].doc),
+            "<p>Double <code>x</code>.</p>\n<p>Never overflows a small input.</p>\n"
+        );
+    }
+
+    #[test]
+    fn html_writes_an_index_and_a_page_per_file() {
+        let dir = std::env::temp_dir().join(format!(
+            "rvpm_doc_html_{}",
+            std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_nanos()
+        ));
+        std::fs::create_dir_all(dir.join("src/util")).unwrap();
+        std::fs::write(
+            dir.join("src/main.rv"),
+            "// Entry point.\nfun main() {\n}\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.join("src/util/text.rv"),
+            "// Wrap `s` in <b> tags.\nfun bold(s: String) -> String = \"<b>${s}</b>\"\n",
+        )
+        .unwrap();
+
+        let report = generate_with(&dir, DocFormat::Html).unwrap();
+        assert_eq!(report.item_count, 2);
+        let site = dir.join("target/doc").join(package_name(&dir));
+        assert_eq!(report.output, site.join("index.html"));
+        let index = std::fs::read_to_string(site.join("index.html")).unwrap();
+        assert!(
+            index.contains("<a href=\"src.main.html\">src/main.rv</a>"),
+            "{index}"
+        );
+        assert!(index.contains("<a href=\"src.util.text.html\">"), "{index}");
+        let page = std::fs::read_to_string(site.join("src.util.text.html")).unwrap();
+        assert!(page.contains("<section id=\"bold\">"), "{page}");
+        assert!(
+            page.contains("<pre><code>fun bold(s: String) -&gt; String</code></pre>"),
+            "{page}"
+        );
+        assert!(
+            page.contains("<p>Wrap <code>s</code> in &lt;b&gt; tags.</p>"),
+            "{page}"
+        );
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn colliding_page_names_get_a_suffix() {
+        let files: Vec<DocFile> = ["a/b.rv", "a.b.rv", "index.rv", "a.b-2.rv"]
+            .iter()
+            .map(|rel| DocFile {
+                rel: rel.to_string(),
+                items: Vec::new(),
+            })
+            .collect();
+        assert_eq!(
+            html_page_names(&files),
+            ["a.b.html", "a.b-2.html", "index-2.html", "a.b-2-2.html"]
+        );
+    }
 }
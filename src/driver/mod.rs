@@ -41,7 +41,7 @@ pub enum DriverError {
 /// when it differs from the entry file (for example an error inside a local
 /// module or a dependency).
 fn render_one(e: &crate::error::RavenError, input: &Path, source: &str) -> String {
-    let span_file = e.span().file.clone();
+    let span_file = e.span().host().file;
     let src: std::borrow::Cow<str> = if span_file.as_path() == input {
         std::borrow::Cow::Borrowed(source)
     } else {
@@ -53,6 +53,19 @@ fn render_one(e: &crate::error::RavenError, input: &Path, source: &str) -> Strin
     e.display(&src)
 }
 
+/// Print each type checker warning to stderr. Warnings never fail the
+/// compile.
+fn report_warnings(warnings: &[crate::error::Warning], input: &Path, source: &str) {
+    for w in warnings {
+        let span_file = w.span().host().file;
+        if span_file.as_path() == input {
+            eprintln!("{}", w.display(source));
+        } else if let Ok(src) = std::fs::read_to_string(span_file.as_path()) {
+            eprintln!("{}", w.display(&src));
+        }
+    }
+}
+
 /// Render a single front-end error into a [`DriverError::Diagnostic`].
 fn frontend_diag(e: crate::error::RavenError, input: &Path, source: &str) -> DriverError {
     DriverError::Diagnostic(render_one(&e, input, source))
@@ -118,8 +131,30 @@ pub fn build_binary_native(
 ) -> Result<(), DriverError> {
     let source = std::fs::read_to_string(input)
         .map_err(|e| DriverError::Io(format!("read {}: {}", input.display(), e)))?;
+    build_source(
+        &source,
+        input,
+        output,
+        ctx,
+        native,
+        &codegen::CodegenOptions::default(),
+    )
+}
 
-    let object_bytes = compile_to_object(&source, input, ctx)?;
+/// Compile already-read `source` to a native executable at `output`.
+/// `input` names the source in diagnostics and anchors `./` imports; it
+/// need not exist on disk, so `raven build -` can compile piped input under
+/// the name given with `--filename`. `options` carries the back end
+/// switches, such as `--profile`.
+pub fn build_source(
+    source: &str,
+    input: &Path,
+    output: &Path,
+    ctx: Option<&PackageContext>,
+    native: &linker::NativeLink,
+    options: &codegen::CodegenOptions,
+) -> Result<(), DriverError> {
+    let object_bytes = compile_to_object(source, input, ctx, options)?;
 
     let runtime = locate_runtime_staticlib()?;
     let tmp = TempDir::new()?;
@@ -157,16 +192,19 @@ pub fn check(source: &str, input: &Path, ctx: Option<&PackageContext>) -> Result
     let mut loader = FsLoader;
     let resolved = resolve_file_ctx(&file, &mut loader, ctx, macro_def_sites)
         .map_err(|e| frontend_diag(e, input, source))?;
-    check_file_all(&resolved).map_err(|es| frontend_diags(es, input, source))?;
+    let typed = check_file_all(&resolved).map_err(|es| frontend_diags(es, input, source))?;
+    report_warnings(&typed.warnings, input, source);
     Ok(())
 }
 
 /// Run the front and middle ends and Cranelift to produce a relocatable
-/// object for `source`. Threads `ctx` through expansion and resolution.
+/// object for `source`. Threads `ctx` through expansion and resolution and
+/// `options` through to the back end.
 pub fn compile_to_object(
     source: &str,
     input: &Path,
     ctx: Option<&PackageContext>,
+    options: &codegen::CodegenOptions,
 ) -> Result<Vec<u8>, DriverError> {
     let tokens = Lexer::new(source.to_string(), input.to_path_buf())
         .tokenize()
@@ -193,6 +231,7 @@ pub fn compile_to_object(
     let resolved = resolve_file_ctx(&file, &mut loader, ctx, macro_def_sites)
         .map_err(|e| frontend_diag(e, input, source))?;
     let typed = check_file_all(&resolved).map_err(|es| frontend_diags(es, input, source))?;
+    report_warnings(&typed.warnings, input, source);
     let hir = lower_file(&typed).map_err(|e| frontend_diag(e, input, source))?;
     if std::env::var("RAVEN_DUMP_HIR").is_ok() {
         eprintln!("{}", crate::hir::pretty_program(&hir));
@@ -201,7 +240,7 @@ pub fn compile_to_object(
     if std::env::var("RAVEN_DUMP_MIR").is_ok() {
         eprintln!("{}", crate::mir::pretty::pretty_program(&mir));
     }
-    codegen::compile_program(&mir).map_err(DriverError::from)
+    codegen::compile_program_with(&mir, options).map_err(DriverError::from)
 }
 
 /// Locate the `raven-runtime` staticlib next to the compiler binary, in
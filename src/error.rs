@@ -2,6 +2,7 @@
 
 use std::fmt;
 
+use crate::ast::source_type_name;
 use crate::span::Span;
 
 /// Lexical analysis errors.
@@ -217,7 +218,12 @@ impl fmt::Display for TypeError {
                 )
             }
             TypeError::UndefinedField { struct_name, field } => {
-                write!(f, "struct `{}` has no field `{}`", struct_name, field)
+                write!(
+                    f,
+                    "struct `{}` has no field `{}`",
+                    source_type_name(struct_name),
+                    field
+                )
             }
             TypeError::UndefinedMethod {
                 receiver_ty,
@@ -241,7 +247,9 @@ impl fmt::Display for TypeError {
             } => write!(
                 f,
                 "wrong number of arguments to `{}`: expected {}, found {}",
-                func, expected, actual
+                source_type_name(func),
+                expected,
+                actual
             ),
             TypeError::NonExhaustiveMatch { missing } => write!(
                 f,
@@ -255,7 +263,9 @@ impl fmt::Display for TypeError {
             TypeError::RedundantPattern => {
                 write!(f, "unreachable pattern, shadowed by an earlier arm")
             }
-            TypeError::UnknownType(name) => write!(f, "unknown type `{}`", name),
+            TypeError::UnknownType(name) => {
+                write!(f, "unknown type `{}`", source_type_name(name))
+            }
             TypeError::CannotInferType => write!(
                 f,
                 "cannot infer the type of this expression; an annotation is needed"
@@ -277,7 +287,9 @@ impl fmt::Display for TypeError {
             } => write!(
                 f,
                 "`{}` takes {} type argument(s), but {} were supplied",
-                decl, expected, actual
+                source_type_name(decl),
+                expected,
+                actual
             ),
             TypeError::OverlappingImpls {
                 ty,
@@ -383,61 +395,7 @@ impl RavenError {
     /// ```
     pub fn render(&self, source: &str, color: bool) -> String {
         let p = Palette::new(color);
-        let d = self.diagnostic();
-        let span = self.span();
-        let line_no = span.line.max(1);
-        let g = line_no.to_string().len();
-        let pad = " ".repeat(g);
-
-        let mut out = String::new();
-        // Headline.
-        out.push_str(&format!("{}error{}: {}\n", p.err, p.reset, d.headline));
-        // Location, with the box arm aligned under the gutter bar.
-        out.push_str(&format!(
-            "{}{} \u{250c}\u{2500}{} {}\n",
-            pad, p.gutter, p.reset, span
-        ));
-        out.push_str(&format!("{}{} \u{2502}{}\n", pad, p.gutter, p.reset));
-
-        // Source line and caret underline.
-        if let Some(text) = source.lines().nth((line_no.saturating_sub(1)) as usize) {
-            out.push_str(&format!(
-                "{}{:>w$} \u{2502}{} {}\n",
-                p.gutter,
-                line_no,
-                p.reset,
-                text,
-                w = g
-            ));
-            let start_col = span.col.saturating_sub(1) as usize;
-            let lead = " ".repeat(start_col);
-            // Cap the underline to the visible part of the line so a span that
-            // covers several lines (a whole `match`, say) does not run a long
-            // ribbon of carets past the end of the source line.
-            let avail = text.chars().count().saturating_sub(start_col).max(1);
-            let carets = "^".repeat(span.len().max(1).min(avail));
-            let label = match &d.label {
-                Some(l) => format!(" {}", l),
-                None => String::new(),
-            };
-            out.push_str(&format!(
-                "{}{} \u{2502}{} {}{}{}{}{}\n",
-                pad, p.gutter, p.reset, lead, p.err, carets, label, p.reset
-            ));
-        }
-
-        // help: and note: lines, after a closing gutter bar.
-        if !d.helps.is_empty() || !d.notes.is_empty() {
-            out.push_str(&format!("{}{} \u{2502}{}\n", pad, p.gutter, p.reset));
-            for h in &d.helps {
-                out.push_str(&format!("{} {}help{}: {}\n", pad, p.help, p.reset, h));
-            }
-            for n in &d.notes {
-                out.push_str(&format!("{} {}note{}: {}\n", pad, p.note, p.reset, n));
-            }
-        }
-
-        out
+        render_diag(&self.diagnostic(), Level::Error, self.span(), source, &p)
     }
 
     /// Build the friendly headline, the inline caret label, and the
@@ -469,9 +427,83 @@ impl RavenError {
     }
 }
 
-/// The rendered parts of one error.
+/// Whether a rendered diagnostic is an error or a warning. The two share one
+/// layout and differ only in the headline word and its color.
+#[derive(Clone, Copy)]
+enum Level {
+    Error,
+    Warning,
+}
+
+/// Render `d` anchored at `span`: the shared layout behind
+/// [`RavenError::render`] and [`Warning::render`].
+fn render_diag(d: &Diag, level: Level, span: &Span, source: &str, p: &Palette) -> String {
+    let (level_name, level_color) = match level {
+        Level::Error => ("error", p.err),
+        Level::Warning => ("warning", p.warn),
+    };
+    let span = &span.host();
+    let line_no = span.line.max(1);
+    let g = line_no.to_string().len();
+    let pad = " ".repeat(g);
+
+    let mut out = String::new();
+    // Headline.
+    out.push_str(&format!(
+        "{}{}{}: {}\n",
+        level_color, level_name, p.reset, d.headline
+    ));
+    // Location, with the box arm aligned under the gutter bar.
+    out.push_str(&format!(
+        "{}{} \u{250c}\u{2500}{} {}\n",
+        pad, p.gutter, p.reset, span
+    ));
+    out.push_str(&format!("{}{} \u{2502}{}\n", pad, p.gutter, p.reset));
+
+    // Source line and caret underline.
+    if let Some(text) = source.lines().nth((line_no.saturating_sub(1)) as usize) {
+        out.push_str(&format!(
+            "{}{:>w$} \u{2502}{} {}\n",
+            p.gutter,
+            line_no,
+            p.reset,
+            text,
+            w = g
+        ));
+        let start_col = span.col.saturating_sub(1) as usize;
+        let lead = " ".repeat(start_col);
+        // Cap the underline to the visible part of the line so a span that
+        // covers several lines (a whole `match`, say) does not run a long
+        // ribbon of carets past the end of the source line.
+        let avail = text.chars().count().saturating_sub(start_col).max(1);
+        let carets = "^".repeat(span.len().max(1).min(avail));
+        let label = match &d.label {
+            Some(l) => format!(" {}", l),
+            None => String::new(),
+        };
+        out.push_str(&format!(
+            "{}{} \u{2502}{} {}{}{}{}{}\n",
+            pad, p.gutter, p.reset, lead, level_color, carets, label, p.reset
+        ));
+    }
+
+    // help: and note: lines, after a closing gutter bar.
+    if !d.helps.is_empty() || !d.notes.is_empty() {
+        out.push_str(&format!("{}{} \u{2502}{}\n", pad, p.gutter, p.reset));
+        for h in &d.helps {
+            out.push_str(&format!("{} {}help{}: {}\n", pad, p.help, p.reset, h));
+        }
+        for n in &d.notes {
+            out.push_str(&format!("{} {}note{}: {}\n", pad, p.note, p.reset, n));
+        }
+    }
+
+    out
+}
+
+/// The rendered parts of one diagnostic.
 struct Diag {
-    /// The `error: <headline>` sentence.
+    /// The `error: <headline>` (or `warning: <headline>`) sentence.
     headline: String,
     /// An optional short annotation printed next to the caret.
     label: Option<String>,
@@ -484,6 +516,7 @@ struct Diag {
 /// ANSI color codes, or empty strings when color is disabled.
 struct Palette {
     err: &'static str,
+    warn: &'static str,
     gutter: &'static str,
     help: &'static str,
     note: &'static str,
@@ -495,6 +528,7 @@ impl Palette {
         if color {
             Palette {
                 err: "\x1b[1;31m",
+                warn: "\x1b[1;33m",
                 gutter: "\x1b[36m",
                 help: "\x1b[1;32m",
                 note: "\x1b[1;36m",
@@ -503,6 +537,7 @@ impl Palette {
         } else {
             Palette {
                 err: "",
+                warn: "",
                 gutter: "",
                 help: "",
                 note: "",
@@ -530,7 +565,7 @@ fn type_diagnostic(te: &TypeError) -> (String, Option<String>, Vec<String>) {
             vec!["Raven has no implicit conversions; convert explicitly".into()],
         ),
         TypeError::UnknownType(name) => (
-            format!("cannot find the type `{}` in scope", name),
+            format!("cannot find the type `{}` in scope", source_type_name(name)),
             Some("not a type in scope".into()),
             vec!["type names are PascalCase: Int, Float, Bool, String, Char".into()],
         ),
@@ -543,7 +578,11 @@ fn type_diagnostic(te: &TypeError) -> (String, Option<String>, Vec<String>) {
             Vec::new(),
         ),
         TypeError::UndefinedField { struct_name, field } => (
-            format!("`{}` has no field `{}`", struct_name, field),
+            format!(
+                "`{}` has no field `{}`",
+                source_type_name(struct_name),
+                field
+            ),
             Some("no such field".into()),
             Vec::new(),
         ),
@@ -554,7 +593,7 @@ fn type_diagnostic(te: &TypeError) -> (String, Option<String>, Vec<String>) {
         } => (
             format!(
                 "`{}` takes {} argument{}, but got {}",
-                func,
+                source_type_name(func),
                 expected,
                 if *expected == 1 { "" } else { "s" },
                 actual
@@ -597,6 +636,71 @@ impl fmt::Display for RavenError {
     }
 }
 
+/// A compiler warning: a diagnostic that is reported but does not stop the
+/// build.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Warning {
+    /// A top level function in the entry file that nothing calls or
+    /// references. Silenced with `@allow(dead_code)` on the function.
+    DeadCode { name: String, span: Span },
+    /// A `loop` with no `break`, `return`, or `?` that could leave it.
+    /// Silenced with `@allow(infinite_loop)` on the enclosing function.
+    InfiniteLoop { span: Span },
+}
+
+impl Warning {
+    /// The span associated with this warning.
+    pub fn span(&self) -> &Span {
+        match self {
+            Warning::DeadCode { span, .. } => span,
+            Warning::InfiniteLoop { span } => span,
+        }
+    }
+
+    /// Render this warning for the CLI, in the same layout as
+    /// [`RavenError::display`] with a `warning:` headline.
+    pub fn display(&self, source: &str) -> String {
+        self.render(source, color_for_stderr())
+    }
+
+    /// Render the warning, choosing color explicitly.
+    pub fn render(&self, source: &str, color: bool) -> String {
+        let p = Palette::new(color);
+        render_diag(&self.diagnostic(), Level::Warning, self.span(), source, &p)
+    }
+
+    fn diagnostic(&self) -> Diag {
+        match self {
+            Warning::DeadCode { name, .. } => Diag {
+                headline: format!("function `{}` is never used", name),
+                label: Some("never called".into()),
+                helps: Vec::new(),
+                notes: vec!["add `@allow(dead_code)` above the function to silence this".into()],
+            },
+            Warning::InfiniteLoop { .. } => Diag {
+                headline: "this `loop` never ends".into(),
+                label: Some("no `break`, `return`, or `?` leaves this loop".into()),
+                helps: Vec::new(),
+                notes: vec![
+                    "add `@allow(infinite_loop)` above the function if it is meant to run forever"
+                        .into(),
+                ],
+            },
+        }
+    }
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Warning::DeadCode { name, span } => {
+                write!(f, "{}: function `{}` is never used", span, name)
+            }
+            Warning::InfiniteLoop { span } => write!(f, "{}: this `loop` never ends", span),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -65,11 +65,7 @@ pub fn scan(src: &str) -> Vec<Comment> {
             b'/' if i + 1 < n && bytes[i + 1] == b'*' => {
                 let start = i;
                 let own_line = !line_has_code;
-                let mut j = i + 2;
-                while j + 1 < n && !(bytes[j] == b'*' && bytes[j + 1] == b'/') {
-                    j += 1;
-                }
-                j = (j + 2).min(n);
+                let j = skip_block_comment(bytes, i);
                 let raw = &src[start..j];
                 out.push(Comment {
                     start,
@@ -158,3 +154,27 @@ fn skip_char(bytes: &[u8], start: usize) -> usize {
     }
     j
 }
+
+/// Skip a `/* ... */` block comment starting at the opening `/`. Block
+/// comments nest, matching the lexer. Returns the index just past the
+/// closing `*/` of the outermost comment (or end of input).
+fn skip_block_comment(bytes: &[u8], start: usize) -> usize {
+    let n = bytes.len();
+    let mut j = start + 2;
+    let mut depth = 1u32;
+    while j < n {
+        if bytes[j] == b'/' && j + 1 < n && bytes[j + 1] == b'*' {
+            depth += 1;
+            j += 2;
+        } else if bytes[j] == b'*' && j + 1 < n && bytes[j + 1] == b'/' {
+            depth -= 1;
+            j += 2;
+            if depth == 0 {
+                return j;
+            }
+        } else {
+            j += 1;
+        }
+    }
+    n
+}
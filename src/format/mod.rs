@@ -19,7 +19,7 @@ use crate::ast::{
     UnaryOp, VariantPayload,
 };
 use crate::lexer::{Lexer, Token, TokenKind};
-use crate::parser::parse;
+use crate::parser::parse_keeping_local_types;
 
 mod comments;
 
@@ -80,8 +80,10 @@ pub fn format_source_with_opts(
     // are parsed into dedicated AST nodes here (the formatter parses
     // un-expanded source, unlike the compile pipeline, which expands macros
     // at the token level first) and rendered by `macro_decl` / the
-    // `MacroCall` expression arm.
-    let file = parse(&tokens).map_err(|e| FormatError::Parse(e.to_string()))?;
+    // `MacroCall` expression arm. A `struct` or `enum` declared inside a
+    // function body likewise stays where it was written instead of being
+    // lifted to the top level.
+    let file = parse_keeping_local_types(&tokens).map_err(|e| FormatError::Parse(e.to_string()))?;
     let comments = comments::scan(src);
     let indent_unit = " ".repeat(indent_width as usize);
     let mut p = Printer::new(src, comments, indent_unit, wrap_width as usize);
@@ -228,7 +230,7 @@ impl<'a> Printer<'a> {
             // Emit any own-line comments that precede this item, each with a
             // gap-preserving blank, then the item itself.
             prev_end_line = self.emit_toplevel_comments(item.span.start, prev_end_line);
-            // A struct/enum's `@derive`/`@repr` attributes are emitted by
+            // An item's `@derive`/`@repr`/`@allow` attributes are emitted by
             // `decl()` but are not covered by `item.span`, so the source lines
             // they occupy are discounted here. Otherwise a doc comment sitting
             // directly above `@derive` would be split from its item by a
@@ -289,7 +291,12 @@ impl Printer<'_> {
     fn decl(&mut self, decl: &Decl) -> Option<String> {
         match &decl.kind {
             DeclKind::Macro(m) => self.macro_decl(m),
-            DeclKind::Function(f) => self.function(f, ""),
+            DeclKind::Function(f) => {
+                if !f.allows.is_empty() {
+                    self.line(&format!("@allow({})", f.allows.join(", ")));
+                }
+                self.function(f, "")
+            }
             DeclKind::Struct(s) => self.struct_decl(s),
             DeclKind::Trait(t) => self.trait_decl(t),
             DeclKind::Impl(i) => self.impl_decl(i),
@@ -547,6 +554,9 @@ impl Printer<'_> {
                 self.blank();
             }
             self.emit_indented_comments_before(m.span.start, self.line_of(m.span.start));
+            if !m.allows.is_empty() {
+                self.line(&format!("@allow({})", m.allows.join(", ")));
+            }
             if let Some(tr) = self.function(m, "") {
                 self.attach_trailing(&tr);
             }
@@ -807,6 +817,17 @@ impl Printer<'_> {
                 let text = self.render_let(name, ty, init, *mutable);
                 self.emit_multiline(&text);
             }
+            StmtKind::Fun { name, func } => {
+                // The closure renders as `fun(params) ...`; splice the name in
+                // after the keyword to get the declaration form back.
+                let lambda = self.render_expr_col(func, name.chars().count() + 1);
+                let text = format!(
+                    "fun {}{}",
+                    name,
+                    lambda.strip_prefix("fun").unwrap_or(&lambda)
+                );
+                self.emit_multiline(&text);
+            }
             StmtKind::Return(e) => {
                 let text = match e {
                     Some(e) => format!("return {}", self.render_expr_col(e, "return ".len())),
@@ -850,6 +871,11 @@ impl Printer<'_> {
                 let text = self.render_expr(e);
                 self.emit_multiline(&text);
             }
+            StmtKind::TypeDecl(decl) => {
+                if let Some(c) = self.decl(decl) {
+                    self.attach_trailing(&c);
+                }
+            }
         }
         if let Some(c) = self.take_trailing_comment(end_line) {
             self.attach_trailing(&c);
@@ -928,11 +954,22 @@ impl Printer<'_> {
             ExprKind::Bool(b) => b.to_string(),
             ExprKind::Str(s) => render_string_lit(s),
             ExprKind::InterpolatedString(frags) => self.render_interpolated(frags),
+            // A raw `r"..."` string parses to a block string; keep the
+            // spelling the source used.
+            ExprKind::BlockStr(s)
+                if self
+                    .src
+                    .get(e.span.start..e.span.end)
+                    .is_some_and(|t| t.starts_with("r\"") && !t.starts_with("r\"\"\"")) =>
+            {
+                format!("r\"{}\"", s)
+            }
             ExprKind::BlockStr(s) => format!("\"\"\"{}\"\"\"", s),
             ExprKind::Char(c) => format!("'{}'", render_char(*c)),
             ExprKind::CStr(s) => format!("c{}", render_string_lit(s)),
             ExprKind::SelfLower => "self".to_string(),
             ExprKind::SelfUpper => "Self".to_string(),
+            ExprKind::Unit => "()".to_string(),
             ExprKind::Ident { name, generics } => {
                 let mut s = name.clone();
                 if !generics.is_empty() {
@@ -1134,10 +1171,20 @@ impl Printer<'_> {
                 let body = self.render_block(b, base);
                 format!("loop {}", body)
             }
-            ExprKind::While { cond, body } => {
+            ExprKind::While {
+                cond,
+                body,
+                else_block,
+            } => {
                 let cond = self.expr_at(cond, base, col + "while ".len());
                 let body = self.render_block(body, base);
-                format!("while {} {}", cond, body)
+                match else_block {
+                    Some(e) => {
+                        let e = self.render_block(e, base);
+                        format!("while {} {} else {}", cond, body, e)
+                    }
+                    None => format!("while {} {}", cond, body),
+                }
             }
             ExprKind::For {
                 pattern,
@@ -1695,14 +1742,15 @@ fn macro_space_before(prev: &TokenKind, cur: &TokenKind) -> bool {
     true
 }
 
-/// The number of `@derive`/`@repr` attribute lines a declaration emits before
-/// its keyword. These attributes are not covered by the declaration's span, so
-/// the top-level blank-line logic discounts them when deciding whether a real
-/// blank line preceded the item.
+/// The number of `@derive`/`@repr`/`@allow` attribute lines a declaration
+/// emits before its keyword. These attributes are not covered by the
+/// declaration's span, so the top-level blank-line logic discounts them when
+/// deciding whether a real blank line preceded the item.
 fn leading_attr_lines(kind: &DeclKind) -> usize {
     match kind {
         DeclKind::Struct(s) => (s.repr_c as usize) + (!s.derives.is_empty() as usize),
         DeclKind::Enum(e) => !e.derives.is_empty() as usize,
+        DeclKind::Function(f) => !f.allows.is_empty() as usize,
         _ => 0,
     }
 }
@@ -1837,7 +1885,9 @@ fn unary_op(op: UnaryOp) -> &'static str {
     match op {
         UnaryOp::Neg => "-",
         UnaryOp::Not => "!",
+        UnaryOp::BitNot => "~",
         UnaryOp::Ref => "&",
+        UnaryOp::TypeOf => "typeof ",
     }
 }
 
@@ -1850,6 +1900,8 @@ fn binary_op(op: BinaryOp) -> &'static str {
         BinaryOp::Mod => "%",
         BinaryOp::Eq => "==",
         BinaryOp::Ne => "!=",
+        BinaryOp::EqIgnoreCase => "==~",
+        BinaryOp::NeIgnoreCase => "!=~",
         BinaryOp::Lt => "<",
         BinaryOp::Le => "<=",
         BinaryOp::Gt => ">",
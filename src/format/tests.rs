@@ -42,6 +42,32 @@ fn function_block_body() {
     assert_eq!(out, "fun main() {\n    let x = 1\n    return x\n}\n");
 }
 
+#[test]
+fn local_function_keeps_its_declaration_form() {
+    let out = fmt("fun main() {\nfun  twice(x: Int)->Int=x*2\nfun show(x: Int) {\nprint(x)\n}\n}");
+    assert_eq!(
+        out,
+        "fun main() {\n    fun twice(x: Int) -> Int = x * 2\n    fun show(x: Int) {\n        print(x)\n    }\n}\n"
+    );
+}
+
+#[test]
+fn typeof_keeps_a_space_before_its_operand() {
+    let out = fmt("fun main() {\nlet t = typeof   x\n}\n");
+    assert_eq!(out, "fun main() {\n    let t = typeof x\n}\n");
+}
+
+#[test]
+fn nested_block_comment_is_kept_whole() {
+    // The inner `*/` must not end the recovered comment early, or the rest
+    // of the outer comment would be emitted as code.
+    let out = fmt("fun main() {\n    /* outer /* inner */ still outer */\n    let x = 1\n}\n");
+    assert!(
+        out.contains("/* outer /* inner */ still outer */"),
+        "nested block comment was split: {out:?}"
+    );
+}
+
 #[test]
 fn comment_inside_call_args_stays_in_place() {
     // A comment interior to a call must stay with its argument and a later
@@ -112,6 +138,30 @@ fn doc_comment_stays_attached_to_derived_item() {
     );
 }
 
+#[test]
+fn unit_value_round_trips() {
+    let src = "fun g() -> Unit = ()\n";
+    assert_eq!(fmt(src), src);
+}
+
+#[test]
+fn allow_attribute_is_kept_above_its_function() {
+    let src = "// Kept for debugging.\n@allow(dead_code)\nfun dump() {}\n";
+    assert_eq!(fmt(src), src);
+}
+
+#[test]
+fn allow_attribute_is_kept_above_its_method() {
+    let src = "impl Server {\n    // Runs until the process exits.\n    @allow(infinite_loop)\n    fun serve(self) {\n        loop {\n            self.accept()\n        }\n    }\n}\n";
+    assert_eq!(fmt(src), src);
+}
+
+#[test]
+fn local_struct_stays_inside_its_function() {
+    let src = "fun area() -> Int {\n    struct Rect {\n        w: Int,\n        h: Int,\n    }\n\n    let r = Rect { w: 3, h: 4 }\n    r.w * r.h\n}\n";
+    assert_eq!(fmt(src), src);
+}
+
 #[test]
 fn intentional_blank_above_derived_item_is_preserved() {
     // A real blank line between a comment and a derived item is kept.
@@ -199,6 +249,12 @@ fn while_loop() {
     assert!(out.contains("while x < 10 {"));
 }
 
+#[test]
+fn while_else_keeps_else_on_the_closing_brace_line() {
+    let out = fmt("fun f(){while x<10{x=x+1}\nelse{g()}}");
+    assert!(out.contains("} else {\n        g()"), "{}", out);
+}
+
 #[test]
 fn defer_stmt() {
     let out = fmt("fun f(){defer cleanup()}");
@@ -360,6 +416,18 @@ fn dollar_brace_in_plain_string_escaped() {
     assert!(out.contains("\\${x}"));
 }
 
+#[test]
+fn bitwise_complement_keeps_its_operand_tight() {
+    let out = fmt("fun f(x:Int)->Int{return ~ x&0xFF}");
+    assert!(out.contains("return ~x & 255"), "{out}");
+}
+
+#[test]
+fn raw_string_keeps_its_prefix() {
+    let out = fmt("fun f()->String{return r\"C:\\Users\\${x}\"}");
+    assert!(out.contains("return r\"C:\\Users\\${x}\""), "{out}");
+}
+
 #[test]
 fn set_literal_round_trips() {
     let out = fmt("fun f(){let s={1,2,  3}}");
@@ -40,6 +40,7 @@ pub struct HirBlock {
 pub enum HirUnaryOp {
     Neg,
     Not,
+    BitNot,
     Ref,
 }
 
@@ -91,6 +92,8 @@ pub enum HirBinaryOp {
     Mod,
     Eq,
     Ne,
+    EqIgnoreCase,
+    NeIgnoreCase,
     Lt,
     Le,
     Gt,
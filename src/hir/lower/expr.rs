@@ -12,6 +12,7 @@ use crate::hir::expr::{
 use crate::hir::pattern::{HirPattern, HirPatternKind};
 use crate::hir::stmt::{HirAssignTarget, HirStmt, HirStmtKind};
 
+use super::list_snapshot::{body_may_change_a_list, snapshot_list};
 use super::pattern::lower_pattern;
 use super::stmt::lower_stmt;
 use super::sugar::{assign_stmt, block_of_tail, ident_expr, let_stmt, make_expr};
@@ -96,28 +97,7 @@ pub(crate) fn lower_expr(
         ExprKind::Float(f) => HirExprKind::Float(*f),
         ExprKind::Bool(b) => HirExprKind::Bool(*b),
         ExprKind::Str(s) | ExprKind::BlockStr(s) => HirExprKind::Str(s.clone()),
-        ExprKind::InterpolatedString(fragments) => {
-            let mut parts = Vec::with_capacity(fragments.len());
-            for frag in fragments {
-                match frag {
-                    crate::ast::StrFragment::Literal(text) => {
-                        parts.push(InterpolPart::Text(text.clone()))
-                    }
-                    crate::ast::StrFragment::Expr(e) => {
-                        // Each fragment was parsed as a real expression and
-                        // type-checked under its own span, so it lowers like
-                        // any other value. The built-in scalars (and a
-                        // `String`) lower to MIR per-type to-string
-                        // conversions and a concat. Any other type is
-                        // routed through its `ToString` impl here, so the
-                        // MIR part is already a `String`.
-                        let lowered = lower_expr(e, &Ty::Str, cx)?;
-                        parts.push(InterpolPart::Expr(to_string_if_needed(lowered)));
-                    }
-                }
-            }
-            HirExprKind::Interpolate(parts)
-        }
+        ExprKind::InterpolatedString(fragments) => lower_interpolation(fragments, cx)?,
         ExprKind::CStr(s) => HirExprKind::CStr(s.clone()),
         ExprKind::Char(c) => HirExprKind::Char(*c),
         ExprKind::SelfLower => HirExprKind::SelfValue,
@@ -175,11 +155,33 @@ pub(crate) fn lower_expr(
                 &span,
             ));
         }
+        ExprKind::Unit => HirExprKind::Unit,
         ExprKind::Paren(inner) => {
             let lowered = lower_expr(inner, &ty, cx)?;
             HirExprKind::Paren(Box::new(lowered))
         }
         ExprKind::Block(b) => HirExprKind::Block(lower_block_to_block(b, &ty, cx)?),
+        // `typeof x` still evaluates `x` for its side effects, then yields the
+        // name of its static type. The name comes from the same `TypeName`
+        // node as `type_name<T>()`, so a generic operand is grounded per
+        // monomorphization.
+        ExprKind::Unary {
+            op: UnaryOp::TypeOf,
+            operand,
+        } => {
+            let lowered = lower_expr(operand, &Ty::Error, cx)?;
+            let operand_ty = lowered.ty.clone();
+            let name = make_expr(HirExprKind::TypeName(operand_ty), ty.clone(), span.clone());
+            HirExprKind::Block(HirBlock {
+                stmts: vec![HirStmt {
+                    span: lowered.span.clone(),
+                    kind: HirStmtKind::Expr(lowered),
+                }],
+                tail: Some(Box::new(name)),
+                ty: ty.clone(),
+                span: span.clone(),
+            })
+        }
         ExprKind::Unary { op, operand } => {
             let lowered = lower_expr(operand, &Ty::Error, cx)?;
             HirExprKind::Unary {
@@ -438,6 +440,14 @@ pub(crate) fn lower_expr(
                     span,
                 ));
             }
+            // `"template {}".format(args)` lowers to the interpolated string
+            // it spells. The type checker already validated the template.
+            if let Some(template) = builtin_string_format(receiver, name, cx) {
+                let fragments =
+                    crate::tycheck::builtin::format_fragments(template, &receiver.span, args)
+                        .map_err(|msg| super::ty_error(msg, &span))?;
+                return Ok(make_expr(lower_interpolation(&fragments, cx)?, ty, span));
+            }
             // `Type.func(args)` lowers to a receiverless associated call.
             // The type checker recorded the implementing type at the
             // receiver span; its presence as a type reference (not a value)
@@ -538,12 +548,22 @@ pub(crate) fn lower_expr(
             }
         }
         ExprKind::Loop(b) => HirExprKind::Loop(lower_block_to_block(b, &Ty::Unit, cx)?),
-        ExprKind::While { cond, body } => {
+        ExprKind::While {
+            cond,
+            body,
+            else_block,
+        } => {
             let c = lower_expr(cond, &Ty::Bool, cx)?;
             let b = lower_block_to_block(body, &Ty::Unit, cx)?;
-            HirExprKind::While {
-                cond: Box::new(c),
-                body: b,
+            match else_block {
+                Some(e) => {
+                    let e = lower_block_to_block(e, &Ty::Unit, cx)?;
+                    return Ok(lower_while_else(c, b, e, &span, cx));
+                }
+                None => HirExprKind::While {
+                    cond: Box::new(c),
+                    body: b,
+                },
             }
         }
         ExprKind::For {
@@ -784,7 +804,9 @@ fn lower_unop(op: UnaryOp) -> HirUnaryOp {
     match op {
         UnaryOp::Neg => HirUnaryOp::Neg,
         UnaryOp::Not => HirUnaryOp::Not,
+        UnaryOp::BitNot => HirUnaryOp::BitNot,
         UnaryOp::Ref => HirUnaryOp::Ref,
+        UnaryOp::TypeOf => unreachable!("`typeof` lowers to a type name, not a unary op"),
     }
 }
 
@@ -826,6 +848,8 @@ fn lower_binop(op: BinaryOp) -> HirBinaryOp {
         BinaryOp::Mod => HirBinaryOp::Mod,
         BinaryOp::Eq => HirBinaryOp::Eq,
         BinaryOp::Ne => HirBinaryOp::Ne,
+        BinaryOp::EqIgnoreCase => HirBinaryOp::EqIgnoreCase,
+        BinaryOp::NeIgnoreCase => HirBinaryOp::NeIgnoreCase,
         BinaryOp::Lt => HirBinaryOp::Lt,
         BinaryOp::Le => HirBinaryOp::Le,
         BinaryOp::Gt => HirBinaryOp::Gt,
@@ -948,7 +972,8 @@ fn ctor_pat(name: &str, elements: Vec<HirPattern>, span: Span) -> HirPattern {
 ///   interval. The endpoints are each evaluated once into a local.
 /// * A `List<T>` value is lowered to an index loop driven by the list's
 ///   `len()` and element indexing (issue #138). The list expression is
-///   evaluated once into a local.
+///   evaluated once into a local, or into a copy of the list when the body
+///   might change it (see `list_snapshot`).
 ///
 /// Both forms produce the same shape:
 ///
@@ -1016,11 +1041,17 @@ fn lower_for(
     if !matches!(source_expr.ty.strip_self(), Ty::List(_)) {
         return Ok(lower_iterator_for(pat, source_expr, body, span, cx));
     }
-    let list_expr = source_expr;
-    let element_ty = match list_expr.ty.strip_self() {
+    let element_ty = match source_expr.ty.strip_self() {
         Ty::List(t) => (**t).clone(),
         _ => Ty::Error,
     };
+    // Iterate a copy when the body might change the list, so the loop sees
+    // the elements the list held when it started.
+    let list_expr = if body_may_change_a_list(body) {
+        snapshot_list(source_expr, span, cx)
+    } else {
+        source_expr
+    };
     let list_ty = list_expr.ty.clone();
     let list_name = cx.fresh("list");
     let list_let = let_stmt(&list_name, list_ty.clone(), list_expr, span.clone());
@@ -1176,6 +1207,82 @@ fn lower_iterator_for(
     make_expr(HirExprKind::Block(block), Ty::Unit, span.clone())
 }
 
+/// Lower `while cond { body } else { alt }`. A flag records whether the
+/// body ran; the `else` block runs after the loop only when it did not:
+///
+/// ```text
+/// let __ran = false;
+/// while cond { __ran = true; body }
+/// if !__ran { alt }
+/// ```
+///
+/// The flag is set first in the body, so a `continue` or `break` there still
+/// counts as having run.
+fn lower_while_else(
+    cond: HirExpr,
+    mut body: HirBlock,
+    alt: HirBlock,
+    span: &Span,
+    cx: &LowerCtx<'_>,
+) -> HirExpr {
+    let ran_name = cx.fresh("ran");
+    let ran_let = let_stmt(
+        &ran_name,
+        Ty::Bool,
+        make_expr(HirExprKind::Bool(false), Ty::Bool, span.clone()),
+        span.clone(),
+    );
+    body.stmts.insert(
+        0,
+        assign_stmt(
+            HirAssignTarget::Ident {
+                name: ran_name.clone(),
+                span: span.clone(),
+            },
+            make_expr(HirExprKind::Bool(true), Ty::Bool, span.clone()),
+            span.clone(),
+        ),
+    );
+    let while_expr = make_expr(
+        HirExprKind::While {
+            cond: Box::new(cond),
+            body,
+        },
+        Ty::Unit,
+        span.clone(),
+    );
+    let not_ran = make_expr(
+        HirExprKind::Unary {
+            op: HirUnaryOp::Not,
+            operand: Box::new(ident_expr(&ran_name, Ty::Bool, span.clone())),
+        },
+        Ty::Bool,
+        span.clone(),
+    );
+    let if_expr = make_expr(
+        HirExprKind::If {
+            cond: Box::new(not_ran),
+            then_block: alt,
+            else_block: None,
+        },
+        Ty::Unit,
+        span.clone(),
+    );
+    let block = HirBlock {
+        stmts: vec![
+            ran_let,
+            HirStmt {
+                kind: HirStmtKind::Expr(while_expr),
+                span: span.clone(),
+            },
+        ],
+        tail: Some(Box::new(if_expr)),
+        ty: Ty::Unit,
+        span: span.clone(),
+    };
+    make_expr(HirExprKind::Block(block), Ty::Unit, span.clone())
+}
+
 /// Build the counter-loop body shared by the range and list for-loop
 /// forms. `start`/`end` are the already-lowered bounds, `inclusive`
 /// selects `>` over `>=` for the break test, and `list_name` (when
@@ -1842,6 +1949,7 @@ fn literal_hir_kind(e: &Expr) -> Option<HirExprKind> {
                 (UnaryOp::Neg, HirExprKind::Int(i)) => Some(HirExprKind::Int(i.wrapping_neg())),
                 (UnaryOp::Neg, HirExprKind::Float(f)) => Some(HirExprKind::Float(-f)),
                 (UnaryOp::Not, HirExprKind::Bool(b)) => Some(HirExprKind::Bool(!b)),
+                (UnaryOp::BitNot, HirExprKind::Int(i)) => Some(HirExprKind::Int(!i)),
                 _ => None,
             }
         }
@@ -1859,7 +1967,7 @@ fn literal_hir_kind(e: &Expr) -> Option<HirExprKind> {
 /// a divide or modulo by zero (which traps at runtime) yields `None`.
 fn fold_binary(op: BinaryOp, l: HirExprKind, r: HirExprKind) -> Option<HirExprKind> {
     use BinaryOp::*;
-    use HirExprKind::{Bool, Float, Int};
+    use HirExprKind::{Bool, Float, Int, Str};
     match (l, r) {
         (Int(a), Int(b)) => match op {
             // Integer arithmetic wraps two's-complement at runtime (`iadd`,
@@ -1896,7 +2004,7 @@ fn fold_binary(op: BinaryOp, l: HirExprKind, r: HirExprKind) -> Option<HirExprKi
             Le => Some(Bool(a <= b)),
             Gt => Some(Bool(a > b)),
             Ge => Some(Bool(a >= b)),
-            And | Or => None,
+            And | Or | EqIgnoreCase | NeIgnoreCase => None,
         },
         (Float(a), Float(b)) => match op {
             Add => Some(Float(a + b)),
@@ -1911,6 +2019,14 @@ fn fold_binary(op: BinaryOp, l: HirExprKind, r: HirExprKind) -> Option<HirExprKi
             Ge => Some(Bool(a >= b)),
             _ => None,
         },
+        // An ordering may mix `Int` and `Float`; the `Int` widens, as at
+        // runtime.
+        (Int(a), Float(b)) => fold_mixed_ordering(op, a as f64, b),
+        (Float(a), Int(b)) => fold_mixed_ordering(op, a, b as f64),
+        (Str(a), Str(b)) => match op {
+            Add => Some(Str(a + &b)),
+            _ => None,
+        },
         (Bool(a), Bool(b)) => match op {
             And => Some(Bool(a && b)),
             Or => Some(Bool(a || b)),
@@ -1922,9 +2038,22 @@ fn fold_binary(op: BinaryOp, l: HirExprKind, r: HirExprKind) -> Option<HirExprKi
     }
 }
 
+/// Fold an ordering between an `Int` and a `Float` after widening the
+/// `Int`. Other operators do not mix the two types.
+fn fold_mixed_ordering(op: BinaryOp, a: f64, b: f64) -> Option<HirExprKind> {
+    match op {
+        BinaryOp::Lt => Some(HirExprKind::Bool(a < b)),
+        BinaryOp::Le => Some(HirExprKind::Bool(a <= b)),
+        BinaryOp::Gt => Some(HirExprKind::Bool(a > b)),
+        BinaryOp::Ge => Some(HirExprKind::Bool(a >= b)),
+        _ => None,
+    }
+}
+
 /// When `receiver.name` is a `module.func` call through a stdlib import
 /// alias (`import std/fs` then `fs.write(...)`), return the namespaced
-/// function symbol (`std.<module>.<func>`) the call should target. The type
+/// function symbol (`std.<module>.<func>`) the call should target, or the
+/// bare C name of an extern the module declares (`math.sqrt`). The type
 /// checker has already verified the call against this function's signature.
 fn module_qualified_fn(receiver: &Expr, name: &str, cx: &LowerCtx<'_>) -> Option<String> {
     use crate::resolve::Binding;
@@ -1942,12 +2071,61 @@ fn module_qualified_fn(receiver: &Expr, name: &str, cx: &LowerCtx<'_>) -> Option
     // alias call `alias.fn()` targets `<prefix>.fn`, for std, local, and
     // external sources alike.
     let prefix = import.mangled_prefix.as_ref()?;
-    Some(format!(
+    let mangled = format!(
         "{}{}{}",
         prefix,
         crate::resolve::stdlib::NAMESPACE_SEP,
         name
-    ))
+    );
+    // A module's `extern "C"` function is merged under its bare C name.
+    if !cx.env.functions.values().any(|f| f.name == mangled)
+        && cx.resolved.module_extern(import, name).is_some()
+    {
+        return Some(name.to_string());
+    }
+    Some(mangled)
+}
+
+/// Lower the fragments of an interpolated string.
+fn lower_interpolation(
+    fragments: &[crate::ast::StrFragment],
+    cx: &LowerCtx<'_>,
+) -> Result<HirExprKind, RavenError> {
+    let mut parts = Vec::with_capacity(fragments.len());
+    for frag in fragments {
+        match frag {
+            crate::ast::StrFragment::Literal(text) => parts.push(InterpolPart::Text(text.clone())),
+            crate::ast::StrFragment::Expr(e) => {
+                // Each fragment was parsed as a real expression and
+                // type-checked under its own span, so it lowers like any
+                // other value. The built-in scalars (and a `String`) lower to
+                // MIR per-type to-string conversions and a concat. Any other
+                // type is routed through its `ToString` impl here, so the MIR
+                // part is already a `String`.
+                let lowered = lower_expr(e, &Ty::Str, cx)?;
+                parts.push(InterpolPart::Expr(to_string_if_needed(lowered)));
+            }
+        }
+    }
+    Ok(HirExprKind::Interpolate(parts))
+}
+
+/// When `receiver.name` is the built-in `format` on a string literal, return
+/// the literal template. A user `impl String` method named `format` takes
+/// precedence, as it does in the type checker.
+fn builtin_string_format<'e>(receiver: &'e Expr, name: &str, cx: &LowerCtx<'_>) -> Option<&'e str> {
+    if name != "format" {
+        return None;
+    }
+    let (ExprKind::Str(template) | ExprKind::BlockStr(template)) = &receiver.kind else {
+        return None;
+    };
+    let user_defined = cx
+        .env
+        .impls
+        .iter()
+        .any(|imp| imp.self_ty == Ty::Str && imp.methods.contains_key("format"));
+    (!user_defined).then_some(template.as_str())
 }
 
 /// Wrap an interpolation part in a `to_string()` method call when its
@@ -0,0 +1,146 @@
+//! The snapshot a `for` loop over a list iterates.
+//!
+//! A list loop visits the elements the list held when the loop started:
+//! pushing, popping, or assigning an element in the body changes the list
+//! but not what the loop reads. `for` lowering gets that by iterating a
+//! copy, taken once before the loop, whenever the body might change a
+//! list. A body that cannot (no call of any kind and no element
+//! assignment) iterates the list itself, since no copy could differ from
+//! it.
+//!
+//! The check is syntactic. Any call might reach the loop's list through
+//! another binding, so every call counts, including one to a function that
+//! never touches a list. A lambda that is only defined in the body changes
+//! nothing until it is called, and the call counts. A body that only
+//! looks like it might, such as the prelude `map` calling its function
+//! argument, pays one allocation and one copy of the element buffer.
+
+use crate::ast::{Block, ElseBranch, Expr, ExprKind, Stmt, StmtKind, StrFragment};
+use crate::hir::expr::{HirBlock, HirExpr, HirExprKind};
+use crate::hir::LIST_COPY_METHOD;
+use crate::span::Span;
+
+use super::sugar::{ident_expr, let_stmt, make_expr};
+use super::LowerCtx;
+
+/// Whether running `body` might change a list: it makes a call, or assigns
+/// through an index (`xs[i] = v`, `xs[i] += v`).
+pub(crate) fn body_may_change_a_list(body: &Block) -> bool {
+    block_changes(body)
+}
+
+fn block_changes(b: &Block) -> bool {
+    b.stmts.iter().any(stmt_changes) || b.trailing.as_deref().is_some_and(expr_changes)
+}
+
+fn stmt_changes(s: &Stmt) -> bool {
+    match &s.kind {
+        StmtKind::Let { init, .. } => init.as_ref().is_some_and(expr_changes),
+        StmtKind::Fun { func, .. } => expr_changes(func),
+        StmtKind::Return(e) | StmtKind::Break(e) => e.as_ref().is_some_and(expr_changes),
+        StmtKind::Continue | StmtKind::TypeDecl(_) => false,
+        StmtKind::Defer(e) | StmtKind::Spawn(e) | StmtKind::Expr(e) => expr_changes(e),
+        StmtKind::Assign { target, value, .. } => {
+            matches!(target.kind, ExprKind::Index { .. })
+                || expr_changes(target)
+                || expr_changes(value)
+        }
+    }
+}
+
+fn expr_changes(e: &Expr) -> bool {
+    match &e.kind {
+        ExprKind::Call { .. } | ExprKind::MethodCall { .. } | ExprKind::MacroCall(_) => true,
+        ExprKind::Lambda { .. } => false,
+        ExprKind::Loop(b) | ExprKind::Block(b) => block_changes(b),
+        ExprKind::While {
+            cond,
+            body,
+            else_block,
+        } => {
+            expr_changes(cond)
+                || block_changes(body)
+                || else_block.as_ref().is_some_and(block_changes)
+        }
+        ExprKind::For { iter, body, .. } => expr_changes(iter) || block_changes(body),
+        ExprKind::If {
+            cond,
+            then_branch,
+            else_branch,
+        } => {
+            expr_changes(cond)
+                || block_changes(then_branch)
+                || match else_branch.as_deref() {
+                    Some(ElseBranch::If(e)) => expr_changes(e),
+                    Some(ElseBranch::Block(b)) => block_changes(b),
+                    None => false,
+                }
+        }
+        ExprKind::Match { scrutinee, arms } => {
+            expr_changes(scrutinee)
+                || arms
+                    .iter()
+                    .any(|a| a.guard.as_ref().is_some_and(expr_changes) || expr_changes(&a.body))
+        }
+        ExprKind::InterpolatedString(fragments) => fragments
+            .iter()
+            .any(|f| matches!(f, StrFragment::Expr(e) if expr_changes(e))),
+        ExprKind::StructLit { fields, .. } => fields.iter().any(|f| expr_changes(&f.value)),
+        ExprKind::Array(items) | ExprKind::Tuple(items) | ExprKind::SetLit(items) => {
+            items.iter().any(expr_changes)
+        }
+        ExprKind::MapLit(pairs) => pairs
+            .iter()
+            .any(|(k, v)| expr_changes(k) || expr_changes(v)),
+        ExprKind::Binary { lhs, rhs, .. } => expr_changes(lhs) || expr_changes(rhs),
+        ExprKind::Range { start, end, .. } => expr_changes(start) || expr_changes(end),
+        ExprKind::Index { receiver, index } => expr_changes(receiver) || expr_changes(index),
+        ExprKind::Paren(inner)
+        | ExprKind::Try(inner)
+        | ExprKind::Unary { operand: inner, .. }
+        | ExprKind::Field {
+            receiver: inner, ..
+        } => expr_changes(inner),
+        ExprKind::Int(_)
+        | ExprKind::Float(_)
+        | ExprKind::Bool(_)
+        | ExprKind::Str(_)
+        | ExprKind::BlockStr(_)
+        | ExprKind::Char(_)
+        | ExprKind::CStr(_)
+        | ExprKind::SelfLower
+        | ExprKind::SelfUpper
+        | ExprKind::Ident { .. }
+        | ExprKind::Unit => false,
+    }
+}
+
+/// A copy of the list `list` evaluates to, as a block expression:
+///
+/// ```text
+/// { let __src = <list>; __src.__copy() }
+/// ```
+///
+/// `__copy` ([`LIST_COPY_METHOD`]) copies the element buffer in one go.
+/// The source sits in a local for the call so it stays rooted while the
+/// copy allocates.
+pub(crate) fn snapshot_list(list: HirExpr, span: &Span, cx: &LowerCtx<'_>) -> HirExpr {
+    let list_ty = list.ty.clone();
+    let src = cx.fresh("src");
+    let copy = make_expr(
+        HirExprKind::MethodCall {
+            receiver: Box::new(ident_expr(&src, list_ty.clone(), span.clone())),
+            name: LIST_COPY_METHOD.into(),
+            args: Vec::new(),
+        },
+        list_ty.clone(),
+        span.clone(),
+    );
+    let block = HirBlock {
+        stmts: vec![let_stmt(&src, list_ty.clone(), list, span.clone())],
+        tail: Some(Box::new(copy)),
+        ty: list_ty.clone(),
+        span: span.clone(),
+    };
+    make_expr(HirExprKind::Block(block), list_ty, span.clone())
+}
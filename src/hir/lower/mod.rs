@@ -5,6 +5,7 @@
 //! is expected to have rejected programs that cannot be lowered.
 
 pub mod expr;
+mod list_snapshot;
 pub mod pattern;
 pub mod stmt;
 pub mod sugar;
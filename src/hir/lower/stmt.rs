@@ -37,6 +37,25 @@ pub(crate) fn lower_stmt(stmt: &Stmt, cx: &LowerCtx<'_>) -> Result<Vec<HirStmt>,
                 span: stmt.span.clone(),
             }])
         }
+        // A local function is an immutable binding of its closure.
+        StmtKind::Fun { name, func } => {
+            let lowered = lower_expr(func, &cx.ty_at(&func.span), cx)?;
+            let ty = lowered.ty.clone();
+            Ok(vec![HirStmt {
+                kind: HirStmtKind::Let {
+                    name: name.clone(),
+                    ty,
+                    init: lowered,
+                },
+                span: stmt.span.clone(),
+            }])
+        }
+        // Only the formatter keeps a local type in place; every compile
+        // parse lifts it to a top-level item.
+        StmtKind::TypeDecl(_) => Err(super::ty_error(
+            "internal error: local type declaration reached HIR lowering",
+            &stmt.span,
+        )),
         StmtKind::Return(value) => {
             let payload = match value {
                 Some(v) => Some(Box::new(lower_expr(v, &Ty::Error, cx)?)),
@@ -37,6 +37,11 @@ use crate::span::Span;
 /// it a root, and codegen calls it from the program entry shim before `main`.
 pub const GLOBALS_INIT_FN: &str = "__raven_init_globals";
 
+/// Name of the `List<T>` method a `for` loop's snapshot calls to copy the
+/// list it iterates. Only lowering writes it; MIR lowers it to one runtime
+/// copy of the element buffer.
+pub const LIST_COPY_METHOD: &str = "__copy";
+
 /// One Raven source file after HIR lowering.
 #[derive(Debug, Clone)]
 pub struct HirProgram {
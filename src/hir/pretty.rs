@@ -650,6 +650,7 @@ fn unop(op: HirUnaryOp) -> &'static str {
     match op {
         HirUnaryOp::Neg => "neg",
         HirUnaryOp::Not => "not",
+        HirUnaryOp::BitNot => "bitnot",
         HirUnaryOp::Ref => "ref",
     }
 }
@@ -663,6 +664,8 @@ fn binop(op: HirBinaryOp) -> &'static str {
         HirBinaryOp::Mod => "%",
         HirBinaryOp::Eq => "==",
         HirBinaryOp::Ne => "!=",
+        HirBinaryOp::EqIgnoreCase => "==~",
+        HirBinaryOp::NeIgnoreCase => "!=~",
         HirBinaryOp::Lt => "<",
         HirBinaryOp::Le => "<=",
         HirBinaryOp::Gt => ">",
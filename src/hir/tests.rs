@@ -144,6 +144,34 @@ fn for_over_list_lowers_to_index_loop() {
     assert!(!hir_uses_iterator_intrinsics(&p));
 }
 
+#[test]
+fn for_over_list_copies_it_only_when_the_body_might_change_it() {
+    // The bound list is the source itself when the body makes no call and
+    // assigns no element, and a copy (a block ending in the copy) otherwise.
+    let list_init = |src: &str| {
+        let p = lower(src);
+        let f = only_fn(&p, "f");
+        let HirExprKind::Block(inner) = &first_for_block(f.body.as_ref().expect("body")).kind
+        else {
+            panic!("expected block after for desugaring");
+        };
+        match &inner.stmts[0].kind {
+            HirStmtKind::Let { init, .. } => init.kind.clone(),
+            other => panic!("expected the list binding, got {:?}", other),
+        }
+    };
+    let reads = list_init("fun f(xs: List<Int>) { let t = 0\n for x in xs { t += x } }");
+    assert!(matches!(reads, HirExprKind::Ident(_)), "got {:?}", reads);
+    let pops = list_init("fun f(xs: List<Int>) { for x in xs { xs.pop() } }");
+    assert!(matches!(pops, HirExprKind::Block(_)), "got {:?}", pops);
+    let assigns = list_init("fun f(xs: List<Int>) { for x in xs { xs[0] = x } }");
+    assert!(
+        matches!(assigns, HirExprKind::Block(_)),
+        "got {:?}",
+        assigns
+    );
+}
+
 #[test]
 fn for_over_range_lowers_to_counter_loop() {
     // `for x in 0..3` lowers straight to a counter loop over the integer
@@ -407,6 +435,18 @@ fn computed_const_folds_and_inlines() {
     );
 }
 
+#[test]
+fn mixed_int_float_ordering_const_folds() {
+    let p = lower("const C: Bool = 5 < 3.14\nfun get() -> Bool = C\n");
+    let f = only_fn(&p, "get");
+    let tail = f.body.as_ref().unwrap().tail.as_ref().unwrap();
+    assert!(
+        matches!(tail.kind, HirExprKind::Bool(false)),
+        "expected inlined Bool(false), got {:?}",
+        tail.kind
+    );
+}
+
 #[test]
 fn module_let_becomes_a_global_with_an_init_function() {
     // A module-level `let` is a mutable global: a reference reads its slot
@@ -65,6 +65,8 @@ pub enum TokenKind {
     Extern,
     Defer,
     Spawn,
+    /// `typeof`, the prefix operator naming its operand's static type.
+    TypeOf,
     True,
     False,
     /// lowercase `self`
@@ -87,6 +89,10 @@ pub enum TokenKind {
     // Comparison.
     EqEq,
     NotEq,
+    /// `==~`, string equality ignoring ASCII case.
+    EqEqTilde,
+    /// `!=~`, the negation of `==~`.
+    NotEqTilde,
     Lt,
     Gt,
     LtEq,
@@ -247,13 +253,18 @@ impl Lexer {
             return self.lex_number(start, line, col).map(Some);
         }
 
-        // Identifiers and keywords, with the c"..." prefix special case.
+        // Identifiers and keywords, with the c"..." and r"..." prefix
+        // special cases.
         if ch == 'c' && self.peek_at(1) == Some('"') {
             self.bump(); // consume 'c'
             return self
                 .lex_string_after_quote(start, line, col, true)
                 .map(Some);
         }
+        if ch == 'r' && self.peek_at(1) == Some('"') {
+            self.bump(); // consume 'r'
+            return self.lex_raw_string(start, line, col).map(Some);
+        }
         if is_ident_start(ch) {
             return self.lex_identifier_or_keyword(start, line, col).map(Some);
         }
@@ -329,21 +340,33 @@ impl Lexer {
         }
     }
 
+    /// Consume a `/* ... */` comment. Block comments nest, so commenting out
+    /// code that already contains a block comment does not end early at the
+    /// inner `*/`. An unterminated comment reports the outermost `/*`.
     fn consume_block_comment(&mut self) -> Result<(), RavenError> {
         let start = self.pos;
         let line = self.line;
         let col = self.col;
         self.bump(); // /
         self.bump(); // *
+        let mut depth: u32 = 1;
         loop {
             match self.peek() {
                 None => {
                     return Err(self.err(LexError::UnterminatedBlockComment, start, line, col));
                 }
+                Some('/') if self.peek_at(1) == Some('*') => {
+                    self.bump();
+                    self.bump();
+                    depth += 1;
+                }
                 Some('*') if self.peek_at(1) == Some('/') => {
                     self.bump();
                     self.bump();
-                    return Ok(());
+                    depth -= 1;
+                    if depth == 0 {
+                        return Ok(());
+                    }
                 }
                 Some(_) => {
                     self.bump();
@@ -399,6 +422,7 @@ impl Lexer {
             "extern" => TokenKind::Extern,
             "defer" => TokenKind::Defer,
             "spawn" => TokenKind::Spawn,
+            "typeof" => TokenKind::TypeOf,
             "true" => TokenKind::True,
             "false" => TokenKind::False,
             "self" => TokenKind::SelfLower,
@@ -484,14 +508,8 @@ impl Lexer {
         // A letter right after a decimal number is a malformed literal
         // (`123abc`, `1.0f`), not a number followed by an identifier. Consume
         // the run so the error covers the whole token.
-        if self
-            .peek()
-            .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
-        {
-            while self
-                .peek()
-                .is_some_and(|c| c.is_ascii_alphanumeric() || c == '_')
-            {
+        if self.peek().is_some_and(is_ident_start) {
+            while self.peek().is_some_and(is_ident_continue) {
                 self.bump();
             }
             let lexeme = self.source[start..self.pos].to_string();
@@ -503,6 +521,11 @@ impl Lexer {
 
         if is_float {
             match cleaned.parse::<f64>() {
+                // `f64::from_str` rounds an out-of-range literal to infinity
+                // rather than failing; a literal that large is a typo.
+                Ok(v) if v.is_infinite() => Err(self
+                    .err(LexError::InvalidNumber(lexeme.into()), start, line, col)
+                    .with_hint("a Float literal must be below about 1.8e308")),
                 Ok(v) => Ok(Token::new(
                     TokenKind::FloatLit(v),
                     self.make_span(start, line, col),
@@ -552,15 +575,19 @@ impl Lexer {
         // for this base (`0b12`, `0xZ`), not the start of a new token. Consume
         // the rest of the run so the error covers the whole bad literal instead
         // of silently splitting it in two.
-        if self.peek().is_some_and(|c| c.is_ascii_alphanumeric()) {
-            while self
-                .peek()
-                .is_some_and(|c| c.is_ascii_alphanumeric() || c == '_')
-            {
+        if self.peek().is_some_and(|c| c.is_alphanumeric()) {
+            while self.peek().is_some_and(is_ident_continue) {
                 self.bump();
             }
             let lexeme = self.source[start..self.pos].to_string();
-            return Err(self.err(LexError::InvalidNumber(lexeme), start, line, col));
+            let digits = match radix {
+                2 => "a binary literal uses only the digits `0` and `1`",
+                8 => "an octal literal uses only the digits `0` through `7`",
+                _ => "a hex literal uses the digits `0` through `9` and `a` through `f`",
+            };
+            return Err(self
+                .err(LexError::InvalidNumber(lexeme), start, line, col)
+                .with_hint(digits));
         }
         let raw = &self.source[digits_start..self.pos];
         let cleaned: String = raw.chars().filter(|c| *c != '_').collect();
@@ -575,7 +602,14 @@ impl Lexer {
             )),
             Err(_) => {
                 let lexeme = self.source[start..self.pos].to_string();
-                Err(self.err(LexError::InvalidNumber(lexeme), start, line, col))
+                let err = self.err(LexError::InvalidNumber(lexeme), start, line, col);
+                // An empty `cleaned` is a prefix followed only by
+                // underscores (`0x_`); anything else overflowed 64 bits.
+                Err(if cleaned.is_empty() {
+                    err
+                } else {
+                    err.with_hint("an integer literal must fit in 64 bits")
+                })
             }
         }
     }
@@ -748,6 +782,37 @@ impl Lexer {
         }
     }
 
+    /// Lex a raw `r"..."` string. The `r` has been consumed and the opening
+    /// `"` is at `self.pos`. Like a block string it decodes no escapes and
+    /// interpolates nothing, so it produces the same token; `r"""..."""` is a
+    /// block string with a redundant prefix. A raw string cannot contain a
+    /// `"`; use a block string for that.
+    fn lex_raw_string(&mut self, start: usize, line: u32, col: u32) -> Result<Token, RavenError> {
+        if self.peek_at(1) == Some('"') && self.peek_at(2) == Some('"') {
+            self.bump();
+            self.bump();
+            self.bump();
+            return self.lex_block_string(start, line, col);
+        }
+        self.bump(); // opening "
+        let mut out = String::new();
+        loop {
+            match self.peek() {
+                None | Some('\n') | Some('\r') => {
+                    return Err(self.err(LexError::UnterminatedString, start, line, col));
+                }
+                Some('"') => {
+                    self.bump();
+                    return Ok(Token::new(
+                        TokenKind::BlockStringLit(out),
+                        self.make_span(start, line, col),
+                    ));
+                }
+                Some(_) => out.push(self.bump().unwrap()),
+            }
+        }
+    }
+
     fn lex_char(&mut self, start: usize, line: u32, col: u32) -> Result<Token, RavenError> {
         self.bump(); // opening '
         let value = match self.peek() {
@@ -942,7 +1007,14 @@ impl Lexer {
             }
             other => {
                 self.bump();
-                Err(self.err(LexError::InvalidEscape(other), esc_start, esc_line, esc_col))
+                // A stray `\d` or `\U` is most often a regex or a Windows
+                // path pasted in verbatim, so say how to keep the backslash.
+                Err(
+                    self.err(LexError::InvalidEscape(other), esc_start, esc_line, esc_col)
+                        .with_hint(format!(
+                            "the escapes are `\\n`, `\\t`, `\\r`, `\\0`, `\\\\`, `\\\"`, `\\'`, `\\$`, `\\xHH`, and `\\u{{...}}`; write `\\\\{other}` for a literal backslash, or make the whole string raw, `r\"...\"`"
+                        )),
+                )
             }
         }
     }
@@ -1002,6 +1074,10 @@ impl Lexer {
                 self.bump();
                 if self.peek() == Some('=') {
                     self.bump();
+                    if self.peek() == Some('~') {
+                        self.bump();
+                        return make(self, TokenKind::EqEqTilde);
+                    }
                     return make(self, TokenKind::EqEq);
                 }
                 if self.peek() == Some('>') {
@@ -1014,6 +1090,10 @@ impl Lexer {
                 self.bump();
                 if self.peek() == Some('=') {
                     self.bump();
+                    if self.peek() == Some('~') {
+                        self.bump();
+                        return make(self, TokenKind::NotEqTilde);
+                    }
                     return make(self, TokenKind::NotEq);
                 }
                 make(self, TokenKind::Bang)
@@ -1156,18 +1236,68 @@ impl Lexer {
             }
             other => {
                 self.bump();
-                Err(self.err(LexError::UnexpectedChar(other), start, line, col))
+                let err = self.err(LexError::UnexpectedChar(other), start, line, col);
+                Err(match unexpected_char_hint(other) {
+                    Some(hint) => err.with_hint(hint),
+                    None => err,
+                })
             }
         }
     }
 }
 
+/// A hint for a character that cannot start a token. Most such characters
+/// in real code are lookalikes pasted from a document or typed on a
+/// non-US layout: typographic quotes and dashes, math symbols, full-width
+/// punctuation, and invisible spaces. Each of those names the ASCII
+/// spelling Raven expects. Any other non-ASCII symbol is only valid inside
+/// a string or comment.
+fn unexpected_char_hint(ch: char) -> Option<String> {
+    let hint = match ch {
+        '\u{201C}' | '\u{201D}' | '\u{201E}' | '\u{2033}' => {
+            "this is a typographic quote; a string is written with the ASCII `\"`"
+        }
+        '\u{2018}' | '\u{2019}' | '\u{2032}' => {
+            "this is a typographic quote; a character literal is written with the ASCII `'`"
+        }
+        '\u{2013}' | '\u{2014}' | '\u{2212}' => "this is a dash; subtraction is the ASCII `-`",
+        '\u{00D7}' => "multiplication is written `*`",
+        '\u{00F7}' => "division is written `/`",
+        '\u{2260}' => "not-equal is written `!=`",
+        '\u{2264}' => "less-or-equal is written `<=`",
+        '\u{2265}' => "greater-or-equal is written `>=`",
+        '\u{2192}' => "an arrow is written `->`",
+        '\u{00A7}' => "did you mean `$`, as in `${...}` interpolation?",
+        '\u{00A0}' | '\u{2007}' | '\u{202F}' | '\u{3000}' => {
+            "this is a non-breaking or wide space; replace it with an ordinary space"
+        }
+        '\u{200B}' | '\u{200C}' | '\u{200D}' | '\u{2060}' => {
+            "this is an invisible zero-width character; delete it"
+        }
+        '#' => "a comment starts with `//`",
+        '`' => "a string is written in double quotes, `\"...\"`",
+        // The full-width forms U+FF01..=U+FF5E mirror ASCII `!`..=`~`.
+        '\u{FF01}'..='\u{FF5E}' => {
+            let ascii = char::from_u32(ch as u32 - 0xFEE0)?;
+            return Some(format!(
+                "this is a full-width `{ch}`; use the ASCII `{ascii}`"
+            ));
+        }
+        c if !c.is_ascii() => "a non-ASCII symbol may appear only inside a string or comment",
+        _ => return None,
+    };
+    Some(hint.to_string())
+}
+
+/// Identifiers may start with `_` or any Unicode letter, so `café` and
+/// `日本語` are ordinary names.
 fn is_ident_start(ch: char) -> bool {
-    ch == '_' || ch.is_ascii_alphabetic()
+    ch == '_' || ch.is_alphabetic()
 }
 
+/// After the first character an identifier may also contain Unicode digits.
 fn is_ident_continue(ch: char) -> bool {
-    ch == '_' || ch.is_ascii_alphanumeric()
+    ch == '_' || ch.is_alphanumeric()
 }
 
 #[cfg(test)]
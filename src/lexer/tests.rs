@@ -135,6 +135,28 @@ fn identifiers_vs_keywords() {
     );
 }
 
+#[test]
+fn word_logical_operators_are_plain_identifiers() {
+    // Logic is spelled `&&` and `||`; `and` and `or` are not keywords, so
+    // they and names built from them lex as whole identifiers.
+    let toks = lex("and or and_something my_or_handler a && b || c");
+    assert_eq!(
+        kinds(&toks),
+        vec![
+            TokenKind::Identifier("and".into()),
+            TokenKind::Identifier("or".into()),
+            TokenKind::Identifier("and_something".into()),
+            TokenKind::Identifier("my_or_handler".into()),
+            TokenKind::Identifier("a".into()),
+            TokenKind::AndAnd,
+            TokenKind::Identifier("b".into()),
+            TokenKind::OrOr,
+            TokenKind::Identifier("c".into()),
+            TokenKind::Eof,
+        ]
+    );
+}
+
 #[test]
 fn integer_literals_in_all_four_bases_with_underscores() {
     let toks = lex("42 1_000_000 0xFF_FF 0b1010_0101 0o755");
@@ -151,6 +173,20 @@ fn integer_literals_in_all_four_bases_with_underscores() {
     );
 }
 
+#[test]
+fn underscores_group_float_digits_but_cannot_lead_a_number() {
+    let toks = lex("1.234_567_8 1_0.5e1_0 _1_000");
+    assert_eq!(
+        kinds(&toks),
+        vec![
+            TokenKind::FloatLit(1.234_567_8),
+            TokenKind::FloatLit(10.5e10),
+            TokenKind::Identifier("_1_000".into()),
+            TokenKind::Eof,
+        ]
+    );
+}
+
 #[test]
 fn float_literals_with_and_without_exponent() {
     let toks = lex("3.25 1.0e10 6.022e-23 1E+3 1e10");
@@ -177,6 +213,37 @@ fn string_with_escape_sequences_is_cooked() {
     }
 }
 
+#[test]
+fn escaped_quote_does_not_end_the_string() {
+    // A lone `\"` is the whole string, and the tokens after it still lex.
+    let toks = lex(r#""\"" == x"#);
+    match &toks[0].kind {
+        TokenKind::StringLit(s) => assert_eq!(s, "\""),
+        other => panic!("expected StringLit, got {:?}", other),
+    }
+    assert!(matches!(toks[1].kind, TokenKind::EqEq));
+}
+
+#[test]
+fn escaped_quotes_around_an_interpolation_are_cooked() {
+    let toks = lex(r#""said \"${word}\"""#);
+    match &toks[0].kind {
+        TokenKind::StringLit(s) => assert_eq!(s, "said \"${word}\""),
+        other => panic!("expected StringLit, got {:?}", other),
+    }
+}
+
+#[test]
+fn carriage_return_and_nul_escapes_are_cooked() {
+    // Escapes decode once, here, so the runtime never re-scans a string for
+    // `\n` or `\0` when it prints one.
+    let toks = lex(r#""a\rb\0c""#);
+    match &toks[0].kind {
+        TokenKind::StringLit(s) => assert_eq!(s, "a\rb\0c"),
+        other => panic!("expected StringLit, got {:?}", other),
+    }
+}
+
 #[test]
 fn string_preserves_interpolation_verbatim() {
     let src = r#""hello, ${name}!""#;
@@ -238,6 +305,35 @@ fn char_literal_basic_and_escaped() {
     assert_eq!(chars, vec!['a', '\n', '\u{1F600}']);
 }
 
+#[test]
+fn raw_string_decodes_no_escapes() {
+    let toks = lex(r#"r"\n" "\n" r"C:\Users\${name}""#);
+    assert_eq!(
+        kinds(&toks),
+        vec![
+            TokenKind::BlockStringLit("\\n".into()),
+            TokenKind::StringLit("\n".into()),
+            TokenKind::BlockStringLit("C:\\Users\\${name}".into()),
+            TokenKind::Eof,
+        ]
+    );
+}
+
+#[test]
+fn raw_triple_quoted_string_is_a_block_string() {
+    let toks = lex("r\"\"\"a \\d+\nb\"\"\"");
+    assert_eq!(toks[0].kind, TokenKind::BlockStringLit("a \\d+\nb".into()));
+}
+
+#[test]
+fn raw_string_cannot_span_lines() {
+    let err = lex_err("r\"abc\ndef\"");
+    assert!(matches!(
+        err,
+        RavenError::Lex(LexError::UnterminatedString, _, _)
+    ));
+}
+
 #[test]
 fn c_string_literal_is_distinct_kind() {
     let toks = lex(r#"c"hello""#);
@@ -296,6 +392,26 @@ fn operators_use_longest_match() {
     assert_eq!(kinds(&toks), expected);
 }
 
+#[test]
+fn case_insensitive_equality_operators() {
+    let toks = lex("a ==~ b != ~c !=~ d == ~e");
+    let expected = vec![
+        TokenKind::Identifier("a".into()),
+        TokenKind::EqEqTilde,
+        TokenKind::Identifier("b".into()),
+        TokenKind::NotEq,
+        TokenKind::Tilde,
+        TokenKind::Identifier("c".into()),
+        TokenKind::NotEqTilde,
+        TokenKind::Identifier("d".into()),
+        TokenKind::EqEq,
+        TokenKind::Tilde,
+        TokenKind::Identifier("e".into()),
+        TokenKind::Eof,
+    ];
+    assert_eq!(kinds(&toks), expected);
+}
+
 #[test]
 fn dotdot_vs_dotdoteq_longest_match() {
     let toks = lex("0..10 0..=10");
@@ -334,6 +450,38 @@ fn punctuation_tokens() {
     );
 }
 
+#[test]
+fn identifiers_may_use_unicode_letters_and_digits() {
+    assert_eq!(
+        kinds(&lex("café größe 日本語 _ü x٣")),
+        vec![
+            TokenKind::Identifier("café".into()),
+            TokenKind::Identifier("größe".into()),
+            TokenKind::Identifier("日本語".into()),
+            TokenKind::Identifier("_ü".into()),
+            TokenKind::Identifier("x٣".into()),
+            TokenKind::Eof,
+        ]
+    );
+    // Columns count chars, so a token after a multi-byte name lines up.
+    let toks = lex("été = 1");
+    assert_eq!(toks[1].span.col, 5);
+}
+
+#[test]
+fn unicode_letter_after_a_number_is_a_malformed_literal() {
+    let err = lex_err("12é");
+    assert!(matches!(
+        err,
+        RavenError::Lex(LexError::InvalidNumber(ref s), _, _) if s == "12é"
+    ));
+    // A non-letter symbol is still an unexpected character, not a name.
+    assert!(matches!(
+        lex_err("€"),
+        RavenError::Lex(LexError::UnexpectedChar('€'), _, _)
+    ));
+}
+
 #[test]
 fn line_comments_are_stripped() {
     let toks = lex("let x // trailing comment\n= 1");
@@ -363,6 +511,25 @@ fn block_comments_are_stripped() {
     );
 }
 
+#[test]
+fn block_comments_nest() {
+    let expected = vec![
+        TokenKind::Identifier("a".into()),
+        TokenKind::Identifier("b".into()),
+        TokenKind::Eof,
+    ];
+    assert_eq!(
+        kinds(&lex("a /* outer /* inner */ still outer */ b")),
+        expected
+    );
+    assert_eq!(
+        kinds(&lex("a /* one /* two /* three */ two */ one */ b")),
+        expected
+    );
+    // Sibling inner comments each close their own level.
+    assert_eq!(kinds(&lex("a /* /* x */ /* y */ */ b")), expected);
+}
+
 #[test]
 fn multiple_newlines_coalesce_into_one_newline_token() {
     let toks = lex("a\n\n\nb\r\n\r\nc");
@@ -413,6 +580,37 @@ fn unexpected_char_reports_span() {
     }
 }
 
+fn unexpected_char_hint(src: &str) -> String {
+    match lex_err(src) {
+        RavenError::Lex(LexError::UnexpectedChar(_), _, Some(hint)) => hint,
+        other => panic!("expected a hinted UnexpectedChar, got {:?}", other),
+    }
+}
+
+#[test]
+fn unexpected_char_hints_name_the_ascii_spelling() {
+    assert!(unexpected_char_hint("let s = \u{201C}hi\u{201D}").contains("`\"`"));
+    assert!(unexpected_char_hint("let n = 5 \u{2013} 2").contains("`-`"));
+    assert!(unexpected_char_hint("let n = 5 \u{00D7} 2").contains("`*`"));
+    assert!(unexpected_char_hint("if a \u{2260} b {}").contains("`!=`"));
+    assert!(unexpected_char_hint("let x = #").contains("`//`"));
+    assert!(unexpected_char_hint("let\u{00A0}x = 1").contains("ordinary space"));
+    assert!(unexpected_char_hint("let x\u{200B} = 1").contains("delete it"));
+}
+
+#[test]
+fn full_width_punctuation_points_at_its_ascii_form() {
+    let hint = unexpected_char_hint("print\u{FF08}1)");
+    assert!(hint.contains("`\u{FF08}`"), "{hint}");
+    assert!(hint.contains("ASCII `(`"), "{hint}");
+}
+
+#[test]
+fn other_non_ascii_symbols_belong_in_strings() {
+    let hint = unexpected_char_hint("let price = 5\u{20AC}");
+    assert!(hint.contains("inside a string or comment"), "{hint}");
+}
+
 #[test]
 fn unterminated_string_is_error() {
     let err = lex_err("\"hello");
@@ -449,6 +647,20 @@ fn unterminated_block_comment_is_error() {
     ));
 }
 
+#[test]
+fn unterminated_nested_block_comment_reports_outer_start() {
+    // The inner `*/` closes only the inner comment, so the outer one is
+    // still open at end of input.
+    let err = lex_err("let x = 1\n/* outer /* inner */ still open");
+    match err {
+        RavenError::Lex(LexError::UnterminatedBlockComment, span, _) => {
+            assert_eq!(span.line, 2);
+            assert_eq!(span.col, 1);
+        }
+        other => panic!("expected UnterminatedBlockComment, got {:?}", other),
+    }
+}
+
 #[test]
 fn invalid_escape_is_error() {
     let err = lex_err(r#""bad \q escape""#);
@@ -458,6 +670,16 @@ fn invalid_escape_is_error() {
     ));
 }
 
+#[test]
+fn invalid_escape_hint_lists_the_escapes_and_the_literal_backslash() {
+    let RavenError::Lex(LexError::InvalidEscape('d'), _, Some(hint)) = lex_err(r#""\d+""#) else {
+        panic!("expected a hinted InvalidEscape");
+    };
+    assert!(hint.contains("`\\n`"), "{hint}");
+    assert!(hint.contains("`\\u{...}`"), "{hint}");
+    assert!(hint.contains("`\\\\d`"), "{hint}");
+}
+
 #[test]
 fn invalid_unicode_escape_short_hex() {
     let err = lex_err(r#""\xZZ""#);
@@ -494,6 +716,74 @@ fn hex_literal_without_digits_is_error() {
     ));
 }
 
+#[test]
+fn radix_literals_accept_mixed_case_digits_and_the_full_64_bits() {
+    let toks = lex("0xDeadBeef 0XFF 0xFFFF_FFFF_FFFF_FFFF 0b1 0O17");
+    assert_eq!(
+        kinds(&toks),
+        vec![
+            TokenKind::IntLit(0xDEAD_BEEF),
+            TokenKind::IntLit(0xFF),
+            TokenKind::IntLit(-1),
+            TokenKind::IntLit(1),
+            TokenKind::IntLit(0o17),
+            TokenKind::Eof,
+        ]
+    );
+}
+
+#[test]
+fn digit_outside_the_base_names_the_allowed_digits() {
+    for (src, digits) in [
+        ("0b102", "`0` and `1`"),
+        ("0o78", "`0` through `7`"),
+        ("0xFG", "`a` through `f`"),
+    ] {
+        match lex_err(src) {
+            RavenError::Lex(LexError::InvalidNumber(lexeme), _, Some(hint)) => {
+                assert_eq!(lexeme, src);
+                assert!(hint.contains(digits), "{hint}");
+            }
+            other => panic!("expected a hinted InvalidNumber for {src}, got {:?}", other),
+        }
+    }
+}
+
+#[test]
+fn radix_literal_past_64_bits_is_an_error() {
+    match lex_err("0x1_0000_0000_0000_0000") {
+        RavenError::Lex(LexError::InvalidNumber(_), _, Some(hint)) => {
+            assert!(hint.contains("64 bits"), "{hint}");
+        }
+        other => panic!("expected a hinted InvalidNumber, got {:?}", other),
+    }
+}
+
+#[test]
+fn float_exponent_scales_the_mantissa() {
+    let toks = lex("1e3 2.5e-2 1.5E10");
+    assert_eq!(
+        kinds(&toks),
+        vec![
+            TokenKind::FloatLit(1000.0),
+            TokenKind::FloatLit(0.025),
+            TokenKind::FloatLit(1.5e10),
+            TokenKind::Eof,
+        ]
+    );
+}
+
+#[test]
+fn float_literal_past_f64_range_is_an_error() {
+    match lex_err("1e400") {
+        RavenError::Lex(LexError::InvalidNumber(lexeme), _, Some(hint)) => {
+            assert_eq!(lexeme, "1e400");
+            assert!(hint.contains("1.8e308"), "{hint}");
+        }
+        other => panic!("expected a hinted InvalidNumber, got {:?}", other),
+    }
+}
+
 #[test]
 fn float_exponent_without_digits_is_error() {
     let err = lex_err("1e");
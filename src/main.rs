@@ -3,8 +3,17 @@
 //! Supports:
 //!   raven build <source.rv> [-o <output>]
 //!     Compile a single source file to a native executable.
+//!   raven build - [--filename <name>] [-o <output>]
+//!     Compile source read from stdin, naming it `<name>` in diagnostics.
+//!   raven check <source.rv | -> [--filename <name>]
+//!     Type-check without producing code. Exits 1 on any error and 0
+//!     otherwise, so it can gate a CI step; warnings are printed but leave
+//!     the status at 0.
 //!   raven help | --help | -h     Print usage.
 //!   raven --version | -V         Print the compiler version.
+//!   raven --version --verbose | -vV
+//!                                Also print the commit, rustc, and target,
+//!                                for bug reports.
 //!   raven                        Print usage.
 //!
 //! The `build` subcommand runs the entire v2 pipeline (lex, parse,
@@ -19,6 +28,7 @@ use std::ffi::{OsStr, OsString};
 use std::path::{Path, PathBuf};
 use std::process::ExitCode;
 
+use raven::codegen::CodegenOptions;
 use raven::driver::{self, DriverError};
 
 /// Stack size for the compiler worker thread.
@@ -59,33 +69,126 @@ fn run() -> ExitCode {
             print_usage();
             ExitCode::SUCCESS
         }
+        Some("-vV") => {
+            print_version(true);
+            ExitCode::SUCCESS
+        }
         Some("--version") | Some("-V") => {
-            print_version();
+            let verbose = match args.get(2).and_then(|a| a.to_str()) {
+                None => false,
+                Some("--verbose") | Some("-v") => true,
+                Some(other) => {
+                    eprintln!("raven: unexpected argument '{}' after --version", other);
+                    return ExitCode::from(2);
+                }
+            };
+            print_version(verbose);
             ExitCode::SUCCESS
         }
-        Some("build") => match run_build(&args[2..]) {
-            Ok(()) => ExitCode::SUCCESS,
-            // A rendered source diagnostic prints verbatim; it carries its own
-            // `error:` header, so the `raven:` prefix would only get in the way.
-            Err(BuildError::Driver(DriverError::Diagnostic(s))) => {
-                eprint!("{}", s);
-                ExitCode::from(1)
-            }
-            Err(e) => {
-                eprintln!("raven: {}", e);
-                ExitCode::from(1)
-            }
-        },
+        Some("build") => exit_code(run_build(&args[2..])),
+        Some("check") => exit_code(run_check(&args[2..])),
         _ => {
-            eprintln!("raven: unknown subcommand '{}'", first.to_string_lossy());
+            let first = first.to_string_lossy();
+            eprintln!("raven: unknown subcommand '{}'", first);
+            if let Some(hint) = unknown_subcommand_hint(&first) {
+                eprintln!("help: {}", hint);
+            }
             eprintln!("Run 'raven help' for usage.");
             ExitCode::from(2)
         }
     }
 }
 
-fn print_version() {
+/// The words `raven` accepts as its first argument, for "did you mean"
+/// suggestions.
+const SUBCOMMANDS: &[&str] = &["build", "check", "help", "--help", "--version"];
+
+/// The flags `raven build` accepts.
+const BUILD_FLAGS: &[&str] = &["-o", "--output", "--filename", "--profile"];
+
+/// The flags `raven check` accepts.
+const CHECK_FLAGS: &[&str] = &["--filename"];
+
+/// A hint for an unrecognized first argument. The 1.x CLI ran a file
+/// directly (`raven file.rv`, once `raven -f file.rv`), so those spellings
+/// point at `raven build`; anything else gets the nearest subcommand.
+fn unknown_subcommand_hint(arg: &str) -> Option<String> {
+    match arg {
+        "-f" | "--file" => {
+            Some("the `-f` flag was removed; compile a file with `raven build <file.rv>`".into())
+        }
+        "run" | "--run" | "-r" => Some(
+            "raven compiles rather than runs: `raven build <file.rv>` writes an executable to run"
+                .into(),
+        ),
+        _ if arg.ends_with(".rv") => Some(format!("to compile a file, use `raven build {}`", arg)),
+        _ => suggest_closest_flag(arg, SUBCOMMANDS).map(|s| format!("did you mean `{}`?", s)),
+    }
+}
+
+/// The candidate closest to `input` by edit distance, when it is close enough
+/// to be a plausible typo: at most one edit for words up to four characters,
+/// two for longer ones. A one- or two-character input such as `-x` is too
+/// short to guess at.
+fn suggest_closest_flag<'a>(input: &str, candidates: &[&'a str]) -> Option<&'a str> {
+    let limit = match input.chars().count() {
+        0..=2 => return None,
+        3 | 4 => 1,
+        _ => 2,
+    };
+    candidates
+        .iter()
+        .map(|c| (edit_distance(input, c), *c))
+        .filter(|(d, _)| *d <= limit)
+        .min_by_key(|(d, _)| *d)
+        .map(|(_, c)| c)
+}
+
+/// Levenshtein distance between two strings, counted in characters.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut cur = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let sub = prev[j] + usize::from(ca != *cb);
+            cur[j + 1] = sub.min(prev[j + 1] + 1).min(cur[j] + 1);
+        }
+        prev = cur;
+    }
+    prev[b.len()]
+}
+
+/// Report a `build` or `check` outcome: success exits 0, and any error is
+/// printed and exits 1.
+fn exit_code(result: Result<(), BuildError>) -> ExitCode {
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        // A rendered source diagnostic prints verbatim; it carries its own
+        // `error:` header, so the `raven:` prefix would only get in the way.
+        Err(BuildError::Driver(DriverError::Diagnostic(s))) => {
+            eprint!("{}", s);
+            ExitCode::from(1)
+        }
+        Err(e) => {
+            eprintln!("raven: {}", e);
+            ExitCode::from(1)
+        }
+    }
+}
+
+/// Print the compiler version. The verbose form adds the build facts a bug
+/// report needs, one `key: value` per line: the git commit, the rustc that
+/// built the compiler, the target it was built for, and the host it runs on
+/// (see `build.rs`).
+fn print_version(verbose: bool) {
     println!("raven {}", env!("CARGO_PKG_VERSION"));
+    if verbose {
+        println!("commit: {}", env!("RAVEN_BUILD_COMMIT"));
+        println!("rustc: {}", env!("RAVEN_BUILD_RUSTC"));
+        println!("target: {}", env!("RAVEN_BUILD_TARGET"));
+        println!("host: {}-{}", std::env::consts::ARCH, std::env::consts::OS);
+    }
 }
 
 fn print_usage() {
@@ -96,17 +199,37 @@ fn print_usage() {
     println!();
     println!("Commands:");
     println!("  build <file.rv> [-o <output>]   Compile a source file to a native executable");
+    println!("  build - [--filename <name>]     Compile source read from stdin");
+    println!("  build <file.rv> --profile       Print per-function call counts and times on exit");
+    println!("  check <file.rv | ->             Type-check without building; exit 1 on errors");
     println!("  help                            Print this message");
     println!();
     println!("Options:");
     println!("  -h, --help                      Print this message");
     println!("  -V, --version                   Print the compiler version");
+    println!("  -vV, --version --verbose        Also print the commit, rustc, and target");
     println!();
     println!("To manage packages, use the 'rvpm' command.");
 }
 
 fn run_build(rest: &[OsString]) -> Result<(), BuildError> {
     let opts = parse_build_args(rest)?;
+    if opts.stdin {
+        // Piped source has no file on disk. It is named in diagnostics by
+        // `--filename`, and relative imports resolve against that name.
+        let mut source = String::new();
+        std::io::Read::read_to_string(&mut std::io::stdin(), &mut source)
+            .map_err(|e| BuildError::Driver(DriverError::Io(format!("read stdin: {}", e))))?;
+        return driver::build_source(
+            &source,
+            &opts.input,
+            &opts.output,
+            None,
+            &raven::codegen::linker::NativeLink::default(),
+            &opts.codegen,
+        )
+        .map_err(BuildError::Driver);
+    }
     // Refuse to write the executable over the input source. The compiler reads
     // the source first and the linker writes the output last, so `-o` pointing
     // at the source would silently replace it with the binary; a typo there
@@ -120,7 +243,41 @@ fn run_build(rest: &[OsString]) -> Result<(), BuildError> {
     // The single-file `raven build` has no package context, so external
     // (`github.com/...`) imports stay deferred and surface as unresolved.
     // Package-aware builds go through `rvpm build`.
-    driver::build_binary(&opts.input, &opts.output, None).map_err(BuildError::Driver)
+    let source = std::fs::read_to_string(&opts.input).map_err(|e| {
+        BuildError::Driver(DriverError::Io(format!(
+            "read {}: {}",
+            opts.input.display(),
+            e
+        )))
+    })?;
+    driver::build_source(
+        &source,
+        &opts.input,
+        &opts.output,
+        None,
+        &raven::codegen::linker::NativeLink::default(),
+        &opts.codegen,
+    )
+    .map_err(BuildError::Driver)
+}
+
+fn run_check(rest: &[OsString]) -> Result<(), BuildError> {
+    let opts = parse_check_args(rest)?;
+    let source = if opts.stdin {
+        let mut source = String::new();
+        std::io::Read::read_to_string(&mut std::io::stdin(), &mut source)
+            .map_err(|e| BuildError::Driver(DriverError::Io(format!("read stdin: {}", e))))?;
+        source
+    } else {
+        std::fs::read_to_string(&opts.input).map_err(|e| {
+            BuildError::Driver(DriverError::Io(format!(
+                "read {}: {}",
+                opts.input.display(),
+                e
+            )))
+        })?
+    };
+    driver::check(&source, &opts.input, None).map_err(BuildError::Driver)
 }
 
 /// Whether two paths refer to the same file. Canonicalization resolves `.`,
@@ -135,10 +292,18 @@ fn same_file(input: &Path, output: &Path) -> bool {
 
 #[derive(Debug)]
 struct BuildOpts {
+    /// The source path, or for stdin input the name diagnostics report.
     input: PathBuf,
     output: PathBuf,
+    /// Whether the source is read from stdin (the input was `-`).
+    stdin: bool,
+    /// Back end switches: `--profile`.
+    codegen: CodegenOptions,
 }
 
+/// The name piped source carries in diagnostics when `--filename` is absent.
+const STDIN_FILENAME: &str = "<stdin>";
+
 /// Whether an argument is an option flag, i.e. begins with `-`. The leading
 /// byte is checked directly so the test works for arguments that are not valid
 /// UTF-8 (a non-UTF-8 source path is treated as a positional, not a flag).
@@ -147,8 +312,17 @@ fn is_flag(arg: &OsStr) -> bool {
 }
 
 fn parse_build_args(args: &[OsString]) -> Result<BuildOpts, BuildError> {
+    parse_source_args(args, BUILD_FLAGS)
+}
+
+/// Parse the source path and the flags `raven build` and `raven check` have
+/// in common. An unknown flag is answered with the nearest of `flags`, the
+/// ones the running subcommand accepts.
+fn parse_source_args(args: &[OsString], flags: &[&str]) -> Result<BuildOpts, BuildError> {
     let mut input: Option<PathBuf> = None;
     let mut output: Option<PathBuf> = None;
+    let mut filename: Option<PathBuf> = None;
+    let mut codegen = CodegenOptions::default();
     let mut i = 0;
     while i < args.len() {
         let a = &args[i];
@@ -160,11 +334,22 @@ fn parse_build_args(args: &[OsString]) -> Result<BuildOpts, BuildError> {
             // The path keeps its original bytes; a non-UTF-8 path then surfaces
             // as an ordinary "no such file" diagnostic from the driver.
             output = Some(PathBuf::from(&args[i]));
-        } else if is_flag(a) {
-            return Err(BuildError::Args(format!(
-                "unknown flag `{}`",
-                a.to_string_lossy()
-            )));
+        } else if a == "--filename" {
+            i += 1;
+            if i >= args.len() {
+                return Err(BuildError::Args(
+                    "expected a file name after --filename".into(),
+                ));
+            }
+            filename = Some(PathBuf::from(&args[i]));
+        } else if a == "--profile" {
+            codegen.profile = true;
+        } else if is_flag(a) && a != "-" {
+            let flag = a.to_string_lossy();
+            return Err(BuildError::Args(match suggest_closest_flag(&flag, flags) {
+                Some(s) => format!("unknown flag `{}`; did you mean `{}`?", flag, s),
+                None => format!("unknown flag `{}`", flag),
+            }));
         } else if input.is_none() {
             input = Some(PathBuf::from(a));
         } else {
@@ -176,8 +361,48 @@ fn parse_build_args(args: &[OsString]) -> Result<BuildOpts, BuildError> {
         i += 1;
     }
     let input = input.ok_or_else(|| BuildError::Args("missing input source file".into()))?;
+    if input.as_os_str() == "-" {
+        // Without `-o`, the binary is named after `--filename` when given.
+        let output = output
+            .unwrap_or_else(|| default_output_for(filename.as_deref().unwrap_or(Path::new("a"))));
+        let input = filename.unwrap_or_else(|| PathBuf::from(STDIN_FILENAME));
+        return Ok(BuildOpts {
+            input,
+            output,
+            stdin: true,
+            codegen,
+        });
+    }
+    if filename.is_some() {
+        return Err(BuildError::Args(
+            "--filename only applies when the source is read from stdin (`-`)".into(),
+        ));
+    }
     let output = output.unwrap_or_else(|| default_output_for(&input));
-    Ok(BuildOpts { input, output })
+    Ok(BuildOpts {
+        input,
+        output,
+        stdin: false,
+        codegen,
+    })
+}
+
+/// Parse `raven check` arguments: one source path (or `-` for stdin) and an
+/// optional `--filename`. There is no output, so the result reuses
+/// [`BuildOpts`] with an empty output path.
+fn parse_check_args(args: &[OsString]) -> Result<BuildOpts, BuildError> {
+    if let Some(a) = args
+        .iter()
+        .find(|a| *a == "-o" || *a == "--output" || *a == "--profile")
+    {
+        return Err(BuildError::Args(format!(
+            "`{}` does not apply to `raven check`, which produces no output",
+            a.to_string_lossy()
+        )));
+    }
+    let mut opts = parse_source_args(args, CHECK_FLAGS)?;
+    opts.output = PathBuf::new();
+    Ok(opts)
 }
 
 fn default_output_for(input: &Path) -> PathBuf {
@@ -231,6 +456,99 @@ mod tests {
         std::fs::remove_dir_all(&dir).ok();
     }
 
+    #[test]
+    fn dash_reads_stdin_under_the_given_filename() {
+        let args: Vec<OsString> = ["-", "--filename", "demo.rv"]
+            .iter()
+            .map(OsString::from)
+            .collect();
+        let opts = parse_build_args(&args).expect("stdin input parses");
+        assert!(opts.stdin);
+        assert_eq!(opts.input, PathBuf::from("demo.rv"));
+        assert_eq!(opts.output, default_output_for(Path::new("demo.rv")));
+
+        // Without --filename the source is reported as `<stdin>`.
+        let opts = parse_build_args(&[OsString::from("-")]).expect("stdin input parses");
+        assert_eq!(opts.input, PathBuf::from(STDIN_FILENAME));
+        assert_eq!(opts.output, default_output_for(Path::new("a")));
+    }
+
+    #[test]
+    fn filename_is_rejected_for_a_file_input() {
+        let args: Vec<OsString> = ["prog.rv", "--filename", "other.rv"]
+            .iter()
+            .map(OsString::from)
+            .collect();
+        assert!(parse_build_args(&args).is_err());
+    }
+
+    #[test]
+    fn check_takes_a_source_and_rejects_an_output() {
+        let opts = parse_check_args(&[OsString::from("prog.rv")]).expect("check args parse");
+        assert_eq!(opts.input, PathBuf::from("prog.rv"));
+        assert!(!opts.stdin);
+
+        let args: Vec<OsString> = ["prog.rv", "-o", "prog"]
+            .iter()
+            .map(OsString::from)
+            .collect();
+        assert!(parse_check_args(&args).is_err());
+        let args: Vec<OsString> = ["prog.rv", "--profile"]
+            .iter()
+            .map(OsString::from)
+            .collect();
+        assert!(parse_check_args(&args).is_err());
+    }
+
+    #[test]
+    fn profile_flag_turns_on_the_profile_hooks() {
+        let opts = parse_build_args(&[OsString::from("prog.rv")]).expect("args parse");
+        assert!(!opts.codegen.profile);
+        let args: Vec<OsString> = ["prog.rv", "--profile", "-o", "prog"]
+            .iter()
+            .map(OsString::from)
+            .collect();
+        let opts = parse_build_args(&args).expect("args parse");
+        assert!(opts.codegen.profile);
+        assert_eq!(opts.output, PathBuf::from("prog"));
+    }
+
+    #[test]
+    fn unknown_arguments_suggest_the_nearest_valid_one() {
+        assert_eq!(suggest_closest_flag("buld", SUBCOMMANDS), Some("build"));
+        assert_eq!(suggest_closest_flag("chek", SUBCOMMANDS), Some("check"));
+        assert_eq!(
+            suggest_closest_flag("--outptu", BUILD_FLAGS),
+            Some("--output")
+        );
+        assert_eq!(suggest_closest_flag("compile", SUBCOMMANDS), None);
+        assert_eq!(suggest_closest_flag("-x", BUILD_FLAGS), None);
+
+        // Spellings from the old run-a-file CLI point at `raven build`.
+        for old in ["-f", "--run", "prog.rv"] {
+            let hint = unknown_subcommand_hint(old).expect("hint");
+            assert!(hint.contains("raven build"), "{old}: {hint}");
+        }
+
+        let args: Vec<OsString> = ["prog.rv", "--outptu", "prog"]
+            .iter()
+            .map(OsString::from)
+            .collect();
+        let err = parse_build_args(&args).unwrap_err().to_string();
+        assert_eq!(err, "unknown flag `--outptu`; did you mean `--output`?");
+
+        // `raven check` has no output, so it suggests only its own flags.
+        let args: Vec<OsString> = ["prog.rv", "--outptu"].iter().map(OsString::from).collect();
+        let err = parse_check_args(&args).unwrap_err().to_string();
+        assert_eq!(err, "unknown flag `--outptu`");
+        let args: Vec<OsString> = ["prog.rv", "--filenme"]
+            .iter()
+            .map(OsString::from)
+            .collect();
+        let err = parse_check_args(&args).unwrap_err().to_string();
+        assert_eq!(err, "unknown flag `--filenme`; did you mean `--filename`?");
+    }
+
     // A non-UTF-8 source path must reach the build pipeline with its bytes
     // intact instead of panicking while the arguments are collected.
     #[cfg(unix)]
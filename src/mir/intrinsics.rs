@@ -21,6 +21,19 @@ pub const STR_EQ: &str = "__raven_str_eq";
 /// `< <= > >=` operators compare the result against `0`.
 pub const STR_CMP: &str = "__raven_str_cmp";
 
+/// Compare two heap `String` values ignoring ASCII case, yielding a
+/// `Bool`. Lowers to `raven_string_eq_ignore_case`. Backs `==~`; `!=~`
+/// negates the result.
+pub const STR_EQ_IGNORE_CASE: &str = "__raven_str_eq_ignore_case";
+
+/// Repeat a heap `String` an `Int` number of times into a new `String`.
+/// Lowers to `raven_string_repeat`. Backs `String * Int`.
+pub const STR_REPEAT: &str = "__raven_str_repeat";
+
+/// Read the `i`th character of a `String` as a `Char`, panicking when out
+/// of range. Lowers to `raven_string_char_at`. Backs `s[i]`.
+pub const STR_CHAR_AT: &str = "__raven_str_char_at";
+
 /// Render an `Int` as a heap `String`. Lowers to `raven_int_to_string`.
 pub const INT_TO_STRING: &str = "__raven_int_to_string";
 
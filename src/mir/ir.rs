@@ -49,6 +49,7 @@ pub enum MirBinOp {
 pub enum MirUnOp {
     Neg,
     Not,
+    BitNot,
     Ref,
 }
 
@@ -70,6 +71,9 @@ pub enum ListMethodOp {
     /// `get(self, i) -> T`: read the element at `i`. Panics when `i` is
     /// out of range.
     Get,
+    /// A new list holding the same elements, copied in one go. Not a
+    /// source method: the snapshot a `for` loop over a list iterates.
+    Copy,
 }
 
 /// A compile-time constant.
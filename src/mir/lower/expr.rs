@@ -10,6 +10,7 @@ use crate::hir::expr::{
     HirBinaryOp, HirBlock, HirExpr, HirExprKind, HirUnaryOp, InterpolPart, PtrBuiltinOp,
     ReflectBuiltinOp,
 };
+use crate::hir::LIST_COPY_METHOD;
 
 use super::super::ir::{
     ListMethodOp, MirBinOp, MirBlockId, MirConstant, MirFnRef, MirLocal, MirOperand, MirRvalue,
@@ -110,6 +111,21 @@ pub fn lower_expr(cx: &mut LowerCx<'_>, expr: &HirExpr) -> MirOperand {
             {
                 return lower_string_eq(cx, *op, lhs, rhs, ty);
             }
+            // `==~`/`!=~` are defined only on `String`.
+            if matches!(op, HirBinaryOp::EqIgnoreCase | HirBinaryOp::NeIgnoreCase) {
+                return lower_string_eq(cx, *op, lhs, rhs, ty);
+            }
+            // `String * Int` (either order) repeats the string.
+            if matches!(op, HirBinaryOp::Mul) {
+                let (lt, rt) = (mir_ty(&lhs.ty, cx.subst), mir_ty(&rhs.ty, cx.subst));
+                if lt == MirType::Str || rt == MirType::Str {
+                    return lower_string_repeat(cx, lhs, rhs, lt == MirType::Str, ty);
+                }
+            }
+            // `String + String` concatenates.
+            if matches!(op, HirBinaryOp::Add) && mir_ty(&lhs.ty, cx.subst) == MirType::Str {
+                return lower_string_concat(cx, lhs, rhs, ty);
+            }
             // Ordering on `String` compares contents lexicographically:
             // `raven_string_cmp` returns -1/0/1 and the operator compares
             // that against 0.
@@ -120,8 +136,17 @@ pub fn lower_expr(cx: &mut LowerCx<'_>, expr: &HirExpr) -> MirOperand {
             {
                 return lower_string_cmp(cx, *op, lhs, rhs, ty);
             }
-            let l = lower_expr(cx, lhs);
-            let r = lower_expr(cx, rhs);
+            let mut l = lower_expr(cx, lhs);
+            let mut r = lower_expr(cx, rhs);
+            // An ordering between an `Int` and a `Float` (the type checker
+            // allows no other mix) widens the `Int` operand so the compare
+            // runs on floats.
+            let (lt, rt) = (mir_ty(&lhs.ty, cx.subst), mir_ty(&rhs.ty, cx.subst));
+            if lt == MirType::Int && rt == MirType::Float {
+                l = cast_to_float(cx, l);
+            } else if lt == MirType::Float && rt == MirType::Int {
+                r = cast_to_float(cx, r);
+            }
             let dst = cx.builder.fresh_temp("bin", ty);
             cx.builder
                 .assign(cx.current, dst, MirRvalue::BinaryOp(map_binary(*op), l, r));
@@ -199,6 +224,11 @@ pub fn lower_expr(cx: &mut LowerCx<'_>, expr: &HirExpr) -> MirOperand {
             MirOperand::Copy(dst)
         }
         HirExprKind::Index { receiver, index } => {
+            // `s[i]` on a String reads the `i`th character through the
+            // runtime; the list path below assumes a list layout.
+            if mir_ty(&receiver.ty, cx.subst) == MirType::Str {
+                return lower_string_char_at(cx, receiver, index, ty);
+            }
             let base = lower_expr(cx, receiver);
             let idx = lower_expr(cx, index);
             let dst = cx.builder.fresh_temp("index", ty);
@@ -1038,8 +1068,121 @@ fn lower_while(cx: &mut LowerCx<'_>, cond: &HirExpr, body: &HirBlock, _ty: MirTy
     MirOperand::Const(MirConstant::Unit)
 }
 
+/// Lower `String * Int` into a call to the runtime repeat intrinsic.
+/// `str_first` says whether the `String` was written on the left. The
+/// operands are evaluated left to right as written, each into a temp so the
+/// string sits in a GC-rooted slot while the repeat allocates (the hazard
+/// `lower_string_concat` describes); only then are they put in the
+/// intrinsic's `(String, Int)` argument order.
+fn lower_string_repeat(
+    cx: &mut LowerCx<'_>,
+    lhs: &HirExpr,
+    rhs: &HirExpr,
+    str_first: bool,
+    ty: MirType,
+) -> MirOperand {
+    let mut parts = Vec::with_capacity(2);
+    for side in [lhs, rhs] {
+        let v = lower_expr(cx, side);
+        let part = cx
+            .builder
+            .fresh_temp("repeat_part", mir_ty(&side.ty, cx.subst));
+        cx.builder.assign(cx.current, part, MirRvalue::Use(v));
+        parts.push(MirOperand::Copy(part));
+    }
+    if !str_first {
+        parts.swap(0, 1);
+    }
+    let dst = cx.builder.fresh_temp("strrepeat", ty);
+    cx.builder.assign(
+        cx.current,
+        dst,
+        MirRvalue::Call {
+            callee: MirFnRef {
+                mangled: super::super::intrinsics::STR_REPEAT.into(),
+                origin: None,
+            },
+            args: parts,
+        },
+    );
+    MirOperand::Copy(dst)
+}
+
+/// Lower `String + String` into a runtime concatenation. Each operand is
+/// bound to a temp first so it sits in a GC-rooted slot: the concat
+/// allocates and can trigger a collection, which would otherwise free a
+/// literal operand codegen promoted to a heap `String` at the call site
+/// (the same hazard `lower_interpolate` guards against).
+fn lower_string_concat(
+    cx: &mut LowerCx<'_>,
+    lhs: &HirExpr,
+    rhs: &HirExpr,
+    ty: MirType,
+) -> MirOperand {
+    let mut args = Vec::with_capacity(2);
+    for side in [lhs, rhs] {
+        let v = lower_expr(cx, side);
+        let part = cx.builder.fresh_temp("concat_part", MirType::Str);
+        cx.builder.assign(cx.current, part, MirRvalue::Use(v));
+        args.push(MirOperand::Copy(part));
+    }
+    let dst = cx.builder.fresh_temp("concat", ty);
+    cx.builder.assign(
+        cx.current,
+        dst,
+        MirRvalue::Call {
+            callee: MirFnRef {
+                mangled: super::super::intrinsics::STR_CONCAT.into(),
+                origin: None,
+            },
+            args,
+        },
+    );
+    MirOperand::Copy(dst)
+}
+
+/// Lower `s[i]` on a `String` to a `raven_string_char_at` call, which
+/// counts characters and panics when `i` is out of range.
+fn lower_string_char_at(
+    cx: &mut LowerCx<'_>,
+    s: &HirExpr,
+    index: &HirExpr,
+    ty: MirType,
+) -> MirOperand {
+    let s = lower_expr(cx, s);
+    let i = lower_expr(cx, index);
+    let dst = cx.builder.fresh_temp("strchar", ty);
+    cx.builder.assign(
+        cx.current,
+        dst,
+        MirRvalue::Call {
+            callee: MirFnRef {
+                mangled: super::super::intrinsics::STR_CHAR_AT.into(),
+                origin: None,
+            },
+            args: vec![s, i],
+        },
+    );
+    MirOperand::Copy(dst)
+}
+
+/// Widen an `Int` operand to `Float` with a scalar cast.
+fn cast_to_float(cx: &mut LowerCx<'_>, operand: MirOperand) -> MirOperand {
+    let dst = cx.builder.fresh_temp("tofloat", MirType::Float);
+    cx.builder.assign(
+        cx.current,
+        dst,
+        MirRvalue::Cast {
+            operand,
+            target: MirType::Float,
+        },
+    );
+    MirOperand::Copy(dst)
+}
+
 /// Lower a `String` `==`/`!=` into a call to the runtime byte-equality
-/// intrinsic. `==` yields the call result directly; `!=` negates it.
+/// intrinsic, or `==~`/`!=~` into the ASCII case-folding one. `==` and
+/// `==~` yield the call result directly; `!=` and `!=~` negate it.
 fn lower_string_eq(
     cx: &mut LowerCx<'_>,
     op: HirBinaryOp,
@@ -1049,20 +1192,26 @@ fn lower_string_eq(
 ) -> MirOperand {
     let l = lower_expr(cx, lhs);
     let r = lower_expr(cx, rhs);
+    let intrinsic = match op {
+        HirBinaryOp::EqIgnoreCase | HirBinaryOp::NeIgnoreCase => {
+            super::super::intrinsics::STR_EQ_IGNORE_CASE
+        }
+        _ => super::super::intrinsics::STR_EQ,
+    };
     let eq = cx.builder.fresh_temp("streq", MirType::Bool);
     cx.builder.assign(
         cx.current,
         eq,
         MirRvalue::Call {
             callee: MirFnRef {
-                mangled: super::super::intrinsics::STR_EQ.into(),
+                mangled: intrinsic.into(),
                 origin: None,
             },
             args: vec![l, r],
         },
     );
     match op {
-        HirBinaryOp::Eq => MirOperand::Copy(eq),
+        HirBinaryOp::Eq | HirBinaryOp::EqIgnoreCase => MirOperand::Copy(eq),
         _ => {
             let dst = cx.builder.fresh_temp("strne", ty);
             cx.builder.assign(
@@ -1960,12 +2109,16 @@ fn map_binary(op: HirBinaryOp) -> MirBinOp {
         HirBinaryOp::BitXor => MirBinOp::BitXor,
         HirBinaryOp::Shl => MirBinOp::Shl,
         HirBinaryOp::Shr => MirBinOp::Shr,
+        HirBinaryOp::EqIgnoreCase | HirBinaryOp::NeIgnoreCase => {
+            unreachable!("`==~`/`!=~` lower to a runtime call, not a binary op")
+        }
     }
 }
 
 /// Map a built-in `List<T>` method name to its [`ListMethodOp`], or
 /// `None` when the name is not one of the recognized list methods. The
-/// set mirrors `tycheck::builtin::list_methods`.
+/// set mirrors `tycheck::builtin::list_methods`, plus the
+/// [`LIST_COPY_METHOD`] only HIR lowering writes.
 fn list_method_op(name: &str) -> Option<ListMethodOp> {
     Some(match name {
         "len" => ListMethodOp::Len,
@@ -1973,6 +2126,7 @@ fn list_method_op(name: &str) -> Option<ListMethodOp> {
         "push" => ListMethodOp::Push,
         "pop" => ListMethodOp::Pop,
         "get" => ListMethodOp::Get,
+        LIST_COPY_METHOD => ListMethodOp::Copy,
         _ => return None,
     })
 }
@@ -2008,6 +2162,7 @@ fn map_unary(op: HirUnaryOp) -> MirUnOp {
     match op {
         HirUnaryOp::Neg => MirUnOp::Neg,
         HirUnaryOp::Not => MirUnOp::Not,
+        HirUnaryOp::BitNot => MirUnOp::BitNot,
         HirUnaryOp::Ref => MirUnOp::Ref,
     }
 }
@@ -239,6 +239,7 @@ fn pretty_rvalue(buf: &mut String, r: &MirRvalue) {
                 crate::mir::ir::ListMethodOp::Push => "push",
                 crate::mir::ir::ListMethodOp::Pop => "pop",
                 crate::mir::ir::ListMethodOp::Get => "get",
+                crate::mir::ir::ListMethodOp::Copy => "__copy",
             };
             write!(buf, "(list.{} {} ", name, elem_ty).unwrap();
             pretty_operand(buf, receiver);
@@ -465,6 +466,7 @@ fn unop_name(op: MirUnOp) -> &'static str {
     match op {
         MirUnOp::Neg => "neg",
         MirUnOp::Not => "not",
+        MirUnOp::BitNot => "bitnot",
         MirUnOp::Ref => "ref",
     }
 }
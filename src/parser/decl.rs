@@ -1,25 +1,32 @@
 //! Top level declaration parsing.
 
+use std::collections::HashMap;
+
 use crate::ast::{
-    Const, Decl, DeclKind, Enum, EnumVariant, Extern, ExternFn, Function, FunctionBody,
-    GenericParam, Impl, Import, ImportSource, LetDecl, MacroDef, Param, Struct, StructField, Trait,
-    VariantPayload,
+    hoist_local_funs, local_type_name, rename_local_types, Const, Decl, DeclKind, Enum,
+    EnumVariant, Extern, ExternFn, Function, FunctionBody, GenericParam, Impl, Import,
+    ImportSource, LetDecl, MacroDef, Param, Struct, StructField, Trait, VariantPayload,
 };
 use crate::error::{ParseError, RavenError};
 use crate::lexer::TokenKind;
 
 use super::{merge_spans, ParseResult, Parser};
 
+/// Lint names `@allow(...)` accepts.
+const KNOWN_LINTS: &[&str] = &["dead_code", "infinite_loop"];
+
 impl Parser {
     /// Parse one top level declaration.
     pub(crate) fn parse_decl(&mut self) -> ParseResult<Decl> {
         // Leading attributes: `@derive(...)` attaches a derived trait list,
         // `@repr(C)` marks a struct for C memory layout. Both attach to the
-        // struct or enum that immediately follows.
+        // struct or enum that immediately follows. `@allow(...)` silences a
+        // lint on the function that follows.
         let mut derives = Vec::new();
         let mut repr_c = false;
+        let mut allows = Vec::new();
         while matches!(self.peek_kind(), TokenKind::At) {
-            self.parse_item_attr(&mut derives, &mut repr_c)?;
+            self.parse_item_attr(&mut derives, &mut repr_c, &mut allows)?;
             self.skip_separators();
         }
         // `macro name { ... }`. `macro` is a contextual identifier (not a
@@ -31,13 +38,14 @@ impl Parser {
             return self.parse_macro_def();
         }
         match self.peek_kind() {
-            TokenKind::Struct => self.parse_struct_decl(derives, repr_c),
-            TokenKind::Enum if !repr_c => self.parse_enum_decl(derives),
+            TokenKind::Struct if allows.is_empty() => self.parse_struct_decl(derives, repr_c),
+            TokenKind::Enum if !repr_c && allows.is_empty() => self.parse_enum_decl(derives),
             _ if repr_c => Err(self.unexpected("`struct` after `@repr(C)`")),
             _ if !derives.is_empty() => {
                 Err(self.unexpected("`struct` or `enum` after `@derive(...)`"))
             }
-            TokenKind::Fun => self.parse_function_decl(),
+            TokenKind::Fun => self.parse_function_decl(allows),
+            _ if !allows.is_empty() => Err(self.unexpected("`fun` after `@allow(...)`")),
             TokenKind::Trait => self.parse_trait_decl(),
             TokenKind::Impl => self.parse_impl_decl(),
             TokenKind::Extern => self.parse_extern_decl(),
@@ -49,9 +57,15 @@ impl Parser {
     }
 
     /// Parse one `@name(...)` item attribute. `@derive(Name, ...)` appends
-    /// trait names to `derives`; `@repr(C)` sets `repr_c`. The `@` is at the
-    /// cursor on entry. Any other attribute name is a parse error.
-    fn parse_item_attr(&mut self, derives: &mut Vec<String>, repr_c: &mut bool) -> ParseResult<()> {
+    /// trait names to `derives`; `@repr(C)` sets `repr_c`; `@allow(lint)`
+    /// appends lint names to `allows`. The `@` is at the cursor on entry. Any
+    /// other attribute name, or an unknown lint, is a parse error.
+    fn parse_item_attr(
+        &mut self,
+        derives: &mut Vec<String>,
+        repr_c: &mut bool,
+        allows: &mut Vec<String>,
+    ) -> ParseResult<()> {
         self.expect(&TokenKind::At, "`@`")?;
         let (name, name_span) = self.expect_ident("attribute name")?;
         match name.as_str() {
@@ -85,9 +99,35 @@ impl Parser {
                 *repr_c = true;
                 Ok(())
             }
+            "allow" => {
+                self.expect(&TokenKind::LParen, "`(`")?;
+                self.skip_newlines();
+                while !matches!(self.peek_kind(), TokenKind::RParen) {
+                    let (lint, lint_span) = self.expect_ident("lint name")?;
+                    if !KNOWN_LINTS.contains(&lint.as_str()) {
+                        let expected: Vec<String> =
+                            KNOWN_LINTS.iter().map(|l| format!("`{l}`")).collect();
+                        return Err(RavenError::parse(
+                            ParseError::Custom(format!(
+                                "unknown lint `{lint}` in `@allow`, expected {}",
+                                expected.join(" or ")
+                            )),
+                            lint_span,
+                        ));
+                    }
+                    allows.push(lint);
+                    self.skip_newlines();
+                    if !self.eat(&TokenKind::Comma) {
+                        break;
+                    }
+                    self.skip_newlines();
+                }
+                self.expect(&TokenKind::RParen, "`)`")?;
+                Ok(())
+            }
             other => Err(RavenError::parse(
                 ParseError::Custom(format!(
-                    "unknown attribute `@{other}`, expected `@derive` or `@repr`"
+                    "unknown attribute `@{other}`, expected `@derive`, `@repr`, or `@allow`"
                 )),
                 name_span,
             )),
@@ -112,8 +152,8 @@ impl Parser {
         })
     }
 
-    fn parse_function_decl(&mut self) -> ParseResult<Decl> {
-        let fun = self.parse_function(false)?;
+    fn parse_function_decl(&mut self, allows: Vec<String>) -> ParseResult<Decl> {
+        let fun = self.parse_allowed_function(allows)?;
         let span = fun.span.clone();
         Ok(Decl {
             kind: DeclKind::Function(fun),
@@ -121,6 +161,21 @@ impl Parser {
         })
     }
 
+    /// Parse a top level function or method with the lints in `allows`
+    /// silenced. A local function hoisted out of the body answers to the
+    /// same `@allow(...)` as the body it was written in.
+    fn parse_allowed_function(&mut self, allows: Vec<String>) -> ParseResult<Function> {
+        let lifted_from = self.lifted.len();
+        let mut fun = self.parse_function(false)?;
+        for decl in &mut self.lifted[lifted_from..] {
+            if let DeclKind::Function(f) = &mut decl.kind {
+                f.allows.clone_from(&allows);
+            }
+        }
+        fun.allows = allows;
+        Ok(fun)
+    }
+
     /// Parse `fun ...` producing a [`Function`]. When `allow_signature_only`
     /// is true, the function body may be absent (trait member without
     /// default).
@@ -138,7 +193,8 @@ impl Parser {
             None
         };
 
-        let (body, end_span) = match self.peek_kind() {
+        let lifted_from = self.lifted.len();
+        let (mut body, end_span) = match self.peek_kind() {
             TokenKind::LBrace => {
                 let block = self.parse_block()?;
                 let s = block.span.clone();
@@ -157,6 +213,13 @@ impl Parser {
             }
             _ => return Err(self.unexpected("function body")),
         };
+        if self.lifted.len() > lifted_from {
+            self.scope_local_types(&name, lifted_from, &params, &mut body)?;
+        }
+        if !self.keep_local_types {
+            let hoisted = hoist_local_funs(&name, &params, &generics, &mut body);
+            self.lifted.extend(hoisted);
+        }
 
         let span = merge_spans(&start, &end_span);
         Ok(Function {
@@ -165,10 +228,42 @@ impl Parser {
             params,
             ret,
             body,
+            allows: Vec::new(),
             span,
         })
     }
 
+    /// Give the types lifted out of function `func`'s body (`lifted[from..]`)
+    /// names scoped to that function, and point the body's uses, along with
+    /// the types' references to one another, at the new names.
+    fn scope_local_types(
+        &mut self,
+        func: &str,
+        from: usize,
+        params: &[Param],
+        body: &mut FunctionBody,
+    ) -> ParseResult<()> {
+        let mut rename = HashMap::new();
+        for decl in &self.lifted[from..] {
+            let name = match &decl.kind {
+                DeclKind::Struct(s) => &s.name,
+                DeclKind::Enum(e) => &e.name,
+                _ => continue,
+            };
+            let scoped = local_type_name(name, func, decl.span.line);
+            if rename.insert(name.clone(), scoped).is_some() {
+                return Err(RavenError::parse(
+                    ParseError::Custom(format!(
+                        "the type `{name}` is declared more than once in `{func}`"
+                    )),
+                    decl.span.clone(),
+                ));
+            }
+        }
+        rename_local_types(&mut self.lifted[from..], params, body, &rename);
+        Ok(())
+    }
+
     fn parse_generic_params_opt(&mut self) -> ParseResult<Vec<GenericParam>> {
         if !matches!(self.peek_kind(), TokenKind::Lt) {
             return Ok(Vec::new());
@@ -366,10 +461,19 @@ impl Parser {
         self.skip_separators();
         let mut items = Vec::new();
         while !matches!(self.peek_kind(), TokenKind::RBrace) {
+            // A method takes `@allow(...)` like a top level function; the
+            // type attributes have nothing to attach to here.
+            let mut allows = Vec::new();
+            while matches!(self.peek_kind(), TokenKind::At)
+                && matches!(self.peek_kind_at(1), TokenKind::Identifier(n) if n == "allow")
+            {
+                self.parse_item_attr(&mut Vec::new(), &mut false, &mut allows)?;
+                self.skip_separators();
+            }
             if !matches!(self.peek_kind(), TokenKind::Fun) {
                 return Err(self.unexpected("`fun` or `}`"));
             }
-            items.push(self.parse_function(false)?);
+            items.push(self.parse_allowed_function(allows)?);
             self.skip_separators();
         }
         let rb = self.expect(&TokenKind::RBrace, "`}`")?;
@@ -626,6 +730,13 @@ impl Parser {
                 }
                 Ok((ImportSource::Std(parts), span))
             }
+            // A bare module name (`import math`) is ambiguous between a
+            // standard library module and a local file, so both are spelled
+            // as paths.
+            TokenKind::Identifier(n) => Err(self.unexpected("import path").with_hint(format!(
+                "a standard library module is imported as `import std/{n}`, \
+                 and a local file by its quoted path, `import \"./{n}\"`"
+            ))),
             _ => Err(self.unexpected("import path")),
         }
     }
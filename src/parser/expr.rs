@@ -86,6 +86,8 @@ impl Parser {
         let op = match self.peek_kind() {
             TokenKind::EqEq => Some(BinaryOp::Eq),
             TokenKind::NotEq => Some(BinaryOp::Ne),
+            TokenKind::EqEqTilde => Some(BinaryOp::EqIgnoreCase),
+            TokenKind::NotEqTilde => Some(BinaryOp::NeIgnoreCase),
             TokenKind::Lt => Some(BinaryOp::Lt),
             TokenKind::Gt => Some(BinaryOp::Gt),
             TokenKind::LtEq => Some(BinaryOp::Le),
@@ -103,6 +105,8 @@ impl Parser {
             self.peek_kind(),
             TokenKind::EqEq
                 | TokenKind::NotEq
+                | TokenKind::EqEqTilde
+                | TokenKind::NotEqTilde
                 | TokenKind::Lt
                 | TokenKind::Gt
                 | TokenKind::LtEq
@@ -221,10 +225,24 @@ impl Parser {
 
     fn parse_unary(&mut self) -> ParseResult<Expr> {
         let start = self.peek().span.clone();
+        // A prefix `++i` is C's increment. (`--i` parses as a double
+        // negation, so only `++` can be caught here.)
+        if matches!(self.peek_kind(), TokenKind::Plus)
+            && matches!(self.peek_kind_at(1), TokenKind::Plus)
+            && start.end == self.peek_at(1).span.start
+        {
+            let span = merge_spans(&start, &self.peek_at(1).span);
+            self.advance();
+            self.advance();
+            let operand = self.parse_unary()?;
+            return Err(increment_operator_error("+", &operand, span));
+        }
         let op = match self.peek_kind() {
             TokenKind::Minus => Some(UnaryOp::Neg),
             TokenKind::Bang => Some(UnaryOp::Not),
+            TokenKind::Tilde => Some(UnaryOp::BitNot),
             TokenKind::Amp => Some(UnaryOp::Ref),
+            TokenKind::TypeOf => Some(UnaryOp::TypeOf),
             _ => None,
         };
         if let Some(op) = op {
@@ -352,6 +370,28 @@ impl Parser {
                         span,
                     };
                 }
+                // `cond ? a : b` is C's conditional. After `?` the try
+                // operator never takes an operand, so a name or literal
+                // right after it on the same line is that mistake.
+                TokenKind::Question
+                    if matches!(
+                        self.peek_kind_at(1),
+                        TokenKind::Identifier(_)
+                            | TokenKind::IntLit(_)
+                            | TokenKind::FloatLit(_)
+                            | TokenKind::StringLit(_)
+                            | TokenKind::BlockStringLit(_)
+                            | TokenKind::CharLit(_)
+                            | TokenKind::True
+                            | TokenKind::False
+                    ) =>
+                {
+                    return Err(RavenError::parse(
+                        ParseError::Custom("`cond ? a : b` is not a Raven expression".into()),
+                        self.peek().span.clone(),
+                    )
+                    .with_hint("`if` is an expression: write `if cond { a } else { b }`"));
+                }
                 TokenKind::Question => {
                     let q = self.advance();
                     let span = merge_spans(&expr.span, &q.span);
@@ -360,6 +400,68 @@ impl Parser {
                         span,
                     };
                 }
+                // `Color::Red` is the path syntax older Raven used for enum
+                // variants. Variants and associated functions are reached
+                // with `.` now, so name that instead of a bare "expected
+                // expression, found `::`".
+                TokenKind::ColonColon => {
+                    let ExprKind::Ident { name: ty, .. } = &expr.kind else {
+                        break;
+                    };
+                    let TokenKind::Identifier(member) = self.peek_kind_at(1) else {
+                        break;
+                    };
+                    let span = merge_spans(&expr.span, &self.peek_at(1).span);
+                    return Err(RavenError::parse(
+                        ParseError::Custom(format!("`{ty}::{member}` is not a Raven path")),
+                        span,
+                    )
+                    .with_hint(format!(
+                        "enum variants and associated functions use `.`: `{ty}.{member}`"
+                    )));
+                }
+                // `i++` and `i--` are C's increment and decrement. Raven has
+                // compound assignment instead; name it rather than failing on
+                // the second operator with "expected expression". `a--b` is
+                // still a subtraction of `-b`, so `--` only counts at the end
+                // of the expression.
+                TokenKind::Plus | TokenKind::Minus
+                    if self.peek_kind_at(1) == self.peek_kind()
+                        && self.peek().span.end == self.peek_at(1).span.start
+                        && (matches!(self.peek_kind(), TokenKind::Plus)
+                            || matches!(
+                                self.peek_kind_at(2),
+                                TokenKind::Newline
+                                    | TokenKind::Semi
+                                    | TokenKind::RBrace
+                                    | TokenKind::RParen
+                                    | TokenKind::Eof
+                            )) =>
+                {
+                    let op = if matches!(self.peek_kind(), TokenKind::Plus) {
+                        "+"
+                    } else {
+                        "-"
+                    };
+                    let span = merge_spans(&self.peek().span, &self.peek_at(1).span);
+                    return Err(increment_operator_error(op, &expr, span));
+                }
+                // `a ** b` is exponentiation in Python and JavaScript. Raven
+                // spells it as a `std/math` call, and `* *b` has no meaning
+                // (there is no dereference), so `**` is always this mistake.
+                TokenKind::Star
+                    if matches!(self.peek_kind_at(1), TokenKind::Star)
+                        && self.peek().span.end == self.peek_at(1).span.start =>
+                {
+                    let span = merge_spans(&self.peek().span, &self.peek_at(1).span);
+                    return Err(RavenError::parse(
+                        ParseError::Custom("`**` is not a Raven operator".into()),
+                        span,
+                    )
+                    .with_hint(
+                        "raise to a power with `pow_int(base, exp)` for an `Int` or `pow(base, exp)` for a `Float`, both from `std/math`",
+                    ));
+                }
                 _ => break,
             }
         }
@@ -539,11 +641,13 @@ impl Parser {
     fn parse_paren_or_tuple(&mut self) -> ParseResult<Expr> {
         let lparen = self.advance();
         self.skip_newlines();
-        // `()` is a unit value: we represent it as an empty tuple but
-        // reject it as UnsupportedTuple for now. Actually `()` does not
-        // appear in the spec as a value form, so reject.
+        // `()` is the unit value.
         if matches!(self.peek_kind(), TokenKind::RParen) {
-            return Err(RavenError::parse(ParseError::UnsupportedTuple, lparen.span));
+            let rp = self.advance();
+            return Ok(Expr {
+                kind: ExprKind::Unit,
+                span: merge_spans(&lparen.span, &rp.span),
+            });
         }
         let first = self.parse_expr()?;
         self.skip_newlines();
@@ -849,6 +953,13 @@ impl Parser {
     fn parse_lambda_fun(&mut self) -> ParseResult<Expr> {
         // `fun ( params ) [-> Type] body`
         let fun_tok = self.advance();
+        self.parse_lambda_fun_after_keyword(fun_tok.span)
+    }
+
+    /// The part of a `fun` lambda after its keyword: `( params ) [-> Type]
+    /// body`. `start` is the span the lambda begins at. A local function
+    /// declaration shares this once its name is consumed.
+    pub(crate) fn parse_lambda_fun_after_keyword(&mut self, start: Span) -> ParseResult<Expr> {
         self.expect(&TokenKind::LParen, "`(`")?;
         let mut params = Vec::new();
         if !matches!(self.peek_kind(), TokenKind::RParen) {
@@ -890,7 +1001,7 @@ impl Parser {
             let s = block.span.clone();
             (LambdaBody::Block(block), s)
         };
-        let span = merge_spans(&fun_tok.span, &body_span);
+        let span = merge_spans(&start, &body_span);
         Ok(Expr {
             kind: ExprKind::Lambda {
                 params,
@@ -1157,6 +1268,14 @@ impl Parser {
             } else {
                 None
             };
+            // `=>` is the arm arrow in Rust, Scala, and PHP; Raven's is `->`.
+            if matches!(self.peek_kind(), TokenKind::FatArrow) {
+                return Err(RavenError::parse(
+                    ParseError::Custom("match arms use `->`, not `=>`".to_string()),
+                    self.peek().span.clone(),
+                )
+                .with_hint("write the arm as `pattern -> value`"));
+            }
             self.expect(&TokenKind::Arrow, "`->`")?;
             self.skip_newlines();
             let body = self.parse_expr()?;
@@ -1200,11 +1319,19 @@ impl Parser {
         let w = self.advance();
         let cond = self.parse_expr_no_struct()?;
         let body = self.parse_block()?;
-        let span = merge_spans(&w.span, &body.span);
+        let mut span = merge_spans(&w.span, &body.span);
+        let else_block = if self.eat_else() {
+            let block = self.parse_block()?;
+            span = merge_spans(&span, &block.span);
+            Some(block)
+        } else {
+            None
+        };
         Ok(Expr {
             kind: ExprKind::While {
                 cond: Box::new(cond),
                 body,
+                else_block,
             },
             span,
         })
@@ -1212,6 +1339,50 @@ impl Parser {
 
     fn parse_for(&mut self) -> ParseResult<Expr> {
         let f = self.advance();
+        // `for (let i = 0; i < n; i = i + 1)` is the C-style loop older
+        // Raven accepted. There is only `for pattern in iter` now, so name
+        // the replacements instead of failing on `let` as a pattern.
+        let at = usize::from(matches!(self.peek_kind(), TokenKind::LParen));
+        let c_style = match self.peek_kind_at(at) {
+            TokenKind::Let => true,
+            TokenKind::Identifier(_) => matches!(self.peek_kind_at(at + 1), TokenKind::Eq),
+            _ => false,
+        };
+        if c_style {
+            let span = merge_spans(&f.span, &self.peek_at(at).span);
+            // Skip a parenthesized header and its body whole, so recovery
+            // resumes after the loop rather than reporting the header's
+            // `;`-separated parts.
+            if at == 1 {
+                let mut depth = 0usize;
+                loop {
+                    match self.peek_kind() {
+                        TokenKind::LParen => depth += 1,
+                        TokenKind::RParen => {
+                            depth -= 1;
+                            if depth == 0 {
+                                self.advance();
+                                break;
+                            }
+                        }
+                        TokenKind::Eof => break,
+                        _ => {}
+                    }
+                    self.advance();
+                }
+                if matches!(self.peek_kind(), TokenKind::LBrace) {
+                    self.parse_block()?;
+                }
+            }
+            return Err(RavenError::parse(
+                ParseError::Custom("C-style `for` loops are not supported".to_string()),
+                span,
+            )
+            .with_hint(
+                "count with a range, `for i in 0..n { ... }`, or use a `while` loop \
+                 with the step as the last statement of its body",
+            ));
+        }
         let pattern = self.parse_pattern()?;
         self.expect(&TokenKind::In, "`in`")?;
         let iter = self.parse_expr_no_struct()?;
@@ -1271,6 +1442,17 @@ impl Parser {
             } else {
                 self.parse_stmt()?
             };
+            if let StmtKind::TypeDecl(decl) = stmt.kind {
+                if self.keep_local_types {
+                    stmts.push(Stmt {
+                        kind: StmtKind::TypeDecl(decl),
+                        span: stmt.span,
+                    });
+                } else {
+                    self.lifted.push(*decl);
+                }
+                continue;
+            }
             // Detect trailing expression: a bare expression statement
             // followed by only newlines (not semicolons) before the
             // closing `}` becomes the block's value. A `;` terminates
@@ -1331,6 +1513,8 @@ impl Parser {
                 | TokenKind::Percent
                 | TokenKind::EqEq
                 | TokenKind::NotEq
+                | TokenKind::EqEqTilde
+                | TokenKind::NotEqTilde
                 | TokenKind::Lt
                 | TokenKind::Gt
                 | TokenKind::LtEq
@@ -1485,19 +1669,15 @@ fn split_interpolation(
 /// every fragment's spans occupy a private `(file, byte-range)` keyspace
 /// that cannot collide with real source spans. Parse errors are
 /// re-anchored to the literal's span so the diagnostic points the reader
-/// at the offending string.
+/// at the offending string; later passes' errors reach the same literal
+/// through [`Span::host`] when rendered.
 fn parse_interpolation_snippet(
     snippet: &str,
     span: &Span,
     frag_index: usize,
     macros: &crate::macros::MacroTable,
 ) -> ParseResult<(Expr, crate::macros::DefSites)> {
-    let synthetic = std::path::PathBuf::from(format!(
-        "{}<interp:{}:{}>",
-        span.file.display(),
-        span.start,
-        frag_index
-    ));
+    let synthetic = Span::interpolation_file(span, frag_index);
     let tokens = Lexer::new(snippet.to_string(), synthetic)
         .tokenize()
         .map_err(|e| reanchor(e, span))?;
@@ -1561,3 +1741,17 @@ fn reanchor(err: RavenError, span: &Span) -> RavenError {
         }
     }
 }
+
+/// The error for C's `++`/`--` (`op` is `+` or `-`) applied to `target`,
+/// pointing at the compound assignment Raven spells it as.
+fn increment_operator_error(op: &str, target: &Expr, span: Span) -> RavenError {
+    let target = match &target.kind {
+        ExprKind::Ident { name, .. } => name.as_str(),
+        _ => "x",
+    };
+    RavenError::parse(
+        ParseError::Custom(format!("`{op}{op}` is not a Raven operator")),
+        span,
+    )
+    .with_hint(format!("write `{target} {op}= 1`"))
+}
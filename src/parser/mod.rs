@@ -73,6 +73,13 @@ pub struct Parser {
     /// `parse_with_macros`/`parse_with_macros_all` so the resolver resolves them
     /// at the macro's module scope, not against a call-site local.
     def_sites: crate::macros::DefSites,
+    /// When set (by [`parse_keeping_local_types`]), a `struct` or `enum`
+    /// declared inside a function body stays in place as a
+    /// `StmtKind::TypeDecl` statement. Otherwise it is moved to `lifted`.
+    keep_local_types: bool,
+    /// Local type declarations taken out of function bodies, appended to the
+    /// file's top-level items once the whole file has parsed.
+    lifted: Vec<crate::ast::Decl>,
 }
 
 impl Parser {
@@ -90,6 +97,8 @@ impl Parser {
             errors: Vec::new(),
             token_edits: Vec::new(),
             def_sites: crate::macros::DefSites::new(),
+            keep_local_types: false,
+            lifted: Vec::new(),
         }
     }
 
@@ -107,6 +116,8 @@ impl Parser {
             errors: Vec::new(),
             token_edits: Vec::new(),
             def_sites: crate::macros::DefSites::new(),
+            keep_local_types: false,
+            lifted: Vec::new(),
         }
     }
 
@@ -122,6 +133,7 @@ impl Parser {
             // Trailing separators are fine.
             self.skip_separators();
         }
+        items.append(&mut self.lifted);
         let end_span = self.peek().span.clone();
         Ok(File {
             items,
@@ -156,6 +168,7 @@ impl Parser {
         let mut errors = std::mem::take(&mut self.errors);
         dedup_parse_errors(&mut errors);
         if errors.is_empty() {
+            items.append(&mut self.lifted);
             let end_span = self.peek().span.clone();
             Ok(File {
                 items,
@@ -356,11 +369,23 @@ impl Parser {
 }
 
 /// Parse a complete source file from its token slice.
+///
+/// A `struct` or `enum` declared inside a function body is lifted to a
+/// top-level item, so every later stage sees an ordinary type declaration.
 pub fn parse(tokens: &[Token]) -> ParseResult<File> {
     let mut p = Parser::new(tokens);
     p.parse_file()
 }
 
+/// Parse like [`parse`], but keep each local `struct` or `enum` where it was
+/// written, as a `StmtKind::TypeDecl` statement. The formatter uses this so
+/// it reprints the declaration inside its function.
+pub fn parse_keeping_local_types(tokens: &[Token]) -> ParseResult<File> {
+    let mut p = Parser::new(tokens);
+    p.keep_local_types = true;
+    p.parse_file()
+}
+
 /// Parse a complete source file, carrying the file's macro definitions so a
 /// macro call inside a `"${...}"` interpolation fragment expands.
 pub fn parse_with_macros(
@@ -457,6 +482,7 @@ pub(crate) fn describe_token(kind: &TokenKind) -> String {
         TokenKind::Extern => "`extern`".to_string(),
         TokenKind::Defer => "`defer`".to_string(),
         TokenKind::Spawn => "`spawn`".to_string(),
+        TokenKind::TypeOf => "`typeof`".to_string(),
         TokenKind::True => "`true`".to_string(),
         TokenKind::False => "`false`".to_string(),
         TokenKind::SelfLower => "`self`".to_string(),
@@ -473,6 +499,8 @@ pub(crate) fn describe_token(kind: &TokenKind) -> String {
         TokenKind::PercentEq => "`%=`".to_string(),
         TokenKind::EqEq => "`==`".to_string(),
         TokenKind::NotEq => "`!=`".to_string(),
+        TokenKind::EqEqTilde => "`==~`".to_string(),
+        TokenKind::NotEqTilde => "`!=~`".to_string(),
         TokenKind::Lt => "`<`".to_string(),
         TokenKind::Gt => "`>`".to_string(),
         TokenKind::LtEq => "`<=`".to_string(),
@@ -1,7 +1,7 @@
 //! Pattern parsing for `match` arms and `for` heads.
 
 use crate::ast::{FieldPattern, LiteralPattern, Pattern, PatternKind};
-use crate::error::ParseError;
+use crate::error::{ParseError, RavenError};
 use crate::lexer::{TokenKind, ESCAPED_DOLLAR_SENTINEL};
 
 use super::{merge_spans, ParseResult, Parser};
@@ -156,6 +156,27 @@ impl Parser {
             }
             TokenKind::Identifier(_) => {
                 let (name, name_span) = self.expect_ident("pattern name")?;
+                // `Color.Red` (or `Color::Red`) spells the variant the way an
+                // expression constructs it. A pattern names the variant alone and
+                // the scrutinee's type picks the enum, so say that instead of
+                // failing on the `.` with "expected `->`".
+                if matches!(self.peek_kind(), TokenKind::Dot | TokenKind::ColonColon) {
+                    if let TokenKind::Identifier(variant) = self.peek_kind_at(1) {
+                        let sep = if matches!(self.peek_kind(), TokenKind::Dot) {
+                            "."
+                        } else {
+                            "::"
+                        };
+                        let span = merge_spans(&name_span, &self.peek_at(1).span);
+                        return Err(RavenError::parse(
+                            ParseError::Custom(format!("`{name}{sep}{variant}` is not a pattern")),
+                            span,
+                        )
+                        .with_hint(format!(
+                            "a pattern names the variant alone: `{variant}`; the matched value's type picks the enum"
+                        )));
+                    }
+                }
                 // `Name(...)` enum tuple variant.
                 if matches!(self.peek_kind(), TokenKind::LParen) {
                     self.advance(); // (
@@ -1,6 +1,6 @@
 //! Statement parsing inside block bodies.
 
-use crate::ast::{AssignOp, Expr, ExprKind, Stmt, StmtKind};
+use crate::ast::{AssignOp, DeclKind, Expr, ExprKind, Stmt, StmtKind};
 use crate::error::{ParseError, RavenError};
 use crate::lexer::TokenKind;
 
@@ -13,6 +13,10 @@ impl Parser {
         match self.peek_kind() {
             TokenKind::Let => self.parse_let_stmt(true),
             TokenKind::Const => self.parse_let_stmt(false),
+            TokenKind::Fun if matches!(self.peek_kind_at(1), TokenKind::Identifier(_)) => {
+                self.parse_local_fun()
+            }
+            TokenKind::Struct | TokenKind::Enum | TokenKind::At => self.parse_local_type(),
             TokenKind::Return => {
                 self.advance();
                 if matches!(
@@ -78,6 +82,49 @@ impl Parser {
         }
     }
 
+    /// Parse a local function declaration `fun name(params) -> T body`. After
+    /// the name it is exactly a `fun` lambda, so the rest is parsed as one.
+    fn parse_local_fun(&mut self) -> ParseResult<Stmt> {
+        let start = self.advance().span;
+        let (name, _name_span) = self.expect_ident("function name")?;
+        if matches!(self.peek_kind(), TokenKind::Lt) {
+            return Err(RavenError::parse(
+                ParseError::UnexpectedToken {
+                    expected: "`(`; a local function cannot be generic, declare it at top level"
+                        .to_string(),
+                    found: super::describe_token(self.peek_kind()),
+                },
+                self.peek().span.clone(),
+            ));
+        }
+        let func = self.parse_lambda_fun_after_keyword(start.clone())?;
+        let span = merge_spans(&start, &func.span);
+        Ok(Stmt {
+            kind: StmtKind::Fun { name, func },
+            span,
+        })
+    }
+
+    /// Parse a `struct` or `enum` declared inside a function body, with any
+    /// leading `@derive`/`@repr` attributes. The block parser lifts it to the
+    /// top level unless the formatter asked to keep it in place.
+    fn parse_local_type(&mut self) -> ParseResult<Stmt> {
+        let decl = self.parse_decl()?;
+        if !matches!(decl.kind, DeclKind::Struct(_) | DeclKind::Enum(_)) {
+            return Err(RavenError::parse(
+                ParseError::Custom(
+                    "only a `struct` or `enum` can be declared inside a function body".to_string(),
+                ),
+                decl.span,
+            ));
+        }
+        let span = decl.span.clone();
+        Ok(Stmt {
+            kind: StmtKind::TypeDecl(Box::new(decl)),
+            span,
+        })
+    }
+
     /// Parse a `let` (`mutable = true`) or `const` (`mutable = false`) local
     /// binding statement. Both require an initializer inside a function body.
     fn parse_let_stmt(&mut self, mutable: bool) -> ParseResult<Stmt> {
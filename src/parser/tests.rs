@@ -7,7 +7,7 @@
 
 use crate::ast::{
     BinaryOp, DeclKind, ExprKind, FunctionBody, ImportSource, LiteralPattern, PatternKind,
-    StmtKind, TypeKind, UnaryOp, VariantPayload,
+    StmtKind, StrFragment, TypeKind, UnaryOp, VariantPayload,
 };
 use crate::error::{ParseError, RavenError};
 use crate::lexer::Lexer;
@@ -110,6 +110,111 @@ fn parses_unary_negation_and_not() {
     assert_eq!(*op2, UnaryOp::Not);
 }
 
+#[test]
+fn tilde_is_a_prefix_bitwise_complement() {
+    let f = parse_ok("let x = ~a & b\n");
+    let DeclKind::Let(d) = &f.items[0].kind else {
+        panic!()
+    };
+    let ExprKind::Binary { op, lhs, .. } = &d.init.as_ref().unwrap().kind else {
+        panic!();
+    };
+    assert_eq!(*op, BinaryOp::BitAnd);
+    assert!(matches!(
+        &lhs.kind,
+        ExprKind::Unary {
+            op: UnaryOp::BitNot,
+            ..
+        }
+    ));
+}
+
+#[test]
+fn eq_tilde_is_one_token_unless_spaced() {
+    // `==~` lexes as one token wherever it appears, so `a ==~b` ignores case
+    // like `a ==~ b`. Only a space after `==` makes `~` a complement.
+    let f = parse_ok("let x = a ==~b\nlet y = a == ~b\n");
+    let DeclKind::Let(d) = &f.items[0].kind else {
+        panic!()
+    };
+    let ExprKind::Binary { op, rhs, .. } = &d.init.as_ref().unwrap().kind else {
+        panic!();
+    };
+    assert_eq!(*op, BinaryOp::EqIgnoreCase);
+    assert!(matches!(&rhs.kind, ExprKind::Ident { name, .. } if name == "b"));
+    let DeclKind::Let(d) = &f.items[1].kind else {
+        panic!()
+    };
+    let ExprKind::Binary { op, rhs, .. } = &d.init.as_ref().unwrap().kind else {
+        panic!();
+    };
+    assert_eq!(*op, BinaryOp::Eq);
+    assert!(matches!(
+        &rhs.kind,
+        ExprKind::Unary {
+            op: UnaryOp::BitNot,
+            ..
+        }
+    ));
+}
+
+#[test]
+fn bang_is_only_a_prefix_operator() {
+    // `!` negates the operand that follows it. After a complete operand it is
+    // never a binary operator, so `a ! b` is rejected rather than combined.
+    let f = parse_ok("let x = a && !b\n");
+    let DeclKind::Let(d) = &f.items[0].kind else {
+        panic!()
+    };
+    let ExprKind::Binary { op, rhs, .. } = &d.init.as_ref().unwrap().kind else {
+        panic!();
+    };
+    assert_eq!(*op, BinaryOp::And);
+    assert!(matches!(
+        rhs.kind,
+        ExprKind::Unary {
+            op: UnaryOp::Not,
+            ..
+        }
+    ));
+    parse_err("fun f(a: Bool, b: Bool) -> Bool {\n    a ! b\n}\n");
+}
+
+#[test]
+fn typeof_binds_tighter_than_comparison() {
+    let f = parse_ok("let t = typeof p.x == \"Int\"\n");
+    let DeclKind::Let(d) = &f.items[0].kind else {
+        panic!()
+    };
+    let ExprKind::Binary { lhs, .. } = &d.init.as_ref().unwrap().kind else {
+        panic!("expected `==` at the top");
+    };
+    let ExprKind::Unary { op, operand } = &lhs.kind else {
+        panic!("expected `typeof` on the left of `==`");
+    };
+    assert_eq!(*op, UnaryOp::TypeOf);
+    assert!(matches!(operand.kind, ExprKind::Field { .. }));
+}
+
+#[test]
+fn not_binds_tighter_than_comparison() {
+    // `!x == y` is `(!x) == y`, not `!(x == y)`.
+    let f = parse_ok("let b = !x == y\n");
+    let DeclKind::Let(d) = &f.items[0].kind else {
+        panic!()
+    };
+    let ExprKind::Binary { op, lhs, rhs } = &d.init.as_ref().unwrap().kind else {
+        panic!("expected `==` at the top");
+    };
+    assert_eq!(*op, BinaryOp::Eq);
+    let ExprKind::Unary { op, operand } = &lhs.kind else {
+        panic!("expected `!` on the left of `==`");
+    };
+    assert_eq!(*op, UnaryOp::Not);
+    assert!(matches!(&operand.kind, ExprKind::Ident { name, .. } if name == "x"));
+    assert!(matches!(&rhs.kind, ExprKind::Ident { name, .. } if name == "y"));
+}
+
 #[test]
 fn chained_comparison_is_rejected() {
     let err = parse_err("let x = a < b < c\n");
@@ -179,6 +284,51 @@ fn parses_array_literal() {
     assert_eq!(items.len(), 3);
 }
 
+#[test]
+fn interpolation_parses_method_calls_with_braces_in_arguments() {
+    let f = parse_ok(
+        "let s = \"[${arr.join(\", \")}] ${wrap(\"{}\", (1 + 2) * 3)} ${o.method(\"}\").v}\"\n",
+    );
+    let DeclKind::Let(d) = &f.items[0].kind else {
+        panic!()
+    };
+    let ExprKind::InterpolatedString(fragments) = &d.init.as_ref().unwrap().kind else {
+        panic!("expected InterpolatedString, got {:?}", d.init);
+    };
+    let exprs: Vec<&ExprKind> = fragments
+        .iter()
+        .filter_map(|f| match f {
+            StrFragment::Expr(e) => Some(&e.kind),
+            StrFragment::Literal(_) => None,
+        })
+        .collect();
+    assert_eq!(exprs.len(), 3);
+    let ExprKind::MethodCall { name, args, .. } = exprs[0] else {
+        panic!("expected MethodCall, got {:?}", exprs[0]);
+    };
+    assert_eq!(name, "join");
+    assert!(matches!(&args[0].kind, ExprKind::Str(s) if s == ", "));
+    let ExprKind::Call { args, .. } = exprs[1] else {
+        panic!("expected Call, got {:?}", exprs[1]);
+    };
+    assert!(matches!(&args[0].kind, ExprKind::Str(s) if s == "{}"));
+    assert!(matches!(
+        &args[1].kind,
+        ExprKind::Binary {
+            op: BinaryOp::Mul,
+            ..
+        }
+    ));
+    let ExprKind::Field { receiver, name } = exprs[2] else {
+        panic!("expected Field, got {:?}", exprs[2]);
+    };
+    assert_eq!(name, "v");
+    let ExprKind::MethodCall { args, .. } = &receiver.kind else {
+        panic!("expected MethodCall receiver");
+    };
+    assert!(matches!(&args[0].kind, ExprKind::Str(s) if s == "}"));
+}
+
 // ----- set and map literals -----
 
 /// Extract the initializer expression of the first top-level `let`.
@@ -293,6 +443,15 @@ fn tuple_expr_is_unsupported() {
     );
 }
 
+#[test]
+fn empty_parens_are_the_unit_value() {
+    let f = parse_ok("let a = ()\n");
+    let DeclKind::Let(d) = &f.items[0].kind else {
+        panic!()
+    };
+    assert!(matches!(d.init.as_ref().unwrap().kind, ExprKind::Unit));
+}
+
 #[test]
 fn parses_if_expr_with_else() {
     let f = parse_ok("let x = if a { 1 } else { 2 }\n");
@@ -361,6 +520,46 @@ fn parses_while_and_for() {
     assert_eq!(f.items.len(), 1);
 }
 
+#[test]
+fn c_style_for_points_at_ranges_and_while() {
+    for src in [
+        "fun f() {\n    for (let i = 0; i < 3; i = i + 1) {\n        g(i)\n    }\n}\n",
+        "fun f() {\n    for (i = 0; i < 3; tick(i)) {}\n}\n",
+        "fun f() {\n    for let i = 0; i < 3; i += 1 {}\n}\n",
+    ] {
+        let err = parse_err(src);
+        let RavenError::Parse(ParseError::Custom(msg), _, Some(hint)) = &err else {
+            panic!("expected a custom parse error with a hint, got {:?}", err);
+        };
+        assert_eq!(msg, "C-style `for` loops are not supported");
+        assert!(hint.contains("for i in 0..n"), "{hint}");
+    }
+    // The whole loop is skipped, so recovery reports nothing else.
+    let errors = parse_all_errors(
+        "fun f() {\n    for (let i = 0; i < 3; i = i + 1) {\n        g(i)\n    }\n}\n",
+    );
+    assert_eq!(errors.len(), 1, "{errors:?}");
+    // A parenthesized `for ... in` pattern is not mistaken for one.
+    parse_ok("fun f() {\n    for (x) in xs {}\n}\n");
+}
+
+#[test]
+fn parses_while_else() {
+    let f = parse_ok(
+        "fun f() {\n    while a {\n        g()\n    }\n    else {\n        h()\n    }\n}\n",
+    );
+    let DeclKind::Function(fun) = &f.items[0].kind else {
+        panic!()
+    };
+    let FunctionBody::Block(b) = &fun.body else {
+        panic!()
+    };
+    let ExprKind::While { else_block, .. } = &b.trailing.as_ref().unwrap().kind else {
+        panic!("expected a while, got {:?}", b)
+    };
+    assert!(else_block.is_some());
+}
+
 #[test]
 fn parses_lambda_full_form() {
     let f = parse_ok("let f = fun(x: Int) -> Int { x + 1 }\n");
@@ -490,6 +689,112 @@ fn compound_assignment() {
     assert!(matches!(b.stmts[1].kind, StmtKind::Assign { .. }));
 }
 
+#[test]
+fn local_function_declaration() {
+    let f = parse_ok("fun f(k: Int) {\n    fun helper(x: Int) -> Int = x + k\n    helper(1)\n}\n");
+    let DeclKind::Function(fun) = &f.items[0].kind else {
+        panic!()
+    };
+    let FunctionBody::Block(b) = &fun.body else {
+        panic!()
+    };
+    let StmtKind::Fun { name, func } = &b.stmts[0].kind else {
+        panic!("expected a local fun, got {:?}", b.stmts[0].kind);
+    };
+    assert_eq!(name, "helper");
+    assert!(matches!(func.kind, ExprKind::Lambda { .. }));
+    // A generic local function is rejected: it would need monomorphizing.
+    parse_err("fun f() {\n    fun id<T>(x: T) -> T = x\n}\n");
+}
+
+#[test]
+fn a_local_function_that_captures_nothing_is_hoisted() {
+    let f = parse_ok(
+        "fun f(k: Int) {\n    print(twice(k))\n    fun twice(x: Int) -> Int = x + x\n    fun shift(x: Int) -> Int = x + k\n}\n",
+    );
+    assert_eq!(f.items.len(), 2);
+    let DeclKind::Function(fun) = &f.items[0].kind else {
+        panic!()
+    };
+    let FunctionBody::Block(b) = &fun.body else {
+        panic!()
+    };
+    // `shift` reads the parameter `k`, so it stays a closure in the body.
+    assert_eq!(b.stmts.len(), 2);
+    assert!(matches!(&b.stmts[1].kind, StmtKind::Fun { name, .. } if name == "shift"));
+    assert!(matches!(&f.items[1].kind, DeclKind::Function(h) if h.name == "twice__in_f_3"));
+    let StmtKind::Expr(call) = &b.stmts[0].kind else {
+        panic!()
+    };
+    let ExprKind::Call { args, .. } = &call.kind else {
+        panic!()
+    };
+    assert!(matches!(&args[0].kind, ExprKind::Call { callee, .. }
+        if matches!(&callee.kind, ExprKind::Ident { name, .. } if name == "twice__in_f_3")));
+}
+
+#[test]
+fn local_struct_and_enum_are_lifted_to_top_level_items() {
+    let f = parse_ok(
+        "fun f() {\n    struct P { x: Int }\n    @derive(Eq)\n    enum E { A }\n    let p = P { x: 1 }\n}\n",
+    );
+    assert_eq!(f.items.len(), 3);
+    let DeclKind::Function(fun) = &f.items[0].kind else {
+        panic!()
+    };
+    let FunctionBody::Block(b) = &fun.body else {
+        panic!()
+    };
+    assert_eq!(b.stmts.len(), 1);
+    assert!(matches!(&f.items[1].kind, DeclKind::Struct(s) if s.name == "P__in_f_2"));
+    assert!(
+        matches!(&f.items[2].kind, DeclKind::Enum(e) if e.name == "E__in_f_4" && e.derives == ["Eq"])
+    );
+    let StmtKind::Let {
+        init: Some(init), ..
+    } = &b.stmts[0].kind
+    else {
+        panic!()
+    };
+    assert!(matches!(&init.kind, ExprKind::StructLit { name, .. } if name == "P__in_f_2"));
+}
+
+#[test]
+fn the_same_local_type_name_twice_in_one_function_is_an_error() {
+    let err = parse_err("fun f() {\n    struct P { x: Int }\n    enum P { A }\n}\n");
+    assert!(
+        err.to_string().contains("declared more than once in `f`"),
+        "got: {}",
+        err
+    );
+}
+
+#[test]
+fn formatter_parse_keeps_a_local_type_in_place() {
+    let toks = tokens("fun f() {\n    struct P { x: Int }\n}\n");
+    let f = super::parse_keeping_local_types(&toks).expect("parse ok");
+    assert_eq!(f.items.len(), 1);
+    let DeclKind::Function(fun) = &f.items[0].kind else {
+        panic!()
+    };
+    let FunctionBody::Block(b) = &fun.body else {
+        panic!()
+    };
+    assert!(
+        matches!(&b.stmts[0].kind, StmtKind::TypeDecl(d) if matches!(d.kind, DeclKind::Struct(_)))
+    );
+}
+
+#[test]
+fn only_types_can_be_declared_after_an_attribute_in_a_body() {
+    let err = parse_err("fun f() {\n    @allow(dead_code)\n    fun g() {}\n}\n");
+    assert!(
+        err.to_string().contains("only a `struct` or `enum`"),
+        "got: {}",
+        err
+    );
+}
+
 #[test]
 fn invalid_assignment_target_errors() {
     let err = parse_err("fun f() { 1 + 2 = 3 }\n");
@@ -553,6 +858,20 @@ fn field_and_index_chains_are_valid_targets() {
     assert!(matches!(stmts[2].kind, StmtKind::Assign { .. }));
 }
 
+#[test]
+fn bare_return_before_semicolon_or_brace_has_no_value() {
+    let f = parse_ok("fun f(x: Int) { if x > 0 { return; }\n return }\n");
+    let stmts = fn_body_stmts(&f);
+    assert!(matches!(stmts[1].kind, StmtKind::Return(None)));
+    let StmtKind::Expr(e) = &stmts[0].kind else {
+        panic!("first statement is not an expression");
+    };
+    let ExprKind::If { then_branch, .. } = &e.kind else {
+        panic!("first statement is not an if");
+    };
+    assert!(matches!(then_branch.stmts[0].kind, StmtKind::Return(None)));
+}
+
 // ----- functions, types, generics -----
 
 #[test]
@@ -809,6 +1128,53 @@ fn derive_before_non_type_is_a_parse_error() {
     assert!(matches!(err, RavenError::Parse(_, _, _)), "got: {}", err);
 }
 
+#[test]
+fn allow_attribute_attaches_to_function() {
+    let f = parse_ok("@allow(dead_code)\nfun f() {}\n");
+    let DeclKind::Function(fun) = &f.items[0].kind else {
+        panic!("expected function decl")
+    };
+    assert_eq!(fun.allows, vec!["dead_code"]);
+}
+
+#[test]
+fn allow_attribute_takes_several_lints() {
+    let f = parse_ok("@allow(dead_code, infinite_loop)\nfun f() {}\n");
+    let DeclKind::Function(fun) = &f.items[0].kind else {
+        panic!("expected function decl")
+    };
+    assert_eq!(fun.allows, vec!["dead_code", "infinite_loop"]);
+}
+
+#[test]
+fn unknown_lint_in_allow_is_a_parse_error() {
+    let err = parse_err("@allow(unused)\nfun f() {}\n");
+    assert!(
+        err.to_string()
+            .contains("unknown lint `unused` in `@allow`, expected `dead_code` or `infinite_loop`"),
+        "got: {}",
+        err
+    );
+}
+
+#[test]
+fn allow_attribute_attaches_to_impl_method() {
+    let f = parse_ok(
+        "impl P {\n    @allow(infinite_loop)\n    fun serve(self) {}\n\n    fun stop(self) {}\n}\n",
+    );
+    let DeclKind::Impl(im) = &f.items[0].kind else {
+        panic!("expected impl decl")
+    };
+    assert_eq!(im.items[0].allows, vec!["infinite_loop"]);
+    assert!(im.items[1].allows.is_empty());
+}
+
+#[test]
+fn allow_before_non_function_is_a_parse_error() {
+    let err = parse_err("@allow(dead_code)\nstruct P { x: Int }\n");
+    assert!(matches!(err, RavenError::Parse(_, _, _)), "got: {}", err);
+}
+
 // ----- imports, externs, const -----
 
 #[test]
@@ -995,6 +1361,154 @@ fn invalid_import_path_for_bare_path() {
     assert!(matches!(err, RavenError::Parse(_, _, _)));
 }
 
+#[test]
+fn bare_module_name_import_suggests_both_path_forms() {
+    let err = parse_err("import math\n");
+    let RavenError::Parse(_, span, Some(hint)) = &err else {
+        panic!("expected a parse error with a hint, got {:?}", err);
+    };
+    assert_eq!(
+        hint,
+        "a standard library module is imported as `import std/math`, \
+         and a local file by its quoted path, `import \"./math\"`"
+    );
+    assert_eq!((span.line, span.col), (1, 8));
+}
+
+#[test]
+fn array_suffix_type_suggests_list() {
+    let err = parse_err("struct Graph {\n    nodes: Node[],\n}\n");
+    let RavenError::Parse(ParseError::Custom(msg), span, Some(hint)) = &err else {
+        panic!("expected a custom parse error with a hint, got {:?}", err);
+    };
+    assert_eq!(msg, "`Node[]` is not a type");
+    assert_eq!(hint, "a list of `Node` is written `List<Node>`");
+    assert_eq!((span.line, span.col, span.len()), (2, 12, 6));
+
+    // The `List<T>` spelling parses, including a list of structs.
+    parse_ok("struct Graph {\n    nodes: List<Node>,\n    edges: List<Edge>,\n}\n");
+}
+
+#[test]
+fn double_colon_variant_suggests_dot() {
+    let err = parse_err("enum Color { Red }\nfun f() {\n    let c = Color::Red\n}\n");
+    let RavenError::Parse(ParseError::Custom(msg), span, Some(hint)) = &err else {
+        panic!("expected a custom parse error with a hint, got {:?}", err);
+    };
+    assert_eq!(msg, "`Color::Red` is not a Raven path");
+    assert_eq!(
+        hint,
+        "enum variants and associated functions use `.`: `Color.Red`"
+    );
+    assert_eq!((span.line, span.col, span.len()), (3, 13, 10));
+
+    parse_ok("enum Color { Red }\nfun f() {\n    let c = Color.Red\n}\n");
+}
+
+#[test]
+fn qualified_variant_pattern_suggests_the_bare_name() {
+    for (src, msg, len) in [
+        (
+            "fun f(c: Color) -> Int = match c {\n    Color.Red -> 1\n    _ -> 2\n}\n",
+            "`Color.Red` is not a pattern",
+            9,
+        ),
+        (
+            "fun f(c: Color) -> Int = match c {\n    Color::Red -> 1\n    _ -> 2\n}\n",
+            "`Color::Red` is not a pattern",
+            10,
+        ),
+    ] {
+        let err = parse_err(src);
+        let RavenError::Parse(ParseError::Custom(m), span, Some(hint)) = &err else {
+            panic!("expected a custom parse error with a hint, got {:?}", err);
+        };
+        assert_eq!(m, msg);
+        assert_eq!(
+            hint,
+            "a pattern names the variant alone: `Red`; the matched value's type picks the enum"
+        );
+        assert_eq!((span.line, span.col, span.len()), (2, 5, len));
+    }
+
+    parse_ok("fun f(c: Color) -> Int = match c {\n    Red -> 1\n    _ -> 2\n}\n");
+}
+
+#[test]
+fn fat_arrow_match_arm_suggests_thin_arrow() {
+    let err = parse_err("fun f(n: Int) -> Int = match n {\n    0 => 1\n    _ -> 2\n}\n");
+    let RavenError::Parse(ParseError::Custom(msg), span, Some(hint)) = &err else {
+        panic!("expected a custom parse error with a hint, got {:?}", err);
+    };
+    assert_eq!(msg, "match arms use `->`, not `=>`");
+    assert_eq!(hint, "write the arm as `pattern -> value`");
+    assert_eq!((span.line, span.col, span.len()), (2, 7, 2));
+}
+
+#[test]
+fn increment_operators_suggest_compound_assignment() {
+    for (src, msg, hint, col) in [
+        (
+            "fun f() {\n    i++\n}\n",
+            "`++` is not a Raven operator",
+            "write `i += 1`",
+            6,
+        ),
+        (
+            "fun f() {\n    ++i\n}\n",
+            "`++` is not a Raven operator",
+            "write `i += 1`",
+            5,
+        ),
+        (
+            "fun f() {\n    n--\n}\n",
+            "`--` is not a Raven operator",
+            "write `n -= 1`",
+            6,
+        ),
+    ] {
+        let err = parse_err(src);
+        let RavenError::Parse(ParseError::Custom(m), span, Some(h)) = &err else {
+            panic!("expected a custom parse error with a hint, got {:?}", err);
+        };
+        assert_eq!(m, msg);
+        assert_eq!(h, hint);
+        assert_eq!((span.line, span.col, span.len()), (2, col, 2));
+    }
+
+    // Subtracting a negation still parses.
+    parse_ok("fun f() {\n    let d = a--b\n}\n");
+}
+
+#[test]
+fn double_star_points_at_the_math_power_functions() {
+    let err = parse_err("fun f() {\n    let k = 2 ** 10\n}\n");
+    let RavenError::Parse(ParseError::Custom(msg), span, Some(hint)) = &err else {
+        panic!("expected a custom parse error with a hint, got {:?}", err);
+    };
+    assert_eq!(msg, "`**` is not a Raven operator");
+    assert!(hint.contains("`pow_int(base, exp)`"), "hint: {hint}");
+    assert!(hint.contains("`std/math`"), "hint: {hint}");
+    assert_eq!((span.line, span.col, span.len()), (2, 15, 2));
+}
+
+#[test]
+fn c_conditional_suggests_an_if_expression() {
+    let err = parse_err("fun f(x: Int) -> Int {\n    let a = x >= 0 ? x : -x\n    a\n}\n");
+    let RavenError::Parse(ParseError::Custom(msg), span, Some(hint)) = &err else {
+        panic!("expected a custom parse error with a hint, got {:?}", err);
+    };
+    assert_eq!(msg, "`cond ? a : b` is not a Raven expression");
+    assert_eq!(
+        hint,
+        "`if` is an expression: write `if cond { a } else { b }`"
+    );
+    assert_eq!((span.line, span.col, span.len()), (2, 20, 1));
+
+    // The postfix try operator still chains and ends a line.
+    parse_ok("fun f() -> Result<Int, String> {\n    let n = g()?.len()\n    let m = g()?\n    Ok(n + m)\n}\n");
+}
+
 // ----- error recovery -----
 
 #[test]
@@ -1041,3 +1555,16 @@ fn recovery_on_a_clean_file_reports_nothing() {
         parse_all_errors("fun a() -> Int { return 1 }\nfun b() -> Int { return 2 }\n").is_empty()
     );
 }
+
+#[test]
+fn parse_error_span_starts_at_the_offending_token() {
+    // The span comes from the token's own start, not from wherever the
+    // lexer stopped after consuming it.
+    let err = parse_err("fun main() {\n    let x = )\n}\n");
+    let RavenError::Parse(_, span, _) = err else {
+        panic!("expected parse error, got: {}", err);
+    };
+    assert_eq!(span.line, 2);
+    assert_eq!(span.col, 13);
+    assert_eq!(span.end - span.start, 1);
+}
@@ -1,6 +1,7 @@
 //! Type expression parsing.
 
 use crate::ast::{Type, TypeKind, TypePath, TypePathSegment};
+use crate::error::{ParseError, RavenError};
 use crate::lexer::{Token, TokenKind};
 use crate::span::Span;
 
@@ -11,6 +12,11 @@ impl Parser {
     /// `fun(...) -> T`.
     pub(crate) fn parse_type(&mut self) -> ParseResult<Type> {
         let primary = self.parse_primary_type()?;
+        if matches!(self.peek_kind(), TokenKind::LBracket)
+            && matches!(self.peek_kind_at(1), TokenKind::RBracket)
+        {
+            return Err(self.array_suffix_error(&primary));
+        }
         if matches!(self.peek_kind(), TokenKind::Question) {
             let q = self.advance();
             let span = merge_spans(&primary.span, &q.span);
@@ -22,6 +28,25 @@ impl Parser {
         Ok(primary)
     }
 
+    /// `Node[]` is the array spelling of other languages and of Raven v1.
+    /// It is never a valid type, so name the `List<Node>` spelling rather
+    /// than failing on the stray `[`.
+    fn array_suffix_error(&mut self, elem: &Type) -> RavenError {
+        self.advance(); // [
+        let rbracket = self.advance();
+        let name = match &elem.kind {
+            TypeKind::Path(p) if p.segments.len() == 1 && p.segments[0].generics.is_empty() => {
+                p.segments[0].name.clone()
+            }
+            _ => "T".to_string(),
+        };
+        RavenError::parse(
+            ParseError::Custom(format!("`{}[]` is not a type", name)),
+            merge_spans(&elem.span, &rbracket.span),
+        )
+        .with_hint(format!("a list of `{0}` is written `List<{0}>`", name))
+    }
+
     fn parse_primary_type(&mut self) -> ParseResult<Type> {
         let start = self.peek().span.clone();
         match self.peek_kind() {
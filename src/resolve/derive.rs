@@ -19,8 +19,8 @@
 //! and the formatting conventions.
 
 use crate::ast::{
-    Decl, DeclKind, Enum, EnumVariant, File, GenericParam, Struct, Type, TypeKind, TypePath,
-    VariantPayload,
+    source_type_name, Decl, DeclKind, Enum, EnumVariant, File, GenericParam, Struct, Type,
+    TypeKind, TypePath, VariantPayload,
 };
 use crate::error::{RavenError, ResolveError};
 use crate::lexer::Lexer;
@@ -28,7 +28,7 @@ use crate::parser::parse;
 
 /// The traits this pass can derive.
 const SUPPORTED: &[&str] = &[
-    "Eq", "Ord", "Hash", "ToString", "Debug", "ToJson", "FromJson",
+    "Eq", "Ord", "Hash", "ToString", "Debug", "DeepCopy", "ToJson", "FromJson",
 ];
 
 /// Whether a derive of `trait_name` is present on any type in `file`.
@@ -56,7 +56,8 @@ pub fn needs_json_module(file: &File) -> bool {
     any_derive(file, "ToJson") || any_derive(file, "FromJson")
 }
 
-/// Append a synthesized impl for every `@derive(...)` request in `items` to
+/// Append a synthesized impl for every `@derive(...)` request in `items`,
+/// and the implied `Eq` impl of every plain enum (see [`implicit_eq`]), to
 /// `combined`. Returns whether any derive needs the shared JSON helper free
 /// functions, so the caller can emit them exactly once for the whole program
 /// (see [`json_helper_decls`]); a derive over a single set of items is one of
@@ -89,7 +90,11 @@ pub fn expand_derives(
                     generated.push('\n');
                 }
             }
-            DeclKind::Enum(e) if !e.derives.is_empty() => {
+            DeclKind::Enum(e) => {
+                if implicit_eq(e, items) {
+                    generated.push_str(&enum_impl(e, "Eq", &decl.span)?);
+                    generated.push('\n');
+                }
                 for trait_name in &e.derives {
                     check_supported(trait_name, &decl.span)?;
                     if trait_name == "ToJson" || trait_name == "FromJson" {
@@ -114,6 +119,39 @@ pub fn expand_derives(
     Ok(needs_json_helpers)
 }
 
+/// Whether enum `e` gets an `Eq` impl without asking for one, so `==` on it
+/// compares variants rather than object identity. That holds for a
+/// non-generic enum whose payload slots are all `Int`, `Float`, `Bool`,
+/// `Char`, or `String`, which always implement `Eq`. Any other payload may
+/// not, so such an enum still needs an explicit `@derive(Eq)`. An enum that
+/// already derives `Eq`, or that `items` gives an `equals` method by hand
+/// (or through an earlier expansion of the same module), is left alone.
+fn implicit_eq(e: &Enum, items: &[Decl]) -> bool {
+    const SCALARS: &[&str] = &["Int", "Float", "Bool", "Char", "String"];
+    let scalar = |t: &Type| match &t.kind {
+        TypeKind::Path(p) => {
+            p.segments.len() == 1
+                && p.segments[0].generics.is_empty()
+                && SCALARS.contains(&p.segments[0].name.as_str())
+        }
+        _ => false,
+    };
+    let plain_payloads = e.variants.iter().all(|v| match &v.payload {
+        VariantPayload::Unit => true,
+        VariantPayload::Tuple(tys) => tys.iter().all(scalar),
+        VariantPayload::Struct(_) => false,
+    });
+    let names_enum = |p: &TypePath| p.segments.last().is_some_and(|s| s.name == e.name);
+    let has_equals = items.iter().any(|d| match &d.kind {
+        DeclKind::Impl(i) => {
+            let target = i.for_type.as_ref().unwrap_or(&i.trait_or_type);
+            names_enum(target) && i.items.iter().any(|f| f.name == "equals")
+        }
+        _ => false,
+    });
+    e.generics.is_empty() && plain_payloads && !e.derives.iter().any(|t| t == "Eq") && !has_equals
+}
+
 /// The reserved name prefix for the free helper functions a `@derive`
 /// expansion emits (see [`json_helper_decls`]). A user declaration may not use
 /// it, or it would collide with a generated helper.
@@ -174,7 +212,7 @@ fn check_supported(trait_name: &str, span: &crate::span::Span) -> Result<(), Rav
     } else {
         Err(RavenError::resolve(
             ResolveError::Other(format!(
-                "cannot derive `{trait_name}`: supported traits are Eq, Ord, Hash, ToString, Debug, ToJson, FromJson"
+                "cannot derive `{trait_name}`: supported traits are Eq, Ord, Hash, ToString, Debug, DeepCopy, ToJson, FromJson"
             )),
             span.clone(),
         ))
@@ -207,6 +245,7 @@ fn struct_impl(s: &Struct, trait_name: &str) -> String {
         "Hash" => struct_hash_body(s),
         "ToString" => struct_to_string_body(s, "to_string"),
         "Debug" => struct_to_string_body(s, "debug"),
+        "DeepCopy" => struct_deep_copy_body(s, &self_ty),
         "ToJson" => struct_to_json_body(s),
         "FromJson" => struct_from_json_body(s, &self_ty),
         _ => unreachable!("checked by check_supported"),
@@ -272,7 +311,7 @@ fn struct_to_string_body(s: &Struct, method: &str) -> String {
     if s.fields.is_empty() {
         return format!(
             "    fun {fn_name}(self) -> String {{ return \"{}\" }}\n",
-            s.name
+            source_type_name(&s.name)
         );
     }
     let mut parts = Vec::new();
@@ -281,6 +320,21 @@ fn struct_to_string_body(s: &Struct, method: &str) -> String {
     }
     format!(
         "    fun {fn_name}(self) -> String {{ return \"{} {{ {} }}\" }}\n",
+        source_type_name(&s.name),
+        parts.join(", ")
+    )
+}
+
+/// Rebuild a struct with every field deep-copied. Each field type must
+/// implement `DeepCopy`.
+fn struct_deep_copy_body(s: &Struct, self_ty: &str) -> String {
+    let parts: Vec<String> = s
+        .fields
+        .iter()
+        .map(|f| format!("{0}: self.{0}.deep_copy()", f.name))
+        .collect();
+    format!(
+        "    fun deep_copy(self) -> {self_ty} {{ return {} {{ {} }} }}\n",
         s.name,
         parts.join(", ")
     )
@@ -296,7 +350,8 @@ fn enum_impl(e: &Enum, trait_name: &str, span: &crate::span::Span) -> Result<Str
             return Err(RavenError::resolve(
                 ResolveError::Other(format!(
                     "cannot derive `{trait_name}` for enum `{}`: struct-style variant `{}` is not supported yet",
-                    e.name, v.name
+                    source_type_name(&e.name),
+                    v.name
                 )),
                 span.clone(),
             ));
@@ -309,6 +364,7 @@ fn enum_impl(e: &Enum, trait_name: &str, span: &crate::span::Span) -> Result<Str
         "Hash" => enum_hash_body(e),
         "ToString" => enum_to_string_body(e, "to_string"),
         "Debug" => enum_to_string_body(e, "debug"),
+        "DeepCopy" => enum_deep_copy_body(e, &self_ty),
         "ToJson" => enum_to_json_body(e),
         "FromJson" => enum_from_json_body(e, &self_ty),
         _ => unreachable!("checked by check_supported"),
@@ -408,6 +464,31 @@ fn enum_ord_body(e: &Enum, self_ty: &str) -> String {
     body
 }
 
+/// Rebuild the matched variant with every payload value deep-copied. A unit
+/// variant is rebuilt as is.
+fn enum_deep_copy_body(e: &Enum, self_ty: &str) -> String {
+    let mut body =
+        format!("    fun deep_copy(self) -> {self_ty} {{\n        return match self {{\n");
+    for v in &e.variants {
+        let n = variant_arity(v);
+        if n == 0 {
+            body.push_str(&format!("            {1} -> {0}.{1},\n", e.name, v.name));
+        } else {
+            let binds: Vec<String> = (0..n).map(|i| format!("a{i}")).collect();
+            let copies: Vec<String> = (0..n).map(|i| format!("a{i}.deep_copy()")).collect();
+            body.push_str(&format!(
+                "            {1}({2}) -> {0}.{1}({3}),\n",
+                e.name,
+                v.name,
+                binds.join(", "),
+                copies.join(", ")
+            ));
+        }
+    }
+    body.push_str("        }\n    }\n");
+    body
+}
+
 fn enum_hash_body(e: &Enum) -> String {
     let mut body = String::from("    fun hash(self) -> Int {\n        return match self {\n");
     for (idx, v) in e.variants.iter().enumerate() {
@@ -769,6 +850,31 @@ mod tests {
         assert!(impls.is_empty());
     }
 
+    #[test]
+    fn plain_enum_gets_an_implied_eq() {
+        let traits = |src: &str| -> Vec<String> {
+            derived_impls(src)
+                .iter()
+                .map(|i| i.trait_or_type.segments[0].name.clone())
+                .collect()
+        };
+        // Unit and scalar payloads imply `Eq`, alongside any other derive.
+        assert_eq!(traits("enum Color { Red, Green }\n"), vec!["Eq"]);
+        assert_eq!(
+            traits("@derive(ToString)\nenum Tok { Num(Int), Word(String), End }\n"),
+            vec!["Eq", "ToString"]
+        );
+        // Already `Eq`: derived once, or hand written.
+        assert_eq!(traits("@derive(Eq)\nenum Color { Red }\n"), vec!["Eq"]);
+        assert!(traits(
+            "enum Color { Red }\nimpl Eq for Color {\n    fun equals(self, other: Color) -> Bool { return true }\n}\n"
+        )
+        .is_empty());
+        // A payload that may not be `Eq`, or a generic enum, needs `@derive(Eq)`.
+        assert!(traits("enum Shape { Poly(List<Int>) }\n").is_empty());
+        assert!(traits("enum Box<T> { Full(T), Empty }\n").is_empty());
+    }
+
     #[test]
     fn unsupported_trait_is_rejected() {
         let file = parse_src("@derive(Clone)\nstruct Point { x: Int }\n");
@@ -778,6 +884,18 @@ mod tests {
         assert!(format!("{err}").contains("Clone"), "got: {err}");
     }
 
+    #[test]
+    fn deep_copy_is_derivable_for_structs_and_enums() {
+        let impls = derived_impls(
+            "@derive(DeepCopy)\nstruct P { xs: List<Int> }\n@derive(DeepCopy)\nenum E { A, B(List<Int>) }\n",
+        );
+        let traits: Vec<String> = impls
+            .iter()
+            .map(|i| i.trait_or_type.segments[0].name.clone())
+            .collect();
+        assert_eq!(traits, vec!["DeepCopy", "DeepCopy"]);
+    }
+
     #[test]
     fn ord_is_derivable() {
         let impls = derived_impls("@derive(Ord)\nstruct P { x: Int, y: Int }\n");
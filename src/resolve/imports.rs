@@ -45,20 +45,40 @@ pub struct LoadedSource {
     pub source: String,
 }
 
-/// Filesystem backed loader. Resolves `target` relative to
-/// `importing.parent()` and reads the file from disk. A `.rv`
-/// extension is appended if `target` does not already have an
-/// extension. Used by the CLI; tests rarely touch this.
+/// Filesystem backed loader. Resolves `target` against the importing
+/// file's directory, trying in order:
+///
+/// 1. the path as written, when it names a file (`./data.rv`);
+/// 2. the path with `.rv` appended (`./helpers` reads `helpers.rv`);
+/// 3. the same `.rv` file under a `lib/` directory next to the importing
+///    file (`./math` reads `lib/math.rv`).
+///
+/// The first file found wins. Both the import resolver and the module
+/// expander load through this, so they always agree on which file an
+/// import names. Used by the CLI; tests rarely touch this.
 #[derive(Debug, Default)]
 pub struct FsLoader;
 
-impl SourceLoader for FsLoader {
-    fn load(&mut self, importing: &Path, target: &str) -> Option<LoadedSource> {
+impl FsLoader {
+    /// The candidate paths for `target`, in lookup order.
+    fn candidates(importing: &Path, target: &str) -> Vec<PathBuf> {
         let parent = importing.parent().unwrap_or_else(|| Path::new("."));
-        let mut path = parent.join(target);
-        if path.extension().is_none() {
-            path.set_extension("rv");
+        let with_rv = format!("{}.rv", target);
+        let mut out = vec![parent.join(target), parent.join(&with_rv)];
+        // `lib/` only makes sense for a path that stays beside the
+        // importing file; `../x` already names a directory explicitly.
+        if let Some(rest) = with_rv.strip_prefix("./") {
+            out.push(parent.join("lib").join(rest));
         }
+        out
+    }
+}
+
+impl SourceLoader for FsLoader {
+    fn load(&mut self, importing: &Path, target: &str) -> Option<LoadedSource> {
+        let path = Self::candidates(importing, target)
+            .into_iter()
+            .find(|p| p.is_file())?;
         let canonical_path = path.canonicalize().unwrap_or_else(|_| path.clone());
         let source = std::fs::read_to_string(&path).ok()?;
         Some(LoadedSource {
@@ -84,6 +104,7 @@ pub const STDLIB_MODULES: &[&str] = &[
     "fmt",
     "string",
     "math",
+    "bigint",
     "path",
     "error",
     "env",
@@ -44,6 +44,30 @@ pub struct ResolvedFile<'a> {
     pub module_scope: ScopeStack,
 }
 
+impl ResolvedFile<'_> {
+    /// The `extern "C"` function `name` declared by the module `import`
+    /// brings in, as its resolver binding. The expander merges a module's
+    /// `fun` items under the import's mangled prefix but leaves an extern
+    /// under its bare C name, so a module-alias call such as `math.sqrt(x)`
+    /// (from `import std/math`) finds the extern here instead. Only an
+    /// extern whose declaration comes from that module's own file matches.
+    pub fn module_extern(&self, import: &ResolvedImport, name: &str) -> Option<Binding> {
+        let module_file = match &import.target {
+            ImportTarget::StdlibModule { segments } => {
+                std::path::PathBuf::from(format!("<bundled>/std/{}.rv", segments.first()?))
+            }
+            ImportTarget::LocalModule { canonical_path, .. } => canonical_path.clone(),
+            ImportTarget::ExternalPackage { .. } => return None,
+        };
+        let binding = self.module_scope.lookup_module(name)?.binding.clone();
+        let Binding::Extern { decl_id, .. } = binding else {
+            return None;
+        };
+        let decl = self.file.items.get(decl_id.0)?;
+        (*decl.span.file == module_file).then_some(binding)
+    }
+}
+
 /// Resolve `file` using `loader` for any local imports it contains.
 ///
 /// Returns a [`ResolvedFile`] on success or the first
@@ -160,6 +160,24 @@ impl ScopeStack {
         None
     }
 
+    /// True when `name` resolves to a local, parameter, or pattern binding
+    /// of an enclosing function rather than the innermost one, so a use of
+    /// it here is a closure capture.
+    pub fn is_capture(&self, name: &str) -> bool {
+        let mut crossed_function = false;
+        for frame in self.frames.iter().rev() {
+            if let Some(entry) = frame.names.get(name) {
+                return crossed_function
+                    && matches!(
+                        entry.binding,
+                        Binding::Local(_) | Binding::Param(_) | Binding::PatternBinding(_)
+                    );
+            }
+            crossed_function |= frame.kind == ScopeKind::Function;
+        }
+        false
+    }
+
     /// True if any enclosing frame is an `Impl` scope.
     pub fn in_impl(&self) -> bool {
         self.frames.iter().any(|f| f.kind == ScopeKind::Impl)
@@ -55,6 +55,7 @@ pub const BUNDLED_MODULES: &[(&str, &str)] = &[
     ("random", include_str!("../../stdlib/std/random.rv")),
     ("fmt", include_str!("../../stdlib/std/fmt.rv")),
     ("math", include_str!("../../stdlib/std/math.rv")),
+    ("bigint", include_str!("../../stdlib/std/bigint.rv")),
     ("path", include_str!("../../stdlib/std/path.rv")),
     ("error", include_str!("../../stdlib/std/error.rv")),
     ("env", include_str!("../../stdlib/std/env.rv")),
@@ -260,6 +261,36 @@ pub fn bundled_source(module_path: &str) -> Option<&'static str> {
         .map(|(_, src)| *src)
 }
 
+/// The bundled module, other than the prelude, whose `impl <self_ty>` block
+/// defines `method`. The type checker uses it to point a call such as
+/// `s.trim()` without `import std/string` at the import it needs. The scan
+/// is textual: an `impl` header at column zero opens a block that ends at
+/// the next `}` at column zero.
+pub fn module_defining_method(self_ty: &str, method: &str) -> Option<&'static str> {
+    let header = format!("impl {self_ty} {{");
+    BUNDLED_MODULES
+        .iter()
+        .filter(|(name, _)| *name != PRELUDE_MODULE)
+        .find(|(_, src)| {
+            let mut inside = false;
+            src.lines().any(|line| {
+                if line == header {
+                    inside = true;
+                } else if line.starts_with('}') {
+                    inside = false;
+                } else if inside {
+                    if let Some(rest) = line.trim_start().strip_prefix("fun ") {
+                        return rest
+                            .strip_prefix(method)
+                            .is_some_and(|after| after.starts_with('(') || after.starts_with('<'));
+                    }
+                }
+                false
+            })
+        })
+        .map(|(name, _)| *name)
+}
+
 /// Expand `user` into a combined [`File`] that contains every bundled
 /// stdlib module the program imports, followed by the user's own items.
 ///
@@ -383,6 +414,12 @@ pub fn expand_with_stdlib_ctx(
         for name in top_level_fn_names(&module_file) {
             rename.insert(name.clone(), mangle_stdlib_fn(module, &name));
         }
+        // Module globals (`const PI` in std/math) are namespaced like the
+        // functions, so a selective import binds them and a user global of
+        // the same name does not collide.
+        for name in top_level_global_names(&module_file) {
+            rename.insert(name.clone(), mangle_stdlib_fn(module, &name));
+        }
         merge_module_items(module_file.items, &rename, &mut combined_items);
     }
 
@@ -602,78 +639,84 @@ fn merge_module_items(
         if matches!(&decl.kind, DeclKind::Import(_)) {
             continue;
         }
-        match &mut decl.kind {
-            DeclKind::Function(f) => {
-                rewrite_fn(f, rename);
-                rename_decl(&mut f.name, rename);
-            }
-            DeclKind::Struct(s) => {
-                rename_decl(&mut s.name, rename);
-                rewrite_generics(&mut s.generics, rename);
-                for field in &mut s.fields {
-                    rewrite_type(&mut field.ty, rename);
-                }
-            }
-            DeclKind::Enum(e) => {
-                rename_decl(&mut e.name, rename);
-                rewrite_generics(&mut e.generics, rename);
-                for v in &mut e.variants {
-                    match &mut v.payload {
-                        VariantPayload::Tuple(tys) => {
-                            for t in tys {
-                                rewrite_type(t, rename);
-                            }
+        rewrite_decl(&mut decl, rename);
+        combined.push(decl);
+    }
+}
+
+/// Rename a declaration through `rename` and rewrite every type and name it
+/// mentions: its signature, fields or variants, and body.
+fn rewrite_decl(decl: &mut Decl, rename: &HashMap<String, String>) {
+    match &mut decl.kind {
+        DeclKind::Function(f) => {
+            rewrite_fn(f, rename);
+            rename_decl(&mut f.name, rename);
+        }
+        DeclKind::Struct(s) => {
+            rename_decl(&mut s.name, rename);
+            rewrite_generics(&mut s.generics, rename);
+            for field in &mut s.fields {
+                rewrite_type(&mut field.ty, rename);
+            }
+        }
+        DeclKind::Enum(e) => {
+            rename_decl(&mut e.name, rename);
+            rewrite_generics(&mut e.generics, rename);
+            for v in &mut e.variants {
+                match &mut v.payload {
+                    VariantPayload::Tuple(tys) => {
+                        for t in tys {
+                            rewrite_type(t, rename);
                         }
-                        VariantPayload::Struct(fields) => {
-                            for f in fields {
-                                rewrite_type(&mut f.ty, rename);
-                            }
+                    }
+                    VariantPayload::Struct(fields) => {
+                        for f in fields {
+                            rewrite_type(&mut f.ty, rename);
                         }
-                        VariantPayload::Unit => {}
                     }
+                    VariantPayload::Unit => {}
                 }
             }
-            DeclKind::Trait(t) => {
-                rename_decl(&mut t.name, rename);
-                rewrite_generics(&mut t.generics, rename);
-                for m in &mut t.members {
-                    rewrite_fn(m, rename);
-                }
+        }
+        DeclKind::Trait(t) => {
+            rename_decl(&mut t.name, rename);
+            rewrite_generics(&mut t.generics, rename);
+            for m in &mut t.members {
+                rewrite_fn(m, rename);
             }
-            DeclKind::Impl(i) => {
-                // The impl target and (for a trait impl) the trait it
-                // implements are type references that follow the same rename
-                // as the declarations. Method names are dispatched by receiver
-                // type so they keep their names, but their signatures and
-                // bodies reference types that may have been namespaced.
-                rewrite_generics(&mut i.generics, rename);
-                rewrite_type_path(&mut i.trait_or_type, rename);
-                if let Some(for_type) = &mut i.for_type {
-                    rewrite_type_path(for_type, rename);
-                }
-                for m in &mut i.items {
-                    rewrite_fn(m, rename);
-                }
+        }
+        DeclKind::Impl(i) => {
+            // The impl target and (for a trait impl) the trait it
+            // implements are type references that follow the same rename
+            // as the declarations. Method names are dispatched by receiver
+            // type so they keep their names, but their signatures and
+            // bodies reference types that may have been namespaced.
+            rewrite_generics(&mut i.generics, rename);
+            rewrite_type_path(&mut i.trait_or_type, rename);
+            if let Some(for_type) = &mut i.for_type {
+                rewrite_type_path(for_type, rename);
             }
-            DeclKind::Const(c) => {
-                rename_decl(&mut c.name, rename);
-                if let Some(t) = &mut c.ty {
-                    rewrite_type(t, rename);
-                }
-                rewrite_expr(&mut c.value, rename);
+            for m in &mut i.items {
+                rewrite_fn(m, rename);
             }
-            DeclKind::Let(l) => {
-                rename_decl(&mut l.name, rename);
-                if let Some(t) = &mut l.ty {
-                    rewrite_type(t, rename);
-                }
-                if let Some(e) = &mut l.init {
-                    rewrite_expr(e, rename);
-                }
+        }
+        DeclKind::Const(c) => {
+            rename_decl(&mut c.name, rename);
+            if let Some(t) = &mut c.ty {
+                rewrite_type(t, rename);
             }
-            DeclKind::Extern(_) | DeclKind::Import(_) | DeclKind::Macro(_) => {}
+            rewrite_expr(&mut c.value, rename);
         }
-        combined.push(decl);
+        DeclKind::Let(l) => {
+            rename_decl(&mut l.name, rename);
+            if let Some(t) = &mut l.ty {
+                rewrite_type(t, rename);
+            }
+            if let Some(e) = &mut l.init {
+                rewrite_expr(e, rename);
+            }
+        }
+        DeclKind::Extern(_) | DeclKind::Import(_) | DeclKind::Macro(_) => {}
     }
 }
 
@@ -795,12 +838,14 @@ fn import_rename_map(
                 if let Some(module) = segments.first() {
                     if let Ok(target) = parse_bundled_module(module) {
                         let fns = top_level_fn_names(&target);
+                        let globals = top_level_global_names(&target);
                         for sel in &import.selectors {
-                            // Only functions are namespaced; a type keeps its
-                            // own name (see `merge_module_items`), so a type
-                            // selector needs no rename. The call site uses the
-                            // local name, mapped to the exported name's symbol.
-                            if fns.contains(&sel.name) {
+                            // Only functions and globals are namespaced; a
+                            // type keeps its own name (see
+                            // `merge_module_items`), so a type selector needs
+                            // no rename. The use site has the local name,
+                            // mapped to the exported name's symbol.
+                            if fns.contains(&sel.name) || globals.contains(&sel.name) {
                                 map.insert(
                                     sel.local().to_string(),
                                     mangle_stdlib_fn(module, &sel.name),
@@ -1472,7 +1517,7 @@ fn rewrite_block(block: &mut Block, rename: &HashMap<String, String>) {
     let mut shadowed: Option<HashMap<String, String>> = None;
     for stmt in &mut block.stmts {
         rewrite_stmt(stmt, shadowed.as_ref().unwrap_or(rename));
-        if let StmtKind::Let { name, .. } = &stmt.kind {
+        if let StmtKind::Let { name, .. } | StmtKind::Fun { name, .. } = &stmt.kind {
             if shadowed.as_ref().unwrap_or(rename).contains_key(name) {
                 let mut m = shadowed.as_ref().unwrap_or(rename).clone();
                 m.remove(name);
@@ -1500,12 +1545,17 @@ fn rewrite_stmt(stmt: &mut Stmt, rename: &HashMap<String, String>) {
                 rewrite_expr(e, rename);
             }
         }
-        StmtKind::Defer(e) | StmtKind::Spawn(e) | StmtKind::Expr(e) => rewrite_expr(e, rename),
+        StmtKind::Fun { func: e, .. }
+        | StmtKind::Defer(e)
+        | StmtKind::Spawn(e)
+        | StmtKind::Expr(e) => rewrite_expr(e, rename),
         StmtKind::Assign { target, value, .. } => {
             rewrite_expr(target, rename);
             rewrite_expr(value, rename);
         }
-        StmtKind::Continue => {}
+        // Local types are lifted to the top level by the parser, so the
+        // rename pass only sees them as ordinary items.
+        StmtKind::Continue | StmtKind::TypeDecl(_) => {}
     }
 }
 
@@ -1690,9 +1740,16 @@ fn rewrite_expr(expr: &mut Expr, rename: &HashMap<String, String>) {
             }
         }
         ExprKind::Loop(block) => rewrite_block(block, rename),
-        ExprKind::While { cond, body } => {
+        ExprKind::While {
+            cond,
+            body,
+            else_block,
+        } => {
             rewrite_expr(cond, rename);
             rewrite_block(body, rename);
+            if let Some(e) = else_block {
+                rewrite_block(e, rename);
+            }
         }
         ExprKind::For {
             pattern,
@@ -1745,6 +1802,7 @@ fn rewrite_expr(expr: &mut Expr, rename: &HashMap<String, String>) {
         | ExprKind::Char(_)
         | ExprKind::CStr(_)
         | ExprKind::SelfLower
+        | ExprKind::Unit
         // A macro call only appears in formatter-parsed source; the stdlib
         // rename pass runs after expansion, so it never sees one.
         | ExprKind::MacroCall(_)
@@ -1807,11 +1865,27 @@ mod tests {
         assert!(bundled_source("string").is_some());
     }
 
+    #[test]
+    fn module_defining_method_finds_the_impl_block() {
+        assert_eq!(module_defining_method("String", "trim"), Some("string"));
+        assert_eq!(module_defining_method("String", "trim_end"), Some("string"));
+        assert_eq!(module_defining_method("Float", "floor"), Some("math"));
+        // `abs` on `Float` is in the prelude, which is always imported.
+        assert_eq!(module_defining_method("Float", "abs"), None);
+        // A prefix of a method name is not a match.
+        assert_eq!(module_defining_method("String", "tri"), None);
+    }
+
     #[test]
     fn math_module_is_bundled() {
         assert!(bundled_source("math").is_some());
     }
 
+    #[test]
+    fn bigint_module_is_bundled() {
+        assert!(bundled_source("bigint").is_some());
+    }
+
     #[test]
     fn path_module_is_bundled() {
         assert!(bundled_source("path").is_some());
@@ -1972,9 +2046,16 @@ mod tests {
                     }
                 }
             }
-            ExprKind::While { cond, body } => {
+            ExprKind::While {
+                cond,
+                body,
+                else_block,
+            } => {
                 collect_expr_idents(cond, out);
                 collect_block_idents(body, out);
+                if let Some(e) = else_block {
+                    collect_block_idents(e, out);
+                }
             }
             ExprKind::Block(b) => collect_block_idents(b, out),
             _ => {}
@@ -2004,6 +2085,24 @@ mod tests {
         assert!(!names.contains(&"std.io.main".to_string()));
     }
 
+    #[test]
+    fn expand_namespaces_bundled_module_consts() {
+        // std/math's `const PI` merges as `std.math.PI`, and `pi()` reads it
+        // under that name, so a user `const PI` does not collide with it.
+        let user = parse_src("import std/math\nconst PI: Float = 3.0\nfun main() {}\n");
+        let combined = expand_with_stdlib(&user).expect("expand");
+        let consts: Vec<&str> = combined
+            .items
+            .iter()
+            .filter_map(|d| match &d.kind {
+                DeclKind::Const(c) => Some(c.name.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert!(consts.contains(&"std.math.PI"), "consts: {consts:?}");
+        assert!(consts.contains(&"PI"), "consts: {consts:?}");
+    }
+
     #[test]
     fn no_std_import_still_merges_the_prelude() {
         // Even with no explicit `import std/...`, the prelude (`std/core`)
@@ -2687,7 +2786,11 @@ mod tests {
         ));
         std::fs::create_dir_all(&dir).expect("mkdir");
         for (name, src) in files {
-            std::fs::write(dir.join(name), src).expect("write");
+            let path = dir.join(name);
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent).expect("mkdir");
+            }
+            std::fs::write(path, src).expect("write");
         }
         let entry_path = dir.join(entry);
         (dir, entry_path)
@@ -2725,6 +2828,45 @@ mod tests {
         std::fs::remove_dir_all(&dir).ok();
     }
 
+    #[test]
+    fn local_import_falls_back_to_lib_directory() {
+        let (dir, entry) = write_temp_project(
+            &[
+                (
+                    "lib/mathx.rv",
+                    "fun twice(n: Int) -> Int { return n * 2 }\n",
+                ),
+                ("main.rv", "import \"./mathx\" { twice }\nfun main() {}\n"),
+            ],
+            "main.rv",
+        );
+        let canon = dir.join("lib/mathx.rv").canonicalize().expect("canon");
+        let user = parse_at("import \"./mathx\" { twice }\nfun main() {}\n", &entry);
+        let combined = expand_with_stdlib(&user).expect("expand");
+        let mangled = mangle_local_fn(&local_module_key(&canon), "twice");
+        let present = combined
+            .items
+            .iter()
+            .any(|d| matches!(&d.kind, DeclKind::Function(f) if f.name == mangled));
+        assert!(present, "lib/ module should merge under {mangled}");
+
+        // A sibling file of the same name shadows the `lib/` copy.
+        std::fs::write(
+            dir.join("mathx.rv"),
+            "fun twice(n: Int) -> Int { return n }\n",
+        )
+        .expect("write");
+        let sibling = dir.join("mathx.rv").canonicalize().expect("canon");
+        let combined = expand_with_stdlib(&user).expect("expand");
+        let mangled = mangle_local_fn(&local_module_key(&sibling), "twice");
+        let present = combined
+            .items
+            .iter()
+            .any(|d| matches!(&d.kind, DeclKind::Function(f) if f.name == mangled));
+        assert!(present, "sibling module should win over lib/");
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
     #[test]
     fn selectively_imported_local_const_resolves() {
         let main_src = "import \"./limits\" { MAX_WIDTH }\nfun main() {\n    print(MAX_WIDTH)\n}\n";
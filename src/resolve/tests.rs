@@ -99,6 +99,32 @@ fn unresolved_name_in_body_is_error() {
     }
 }
 
+#[test]
+fn each_function_has_its_own_local_type() {
+    let file = parse_src(
+        "fun a() {\n    struct P { x: Int }\n    let p = P { x: 1 }\n}\n\
+         fun b() {\n    struct P { s: String }\n    let p = P { s: \"b\" }\n}\n",
+        "test.rv",
+    );
+    resolve_file(&file, &mut NoLoader).expect("both local `P`s resolve");
+}
+
+#[test]
+fn another_functions_local_type_is_not_in_scope() {
+    let file = parse_src(
+        "fun a() {\n    struct P { x: Int }\n}\nfun main() {\n    let q = P { x: 2 }\n}\n",
+        "test.rv",
+    );
+    let err = resolve_file(&file, &mut NoLoader).unwrap_err();
+    match err {
+        RavenError::Resolve(ResolveError::UnresolvedName(name), _, Some(hint)) => {
+            assert_eq!(name, "P");
+            assert!(hint.contains("declared inside `a`"), "hint: {hint}");
+        }
+        other => panic!("expected UnresolvedName with a hint, got {:?}", other),
+    }
+}
+
 #[test]
 fn duplicate_top_level_declaration_is_error() {
     let file = parse_src("fun dup() {}\nfun dup() {}\n", "test.rv");
@@ -127,6 +153,25 @@ fn inner_let_shadows_outer_let() {
     assert_eq!(local_uses.len(), 1);
 }
 
+#[test]
+fn block_locals_do_not_leak_past_their_block() {
+    for body in [
+        "if c {\n        let inner = 1\n    }",
+        "while c {\n        let inner = 1\n    }",
+        "for i in xs {\n        let inner = i\n    }",
+        "{\n        let inner = 1\n    }",
+    ] {
+        let src = format!("fun f(c: Bool, xs: List<Int>) {{\n    {body}\n    inner\n}}\n");
+        let file = parse_src(&src, "test.rv");
+        match resolve_file(&file, &mut NoLoader).unwrap_err() {
+            RavenError::Resolve(ResolveError::UnresolvedName(name), _, _) => {
+                assert_eq!(name, "inner");
+            }
+            other => panic!("expected UnresolvedName, got {:?}", other),
+        }
+    }
+}
+
 #[test]
 fn self_outside_impl_is_an_error() {
     let file = parse_src("fun f() { self.x }\n", "test.rv");
@@ -148,6 +193,42 @@ fn self_inside_impl_is_ok() {
     assert!(has_self_value, "expected at least one SelfValue use");
 }
 
+#[test]
+fn assigning_a_captured_local_in_a_closure_is_an_error() {
+    for (src, name, col) in [
+        (
+            "fun main() {\n    let n = 0\n    let f = fun() { n = n + 1 }\n}\n",
+            "n",
+            21,
+        ),
+        (
+            "fun f(total: Int) {\n    let g = fun(x: Int) {\n total += x }\n}\n",
+            "total",
+            2,
+        ),
+    ] {
+        let file = parse_src(src, "test.rv");
+        let err = resolve_file(&file, &mut NoLoader).unwrap_err();
+        let RavenError::Resolve(ResolveError::Other(msg), span, Some(_)) = &err else {
+            panic!("expected a resolve error with a hint, got {:?}", err);
+        };
+        assert_eq!(
+            msg,
+            &format!("cannot assign to `{name}`, which this closure captures by value")
+        );
+        assert_eq!((span.line, span.col), (3, col));
+    }
+}
+
+#[test]
+fn closure_may_assign_its_own_locals_and_shared_elements() {
+    let file = parse_src(
+        "fun main() {\n    let hits = [0]\n    let f = fun(x: Int) {\n        let y = x\n        y = y + 1\n        x = y\n        hits[0] = hits[0] + y\n    }\n    let n = 0\n    n = 1\n}\n",
+        "test.rv",
+    );
+    resolve_file(&file, &mut NoLoader).expect("only the closure's own names are assigned");
+}
+
 #[test]
 fn self_without_self_param_is_an_error() {
     // A method that uses `self` but does not declare it as a parameter is
@@ -329,3 +410,64 @@ fn enum_use_resolves_to_enum_binding() {
         RavenError::Resolve(ResolveError::UnresolvedName(_), _, _)
     );
 }
+
+#[test]
+fn lowercase_primitive_type_names_suggest_the_raven_spelling() {
+    for (ty, raven) in [("char", "Char"), ("int", "Int"), ("str", "String")] {
+        let src = format!("fun f(x: {ty}) -> Int = 1\n");
+        let file = parse_src(&src, "main.rv");
+        let mut loader = NoLoader;
+        let err = resolve_file(&file, &mut loader).unwrap_err();
+        let RavenError::Resolve(ResolveError::UnresolvedName(name), _, Some(hint)) = &err else {
+            panic!("expected an unresolved name with a hint, got {:?}", err)
+        };
+        assert_eq!(name, ty);
+        assert!(hint.contains(&format!("`{raven}`")), "hint: {}", hint);
+    }
+}
+
+#[test]
+fn free_conversion_functions_point_at_the_raven_spelling() {
+    for (call, expected) in [
+        ("to_int(\"42\")", "s.parse_int()"),
+        ("to_float(3)", "n.to_float()"),
+        ("to_string(5)", "x.to_string()"),
+        ("to_bool(0)", "no truthiness"),
+    ] {
+        let src = format!("fun main() {{\n    let v = {call}\n}}\n");
+        let file = parse_src(&src, "main.rv");
+        let mut loader = NoLoader;
+        let err = resolve_file(&file, &mut loader).unwrap_err();
+        let RavenError::Resolve(ResolveError::UnresolvedName(_), _, Some(hint)) = &err else {
+            panic!("expected an unresolved name with a hint, got {:?}", err)
+        };
+        assert!(hint.contains(expected), "hint: {}", hint);
+    }
+}
+
+#[test]
+fn local_functions_that_capture_nothing_can_call_each_other() {
+    let src = "fun main() {\n    print(even(4))\n    fun even(n: Int) -> Bool = if n == 0 { true } else { odd(n - 1) }\n    fun odd(n: Int) -> Bool = if n == 0 { false } else { even(n - 1) }\n}\n";
+    let file = parse_src(src, "main.rv");
+    let mut loader = NoLoader;
+    resolve_file(&file, &mut loader).expect("hoisted local functions resolve");
+}
+
+#[test]
+fn use_before_a_later_local_function_hints_at_the_order() {
+    // `b` reads the local `k`, so it stays a closure bound where it is
+    // declared, and `a` (which calls it) stays one too.
+    let src = "fun main() {\n    let k = 1\n    fun a(x: Int) -> Int = b(x)\n    fun b(x: Int) -> Int = x + k\n}\n";
+    let file = parse_src(src, "main.rv");
+    let mut loader = NoLoader;
+    let err = resolve_file(&file, &mut loader).unwrap_err();
+    let RavenError::Resolve(ResolveError::UnresolvedName(name), _, Some(hint)) = &err else {
+        panic!("expected an unresolved name with a hint, got {:?}", err)
+    };
+    assert_eq!(name, "b");
+    assert!(
+        hint.contains("declared later in this block"),
+        "hint: {}",
+        hint
+    );
+}
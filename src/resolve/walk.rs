@@ -10,11 +10,11 @@
 //! AST node category. Each function owns its local scope frames and is
 //! responsible for popping anything it pushes.
 
+use crate::ast::{local_type_owner, Expr, Struct, Type};
 use crate::ast::{
     Block, Decl, DeclKind, Enum, ExprKind, File, FunctionBody, Impl, LambdaBody, Pattern,
     PatternKind, Stmt, StmtKind, Trait, TypeKind, TypePath, VariantPayload,
 };
-use crate::ast::{Expr, Struct, Type};
 use crate::error::{RavenError, ResolveError};
 use crate::span::Span;
 
@@ -255,8 +255,8 @@ fn walk_block(
     map: &mut ResolutionMap,
 ) -> Result<(), RavenError> {
     scope.push(ScopeKind::Block);
-    for stmt in &block.stmts {
-        walk_stmt(stmt, scope, map)?;
+    for (i, stmt) in block.stmts.iter().enumerate() {
+        walk_stmt(stmt, scope, map).map_err(|e| later_local_fun_hint(e, &block.stmts[i + 1..]))?;
     }
     if let Some(t) = &block.trailing {
         walk_expr(t, scope, map)?;
@@ -265,6 +265,27 @@ fn walk_block(
     Ok(())
 }
 
+/// Explain an unresolved name that a later statement of the same block
+/// declares as a local function. One still in the block uses the enclosing
+/// function's locals, so the parser left it a closure bound in order rather
+/// than hoisting it, and a use above the declaration (including one local
+/// function calling the next) cannot see it.
+fn later_local_fun_hint(e: RavenError, later: &[Stmt]) -> RavenError {
+    match &e {
+        RavenError::Resolve(ResolveError::UnresolvedName(n), _, None)
+            if later
+                .iter()
+                .any(|s| matches!(&s.kind, StmtKind::Fun { name, .. } if name == n)) =>
+        {
+            let hint = format!(
+                "local function `{n}` is declared later in this block and uses the enclosing function's locals, so it is only visible below its declaration; move it above this use"
+            );
+            e.with_hint(hint)
+        }
+        _ => e,
+    }
+}
+
 fn walk_stmt(
     stmt: &Stmt,
     scope: &mut ScopeStack,
@@ -285,6 +306,20 @@ fn walk_stmt(
             }
             scope.insert_shadowing(name, Binding::Local(stmt.span.clone()), stmt.span.clone());
         }
+        StmtKind::Fun { name, func } => {
+            // Bound after its body is walked, like a `let`: a local function
+            // the parser did not hoist captures a local, and is a closure
+            // value that cannot name itself.
+            walk_expr(func, scope, map).map_err(|e| match &e {
+                RavenError::Resolve(ResolveError::UnresolvedName(n), _, None) if n == name => {
+                    e.with_hint(format!(
+                        "a local function that uses the enclosing function's locals cannot call itself; pass them in as parameters, or declare `{name}` at top level"
+                    ))
+                }
+                _ => e,
+            })?;
+            scope.insert_shadowing(name, Binding::Local(stmt.span.clone()), stmt.span.clone());
+        }
         StmtKind::Return(e) => {
             if let Some(e) = e {
                 walk_expr(e, scope, map)?;
@@ -298,10 +333,29 @@ fn walk_stmt(
         StmtKind::Continue => {}
         StmtKind::Defer(e) | StmtKind::Spawn(e) => walk_expr(e, scope, map)?,
         StmtKind::Assign { target, value, .. } => {
+            // A closure holds a copy of each local it captures, so assigning
+            // one inside the closure would change only the copy and be lost.
+            // A list element or struct field is shared, so those stay legal.
+            if let ExprKind::Ident { name, .. } = &target.kind {
+                if !scope.is_def_site(&target.span) && scope.is_capture(name) {
+                    return Err(RavenError::resolve(
+                        ResolveError::Other(format!(
+                            "cannot assign to `{name}`, which this closure captures by value"
+                        )),
+                        target.span.clone(),
+                    )
+                    .with_hint(format!(
+                        "the assignment would change only the closure's copy; hold the value in a one-element list the closure shares and assign `{name}[0]` instead"
+                    )));
+                }
+            }
             walk_expr(target, scope, map)?;
             walk_expr(value, scope, map)?;
         }
         StmtKind::Expr(e) => walk_expr(e, scope, map)?,
+        // A local type only stays in place in formatter-parsed source; the
+        // compile pipeline sees it lifted to a top-level item.
+        StmtKind::TypeDecl(_) => {}
     }
     Ok(())
 }
@@ -318,6 +372,7 @@ fn walk_expr(
         | ExprKind::Str(_)
         | ExprKind::BlockStr(_)
         | ExprKind::Char(_)
+        | ExprKind::Unit
         // A macro call only appears in formatter-parsed source (the compile
         // pipeline expands macros first), so there are no names to resolve.
         | ExprKind::MacroCall(_)
@@ -385,10 +440,14 @@ fn walk_expr(
                     walk_type(g, scope, map)?;
                 }
             } else {
-                return Err(RavenError::resolve(
+                let err = RavenError::resolve(
                     ResolveError::UnresolvedName(name.clone()),
                     expr.span.clone(),
-                ));
+                );
+                return Err(match conversion_fn_spelling(name) {
+                    Some(hint) => err.with_hint(hint),
+                    None => with_local_type_hint(err, name, scope),
+                });
             }
         }
         ExprKind::StructLit {
@@ -399,10 +458,11 @@ fn walk_expr(
             // The struct name itself is a use site; if it isn't in
             // scope as a struct or import, raise UnresolvedName.
             let entry = scope.lookup(name).ok_or_else(|| {
-                RavenError::resolve(
+                let err = RavenError::resolve(
                     ResolveError::UnresolvedName(name.clone()),
                     expr.span.clone(),
-                )
+                );
+                with_local_type_hint(err, name, scope)
             })?;
             // The span attached to the StructLit covers the whole
             // literal, including the brace block. We record the
@@ -506,9 +566,16 @@ fn walk_expr(
             }
         }
         ExprKind::Loop(b) => walk_block(b, scope, map)?,
-        ExprKind::While { cond, body } => {
+        ExprKind::While {
+            cond,
+            body,
+            else_block,
+        } => {
             walk_expr(cond, scope, map)?;
             walk_block(body, scope, map)?;
+            if let Some(e) = else_block {
+                walk_block(e, scope, map)?;
+            }
         }
         ExprKind::For {
             pattern,
@@ -649,10 +716,16 @@ fn walk_type_path(
     }
 
     let entry = scope.lookup(&head.name).ok_or_else(|| {
-        RavenError::resolve(
+        let err = RavenError::resolve(
             ResolveError::UnresolvedName(head.name.clone()),
             head.span.clone(),
-        )
+        );
+        match primitive_type_spelling(&head.name) {
+            Some(raven) => err.with_hint(format!(
+                "the built-in type is spelled `{raven}`; type names are PascalCase"
+            )),
+            None => with_local_type_hint(err, &head.name, scope),
+        }
     })?;
     let binding = entry.binding.clone();
     // A module-qualified type name (`net.TcpStream`) is not supported: a module
@@ -681,6 +754,55 @@ fn walk_type_path(
     Ok(())
 }
 
+/// Add a hint to an unresolved `name` that is a struct or enum declared
+/// inside some function: such a type is only in scope in that function.
+fn with_local_type_hint(err: RavenError, name: &str, scope: &ScopeStack) -> RavenError {
+    let owner = scope.module_names().keys().find_map(|n| {
+        let (base, func) = local_type_owner(n)?;
+        (base == name).then_some(func)
+    });
+    match owner {
+        Some(func) => err.with_hint(format!(
+            "`{name}` is declared inside `{func}` and can only be used there; \
+             move it to the top level to share it"
+        )),
+        None => err,
+    }
+}
+
+/// The Raven name for a primitive type written the way another language
+/// (or Raven 1.x) spells it: `int`, `char`, `str`, `i64`, `boolean`.
+fn primitive_type_spelling(name: &str) -> Option<&'static str> {
+    Some(match name {
+        "int" | "integer" | "i64" | "long" => "Int",
+        "float" | "double" | "f64" => "Float",
+        "bool" | "boolean" => "Bool",
+        "string" | "str" => "String",
+        "char" | "rune" => "Char",
+        "unit" | "void" => "Unit",
+        _ => return None,
+    })
+}
+
+/// How Raven spells a conversion that other languages (and Raven 1.x) write
+/// as a free function: `to_int(s)`, `to_float(n)`, `to_string(x)`,
+/// `to_bool(v)`. Raven converts with methods and has no truthiness.
+fn conversion_fn_spelling(name: &str) -> Option<&'static str> {
+    Some(match name {
+        "to_int" => {
+            "convert with a method: `x.to_int()` truncates a `Float`, and `s.parse_int()` from std/string parses a `String` into an `Option<Int>`"
+        }
+        "to_float" => {
+            "convert with a method: `n.to_float()` widens an `Int`, and `s.parse_float()` from std/string parses a `String` into an `Option<Float>`"
+        }
+        "to_string" => "call the `ToString` method, `x.to_string()`, or interpolate: `\"${x}\"`",
+        "to_bool" => {
+            "Raven has no truthiness; compare explicitly, such as `n != 0` or `!s.is_empty()`"
+        }
+        _ => return None,
+    })
+}
+
 /// Builtin type names known to the resolver. These bypass scope
 /// lookup; the type checker assigns them their concrete meaning.
 fn is_builtin_type_name(name: &str) -> bool {
@@ -758,5 +880,6 @@ fn is_builtin_ctor_name(name: &str) -> bool {
             | "__str_substring"
             | "__str_from_byte"
             | "__str_concat"
+            | "__char_from_code"
     )
 }
@@ -44,6 +44,53 @@ impl Span {
     pub fn is_empty(&self) -> bool {
         self.end == self.start
     }
+
+    /// The synthetic source path for fragment `index` of the string
+    /// literal at `host`. An embedded `${...}` expression is lexed against
+    /// this path so its spans never collide with real source spans. The
+    /// path records the host span so [`Span::host`] can recover it.
+    pub fn interpolation_file(host: &Span, index: usize) -> PathBuf {
+        PathBuf::from(format!(
+            "{}<interp:{}:{}:{}:{}:{}>",
+            host.file.display(),
+            host.start,
+            host.end,
+            host.line,
+            host.col,
+            index
+        ))
+    }
+
+    /// The span a reader should see for this one. A span inside an
+    /// embedded `${...}` expression maps to the outermost string literal
+    /// that holds it, since its own offsets are relative to the snippet;
+    /// any other span is returned unchanged.
+    pub fn host(&self) -> Span {
+        let mut span = self.clone();
+        while let Some(host) = span.interpolation_host() {
+            span = host;
+        }
+        span
+    }
+
+    /// The enclosing literal's span when this span's file is an
+    /// [`Span::interpolation_file`].
+    fn interpolation_host(&self) -> Option<Span> {
+        let path = self.file.to_str()?.strip_suffix('>')?;
+        let (file, fields) = path.rsplit_once("<interp:")?;
+        let mut fields = fields.split(':').map(|f| f.parse::<usize>().ok());
+        let start = fields.next()??;
+        let end = fields.next()??;
+        let line = fields.next()??;
+        let col = fields.next()??;
+        Some(Span::new(
+            Arc::new(PathBuf::from(file)),
+            start,
+            end,
+            u32::try_from(line).ok()?,
+            u32::try_from(col).ok()?,
+        ))
+    }
 }
 
 impl fmt::Display for Span {
@@ -71,6 +118,19 @@ mod tests {
         assert!(p.is_empty());
     }
 
+    #[test]
+    fn interpolation_spans_map_to_the_outermost_literal() {
+        let literal = Span::new(file(), 40, 60, 3, 9);
+        let inner = Span::new(Arc::new(Span::interpolation_file(&literal, 0)), 2, 5, 1, 3);
+        assert_eq!(inner.host(), literal);
+
+        // A literal nested inside another fragment still maps to the outer one.
+        let nested = Span::new(Arc::new(Span::interpolation_file(&inner, 1)), 0, 1, 1, 1);
+        assert_eq!(nested.host(), literal);
+
+        assert_eq!(literal.host(), literal);
+    }
+
     #[test]
     fn span_display_formats_as_path_line_col() {
         let s = Span::new(file(), 0, 1, 5, 12);
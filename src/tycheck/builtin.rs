@@ -5,6 +5,9 @@
 //! of methods recognized by the method dispatcher.
 
 use super::ty::Ty;
+use crate::ast::{Expr, ExprKind, StrFragment};
+use crate::lexer::ESCAPED_DOLLAR_SENTINEL;
+use crate::span::Span;
 
 /// What a built in method needs the dispatcher to know.
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -182,6 +185,127 @@ pub fn lookup_method(receiver: &Ty, name: &str) -> Option<(Vec<Ty>, Ty)> {
     })
 }
 
+/// The byte offset and column of the cooked template index `at` in the
+/// source text after the literal's opening quote. Escapes make the source
+/// longer than the cooked text: a `"`, `\`, line break, or NUL can only have
+/// been written as a two-character escape, and `\$` cooks to the escaped
+/// dollar sentinel plus the `$`. Any other character is taken as written
+/// verbatim. If the widths that gives do not add up to the literal's span (a
+/// `\t`, `\u{...}`, or block string), the cooked index is used as is,
+/// which still lands inside the literal and differs per placeholder.
+fn source_position(template: &str, at: usize, template_span: &Span) -> (usize, u32) {
+    // Source (bytes, columns) of one cooked char.
+    let width = |c: char| match c {
+        '"' | '\\' | '\n' | '\r' | '\0' => (2, 2),
+        ESCAPED_DOLLAR_SENTINEL => (1, 1),
+        c => (c.len_utf8(), 1),
+    };
+    let source_len: usize = template.chars().map(|c| width(c).0).sum();
+    if source_len + 2 != template_span.end - template_span.start {
+        return (at, template[..at].chars().count() as u32);
+    }
+    template[..at]
+        .chars()
+        .map(width)
+        .fold((0, 0), |(bytes, cols), (b, c)| (bytes + b, cols + c))
+}
+
+/// Split the literal template of a `"...".format(args)` call into the
+/// fragments of the equivalent interpolated string: each `{}` takes the next
+/// argument, each `{:?}` takes the next argument's `debug()` rendering (the
+/// `Debug` trait from `std/fmt`), and `{{` / `}}` stand for a literal brace.
+/// Both the type checker and HIR lowering go through this, so the call
+/// checks and lowers exactly as the spelled out `"...${arg}..."` would. The
+/// error is the message for a malformed template or a placeholder count that
+/// does not match the arguments.
+///
+/// A synthesized `arg.debug()` call needs a span of its own, distinct from
+/// the argument's, because expression types are recorded by span. It gets
+/// the `{:?}` placeholder's offset inside `template_span`, which no argument
+/// can share.
+pub fn format_fragments(
+    template: &str,
+    template_span: &Span,
+    args: &[Expr],
+) -> Result<Vec<StrFragment>, String> {
+    let mut fragments = Vec::new();
+    let mut text = String::new();
+    let mut slots = 0;
+    let mut chars = template.char_indices().peekable();
+    while let Some((at, c)) = chars.next() {
+        match (c, chars.peek().map(|&(_, next)| next)) {
+            ('{', Some('{')) | ('}', Some('}')) => {
+                chars.next();
+                text.push(c);
+            }
+            ('{', Some('}')) => {
+                chars.next();
+                if !text.is_empty() {
+                    fragments.push(StrFragment::Literal(std::mem::take(&mut text)));
+                }
+                if let Some(arg) = args.get(slots) {
+                    fragments.push(StrFragment::Expr(Box::new(arg.clone())));
+                }
+                slots += 1;
+            }
+            ('{', Some(':')) => {
+                let spec = template[at..].split_inclusive('}').next().unwrap_or("{");
+                if spec != "{:?}" {
+                    return Err(format!(
+                        "unsupported format spec `{spec}`; a placeholder is `{{}}` or `{{:?}}`"
+                    ));
+                }
+                for _ in 1..spec.len() {
+                    chars.next();
+                }
+                if !text.is_empty() {
+                    fragments.push(StrFragment::Literal(std::mem::take(&mut text)));
+                }
+                if let Some(arg) = args.get(slots) {
+                    let (offset, col) = source_position(template, at, template_span);
+                    let start = template_span.start + 1 + offset;
+                    let span = Span::new(
+                        template_span.file.clone(),
+                        start,
+                        start + 4,
+                        template_span.line,
+                        template_span.col + 1 + col,
+                    );
+                    fragments.push(StrFragment::Expr(Box::new(Expr {
+                        kind: ExprKind::MethodCall {
+                            receiver: Box::new(arg.clone()),
+                            name: "debug".to_string(),
+                            generics: Vec::new(),
+                            args: Vec::new(),
+                        },
+                        span,
+                    })));
+                }
+                slots += 1;
+            }
+            ('{', _) | ('}', _) => {
+                return Err(format!(
+                    "unmatched `{c}` in format template; write `{c}{c}` for a literal brace"
+                ));
+            }
+            _ => text.push(c),
+        }
+    }
+    if slots != args.len() {
+        return Err(format!(
+            "format template has {} placeholder{} but {} argument{} given",
+            slots,
+            if slots == 1 { "" } else { "s" },
+            args.len(),
+            if args.len() == 1 { " was" } else { "s were" }
+        ));
+    }
+    if !text.is_empty() {
+        fragments.push(StrFragment::Literal(text));
+    }
+    Ok(fragments)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -216,6 +340,80 @@ mod tests {
         assert!(lookup_method(&opt, "no_such_method").is_none());
     }
 
+    fn test_span() -> crate::span::Span {
+        crate::span::Span::new(
+            std::sync::Arc::new(std::path::PathBuf::from("t.rv")),
+            0,
+            0,
+            1,
+            1,
+        )
+    }
+
+    fn int_arg(v: i64) -> Expr {
+        Expr {
+            kind: crate::ast::ExprKind::Int(v),
+            span: test_span(),
+        }
+    }
+
+    #[test]
+    fn format_template_splits_at_placeholders() {
+        let frags = format_fragments("a {} b {{x}} {}", &test_span(), &[int_arg(1), int_arg(2)])
+            .expect("valid");
+        assert_eq!(frags.len(), 4);
+        assert_eq!(frags[0], StrFragment::Literal("a ".into()));
+        assert!(
+            matches!(&frags[1], StrFragment::Expr(e) if e.kind == crate::ast::ExprKind::Int(1))
+        );
+        assert_eq!(frags[2], StrFragment::Literal(" b {x} ".into()));
+        assert!(
+            matches!(&frags[3], StrFragment::Expr(e) if e.kind == crate::ast::ExprKind::Int(2))
+        );
+    }
+
+    #[test]
+    fn format_template_rejects_bad_braces_and_arity() {
+        assert!(format_fragments("{} {}", &test_span(), &[int_arg(1)]).is_err());
+        assert!(format_fragments("none", &test_span(), &[int_arg(1)]).is_err());
+        assert!(format_fragments("open {", &test_span(), &[]).is_err());
+        assert!(format_fragments("close }", &test_span(), &[]).is_err());
+        assert!(format_fragments("{:#?}", &test_span(), &[int_arg(1)]).is_err());
+        assert!(format_fragments("{:?", &test_span(), &[int_arg(1)]).is_err());
+    }
+
+    #[test]
+    fn debug_placeholder_calls_debug_under_its_own_span() {
+        let frags = format_fragments("x = {:?}!", &test_span(), &[int_arg(7)]).expect("valid");
+        assert_eq!(frags.len(), 3);
+        let StrFragment::Expr(e) = &frags[1] else {
+            panic!("expected an expression fragment, got {:?}", frags[1]);
+        };
+        let crate::ast::ExprKind::MethodCall { receiver, name, .. } = &e.kind else {
+            panic!("expected a `debug()` call, got {:?}", e.kind);
+        };
+        assert_eq!(name, "debug");
+        assert_eq!(receiver.kind, crate::ast::ExprKind::Int(7));
+        assert_ne!(
+            (e.span.start, e.span.end),
+            (receiver.span.start, receiver.span.end)
+        );
+        assert_eq!(frags[2], StrFragment::Literal("!".into()));
+    }
+
+    #[test]
+    fn debug_placeholder_span_skips_escapes_before_it() {
+        // Source `"\"q\" {:?}"`: the two escapes put the placeholder two
+        // bytes further into the source than into the cooked template.
+        let mut span = test_span();
+        span.end = 12;
+        let frags = format_fragments("\"q\" {:?}", &span, &[int_arg(7)]).expect("valid");
+        let StrFragment::Expr(e) = &frags[1] else {
+            panic!("expected an expression fragment, got {:?}", frags[1]);
+        };
+        assert_eq!((e.span.start, e.span.end, e.span.col), (7, 11, 8));
+    }
+
     #[test]
     fn numeric_conversions() {
         let (p, r) = lookup_method(&Ty::Int, "to_float").expect("Int has to_float");
@@ -8,8 +8,8 @@
 use std::collections::HashMap;
 
 use crate::ast::{
-    DeclKind, Enum, Expr, ExprKind, Function, GenericParam, Impl, Struct, Trait, Type, TypeKind,
-    TypePath, UnaryOp, VariantPayload,
+    source_type_name, DeclKind, Enum, Expr, ExprKind, Function, GenericParam, Impl, Struct, Trait,
+    Type, TypeKind, TypePath, UnaryOp, VariantPayload,
 };
 use crate::error::{RavenError, TypeError};
 use crate::resolve::{Binding, DeclId, ResolvedFile};
@@ -420,13 +420,13 @@ fn repr_c_struct_layout(
         return Err(ty_custom(
             &format!(
                 "`@repr(C)` struct `{}` must have at least one field",
-                sig.name
+                source_type_name(&sig.name)
             ),
             span,
         ));
     }
     in_progress.push(id);
-    let name = sig.name.clone();
+    let name = source_type_name(&sig.name).to_string();
     let mut size: u32 = 0;
     let mut max_align: u32 = 1;
     for f in &sig.fields {
@@ -446,7 +446,9 @@ fn repr_c_struct_layout(
                     return Err(ty_custom(
                         &format!(
                             "field `{}` of `@repr(C)` struct `{}` is `{}`, which is not `@repr(C)`; a struct field must itself be `@repr(C)` to cross the FFI by value",
-                            f.name, name, nested_name
+                            f.name,
+                            name,
+                            source_type_name(nested_name)
                         ),
                         &f.span,
                     ));
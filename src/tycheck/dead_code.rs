@@ -0,0 +1,101 @@
+//! Dead code detection for top level functions.
+//!
+//! After the body pass has checked every call, a function in the entry file
+//! that `main` cannot reach is dead: no chain of calls or function values
+//! starting in `main` names it. Functions that only call each other are dead
+//! together. Each one produces a [`Warning::DeadCode`] at its declaration.
+//! The resolver already binds every identifier use to its declaration, so the
+//! call graph is read straight off the [`ResolutionMap`] rather than tracked
+//! again during checking: a use belongs to the innermost top level function
+//! whose span holds it.
+//!
+//! The pass stays quiet where a function may be used from elsewhere:
+//!
+//! * `main` is the program entry, called by the runtime.
+//! * An entry file with no `main` is a library (`rvpm build` checks one with
+//!   [`crate::driver::check`]); its functions exist for importers.
+//! * Functions merged from stdlib, local, and external modules carry their own
+//!   file in their span and are skipped; only the entry file is linted.
+//! * Impl and trait methods are dispatched by receiver type, not by name, so
+//!   the graph does not follow them: a function named in a method body, a
+//!   global initializer, or anywhere else outside a top level function is
+//!   live.
+//! * A local function is not a top level function of the source, even when
+//!   the parser hoists it to one.
+//!
+//! A function marked `@allow(dead_code)` is never reported, and the functions
+//! it reaches are live.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::ast::{local_type_owner, DeclKind, Function};
+use crate::error::Warning;
+use crate::resolve::{Binding, DeclId, ResolvedFile, UseKey};
+
+/// Report every top level function in the entry file that `main` cannot
+/// reach.
+pub fn check_dead_code(resolved: &ResolvedFile<'_>) -> Vec<Warning> {
+    let file = resolved.file;
+    let entry = &file.span.file;
+    let functions: Vec<(DeclId, &Function)> = file
+        .items
+        .iter()
+        .enumerate()
+        .filter(|(_, decl)| decl.span.file == *entry)
+        .filter_map(|(idx, decl)| match &decl.kind {
+            DeclKind::Function(f) => Some((DeclId(idx), f)),
+            _ => None,
+        })
+        .collect();
+    if !functions.iter().any(|(_, f)| f.name == "main") {
+        return Vec::new();
+    }
+
+    let mut live: Vec<DeclId> = functions
+        .iter()
+        .filter(|(_, f)| f.name == "main" || f.allows.iter().any(|a| a == "dead_code"))
+        .map(|(id, _)| *id)
+        .collect();
+
+    // A use inside the function's own body (recursion) is not an edge.
+    let mut calls: HashMap<DeclId, Vec<DeclId>> = HashMap::new();
+    for (key, binding) in &resolved.map.uses {
+        let Binding::Function(callee) = binding else {
+            continue;
+        };
+        let caller = functions
+            .iter()
+            .map(|(id, _)| *id)
+            .filter(|id| within(key, &file.items[id.0].span))
+            .min_by_key(|id| {
+                let span = &file.items[id.0].span;
+                span.end - span.start
+            });
+        match caller {
+            Some(caller) if caller == *callee => {}
+            Some(caller) => calls.entry(caller).or_default().push(*callee),
+            None => live.push(*callee),
+        }
+    }
+
+    let mut reached = HashSet::new();
+    while let Some(id) = live.pop() {
+        if reached.insert(id) {
+            live.extend(calls.get(&id).into_iter().flatten().copied());
+        }
+    }
+
+    functions
+        .iter()
+        .filter(|(id, f)| !reached.contains(id) && local_type_owner(&f.name).is_none())
+        .map(|(id, f)| Warning::DeadCode {
+            name: f.name.clone(),
+            span: file.items[id.0].span.clone(),
+        })
+        .collect()
+}
+
+/// Whether the use site `key` lies inside `span`.
+fn within(key: &UseKey, span: &crate::span::Span) -> bool {
+    key.file == span.file && key.start >= span.start && key.end <= span.end
+}
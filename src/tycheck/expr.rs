@@ -12,8 +12,8 @@
 use std::collections::HashMap;
 
 use crate::ast::{
-    AssignOp, BinaryOp, Block, Decl, DeclKind, ElseBranch, Expr, ExprKind, FieldInit, Function,
-    FunctionBody, LambdaBody, Stmt, StmtKind, StrFragment, UnaryOp,
+    source_type_name, AssignOp, BinaryOp, Block, Decl, DeclKind, ElseBranch, Expr, ExprKind,
+    FieldInit, Function, FunctionBody, LambdaBody, Stmt, StmtKind, StrFragment, UnaryOp,
 };
 use crate::error::{RavenError, TypeError};
 use crate::resolve::{Binding, ResolvedFile, UseKey};
@@ -295,6 +295,11 @@ struct Checker<'a, 'b> {
     /// binding's declared `List<T>` type while its initializer is checked.
     /// An empty `[]` has no element to infer from, so it adopts this hint.
     array_hint: Option<Ty>,
+    /// Function type hint for a shorthand `{ x -> ... }` lambda, set from a
+    /// `let` binding's declared `fun(...) -> T` type when the initializer is
+    /// the lambda itself. The lambda takes its parameter and return types
+    /// from it, since the shorthand form annotates neither.
+    lambda_hint: Option<Ty>,
     /// Accumulated diagnostics for this body. Statement and item checking
     /// recover at their boundaries (binding a failed value to `Ty::Error`)
     /// and push the error here instead of returning it, so one compile can
@@ -308,11 +313,12 @@ struct Checker<'a, 'b> {
     /// body's variable against its own (foreign) inference context.
     recorded: Vec<UseKey>,
     /// Stack of enclosing loops, one entry per `loop`/`while`/`for` currently
-    /// being checked; `true` for a value-producing `loop`, `false` for
-    /// `while`/`for`. Empty means `break`/`continue` here is outside any loop.
-    /// Reset to empty when checking a lambda body, since a loop does not extend
-    /// across a nested function.
-    loop_kinds: Vec<bool>,
+    /// being checked. A value-producing `loop` carries the type its `break`s
+    /// agree on (an inference variable until the first `break` pins it);
+    /// `while`/`for` carry `None`. Empty means `break`/`continue` here is
+    /// outside any loop. Reset to empty when checking a lambda body, since a
+    /// loop does not extend across a nested function.
+    loop_kinds: Vec<Option<Ty>>,
 }
 
 /// Keys used by the locals map. Mirrors the resolver's `Binding`
@@ -374,6 +380,7 @@ impl<'a, 'b> Checker<'a, 'b> {
             param_bounds: HashMap::new(),
             infer,
             array_hint: None,
+            lambda_hint: None,
             errors: Vec::new(),
             const_locals: std::collections::HashSet::new(),
             recorded: Vec::new(),
@@ -554,6 +561,10 @@ impl<'a, 'b> Checker<'a, 'b> {
             // The float C FFI types render through the `Float` path the same
             // way (`CFloat` widens f32 to f64 first).
             t if is_int_ffi(t) || is_float_ffi(t) => Ok(()),
+            // The prelude's `impl<T: ToString> ToString for List<T>` is
+            // generic, so the exact-type impl lookup below cannot see it;
+            // a list prints when its element type does.
+            Ty::List(elem) => self.require_to_string(elem, span),
             Ty::Var(v) => {
                 self.infer
                     .add_bound(*v, "ToString".to_string(), span.clone());
@@ -817,8 +828,21 @@ impl<'a, 'b> Checker<'a, 'b> {
                 if let Some(Ty::List(elem)) = &declared {
                     self.array_hint = Some((**elem).clone());
                 }
+                // Likewise a shorthand lambda initializer adopts a declared
+                // function type for its unannotated parameters.
+                if let (
+                    Some(f @ Ty::Function { .. }),
+                    Some(Expr {
+                        kind: ExprKind::Lambda { .. },
+                        ..
+                    }),
+                ) = (&declared, init)
+                {
+                    self.lambda_hint = Some(f.clone());
+                }
                 let init_ty = init.as_ref().map(|e| self.check_expr_recover(e));
                 self.array_hint = prev_hint;
+                self.lambda_hint = None;
                 let final_ty = match (declared, init_ty) {
                     (Some(d), Some(i)) => {
                         self.unify_recover(&d, &i, &init.as_ref().unwrap().span);
@@ -836,16 +860,56 @@ impl<'a, 'b> Checker<'a, 'b> {
                     .insert(BindingKey::local(&stmt.span), final_ty.clone());
                 self.record(&stmt.span, final_ty);
             }
-            StmtKind::Return(e) => {
-                let actual = match e {
-                    Some(expr) => self.check_expr_recover(expr),
-                    None => Ty::Unit,
-                };
+            // A local function binds like a `const` holding its closure.
+            StmtKind::Fun { name: _, func } => {
+                self.const_locals.insert(BindingKey::local(&stmt.span));
+                let ty = self.check_expr_recover(func);
+                self.locals
+                    .insert(BindingKey::local(&stmt.span), ty.clone());
+                self.record(&stmt.span, ty);
+            }
+            StmtKind::Return(Some(expr)) => {
+                let actual = self.check_expr_recover(expr);
                 let ret = self.return_ty.clone();
                 self.unify_recover(&ret, &actual, &stmt.span);
             }
+            StmtKind::Return(None) => {
+                // A bare `return` yields `()`, so only a function with no
+                // return type accepts it. Name the missing value instead of
+                // reporting a `()` mismatch.
+                let ret = self.return_ty.clone();
+                if let Err(e) = self.unify(&ret, &Ty::Unit, &stmt.span) {
+                    let expected = match &e {
+                        RavenError::Type(te, _, _) => match &**te {
+                            TypeError::TypeMismatch { expected, .. } => Some(expected.clone()),
+                            _ => None,
+                        },
+                        _ => None,
+                    };
+                    match expected {
+                        Some(expected) => self.push_error(
+                            RavenError::ty(
+                                TypeError::Custom(format!(
+                                    "bare `return` in a function that returns `{}`",
+                                    expected
+                                )),
+                                stmt.span.clone(),
+                            )
+                            .with_hint(format!(
+                                "return a value of type `{}`: `return <expr>`",
+                                expected
+                            )),
+                        ),
+                        None => self.push_error(e),
+                    }
+                }
+            }
             StmtKind::Break(e) => {
-                match self.loop_kinds.last() {
+                let actual = match e {
+                    Some(expr) => self.check_expr_recover(expr),
+                    None => Ty::Unit,
+                };
+                match self.loop_kinds.last().cloned() {
                     None => self.errors.push(RavenError::ty(
                         TypeError::Custom("`break` is only valid inside a loop".to_string()),
                         stmt.span.clone(),
@@ -853,17 +917,17 @@ impl<'a, 'b> Checker<'a, 'b> {
                     // A value carried by `break` is only meaningful in a `loop`,
                     // which yields it; a `while`/`for` produces no value, so the
                     // operand would be silently dropped by lowering.
-                    Some(false) if e.is_some() => self.errors.push(RavenError::ty(
+                    Some(None) if e.is_some() => self.errors.push(RavenError::ty(
                         TypeError::Custom(
                             "`break` with a value is only valid inside a `loop`, not a `while` or `for`"
                                 .to_string(),
                         ),
                         stmt.span.clone(),
                     )),
-                    _ => {}
-                }
-                if let Some(expr) = e {
-                    self.check_expr_recover(expr);
+                    Some(None) => {}
+                    // Every `break` out of one `loop` must agree on the value
+                    // it yields; a bare `break` yields `()`.
+                    Some(Some(break_ty)) => self.unify_recover(&break_ty, &actual, &stmt.span),
                 }
             }
             StmtKind::Continue => {
@@ -887,19 +951,24 @@ impl<'a, 'b> Checker<'a, 'b> {
                 self.unify_recover(&expected, &ty, &e.span);
             }
             StmtKind::Assign { target, op, value } => {
-                // Reassigning a `const` local is rejected: the binding is
-                // immutable. Only a direct `name = ...` is guarded here.
+                // Reassigning a `const` local or a function is rejected: the
+                // binding is immutable. Only a direct `name = ...` is guarded
+                // here.
                 if let ExprKind::Ident { name, .. } = &target.kind {
-                    let is_const = matches!(
-                        self.resolved.map.lookup(&target.span),
+                    let immutable = match self.resolved.map.lookup(&target.span) {
                         Some(crate::resolve::Binding::Local(decl))
-                            if self.const_locals.contains(&BindingKey::local(decl))
-                    );
-                    if is_const {
+                            if self.const_locals.contains(&BindingKey::local(decl)) =>
+                        {
+                            Some("a `const` binding")
+                        }
+                        Some(crate::resolve::Binding::Function(_)) => Some("a function"),
+                        _ => None,
+                    };
+                    if let Some(what) = immutable {
                         self.push_error(RavenError::ty(
                             TypeError::Custom(format!(
-                                "cannot assign to `{}` because it is a `const` binding",
-                                name
+                                "cannot assign to `{}` because it is {what}",
+                                source_type_name(name)
                             )),
                             target.span.clone(),
                         ));
@@ -907,6 +976,17 @@ impl<'a, 'b> Checker<'a, 'b> {
                 }
                 let target_ty = self.check_expr_recover(target);
                 let value_ty = self.check_expr_recover(value);
+                // Strings are immutable: `s[i]` reads a character but cannot
+                // be assigned through.
+                if let ExprKind::Index { receiver, .. } = &target.kind {
+                    let recv_ty = self.types.types.get(&UseKey::from_span(&receiver.span));
+                    if matches!(recv_ty.map(|t| self.infer.resolve(t)), Some(Ty::Str)) {
+                        self.push_error(RavenError::ty(
+                            TypeError::Custom("cannot assign to a character of a `String`".into()),
+                            target.span.clone(),
+                        ).with_hint("strings are immutable; build a new one, for example with `substring` and interpolation"));
+                    }
+                }
                 match op {
                     AssignOp::Assign => {
                         self.unify_recover(&target_ty, &value_ty, &value.span);
@@ -927,6 +1007,9 @@ impl<'a, 'b> Checker<'a, 'b> {
             StmtKind::Expr(e) => {
                 self.check_expr_recover(e);
             }
+            // Lifted to a top-level item by the parser; only the formatter's
+            // parse keeps it here.
+            StmtKind::TypeDecl(_) => {}
         }
     }
 
@@ -961,6 +1044,7 @@ impl<'a, 'b> Checker<'a, 'b> {
                 .clone()
                 .map(|t| Ty::SelfTy(Box::new(t)))
                 .unwrap_or(Ty::Error)),
+            ExprKind::Unit => Ok(Ty::Unit),
             ExprKind::Paren(inner) => self.check_expr(inner),
             ExprKind::Ident { name, generics } => self.check_ident(name, generics, &expr.span),
             ExprKind::Array(items) => self.check_array(items, &expr.span),
@@ -980,6 +1064,15 @@ impl<'a, 'b> Checker<'a, 'b> {
                 // solved type when one is available.
                 let l = self.infer.resolve(&l);
                 let r = self.infer.resolve(&r);
+                // `==` and `!=` need both sides at one type, so a side
+                // still holding a variable (a bare `None` or `Ok(1)`
+                // against a typed value) takes the other side's type.
+                // A failed unify falls through to the mismatch below.
+                if matches!(op, BinaryOp::Eq | BinaryOp::Ne) && (l.has_var() || r.has_var()) {
+                    let _ = self.infer.unify(&l, &r, &expr.span);
+                }
+                let l = self.infer.resolve(&l);
+                let r = self.infer.resolve(&r);
                 check_binary(&l, &r, *op, &expr.span)
             }
             ExprKind::Range { start, end, .. } => {
@@ -1062,19 +1155,36 @@ impl<'a, 'b> Checker<'a, 'b> {
             } => self.check_if(cond, then_branch, else_branch.as_deref(), &expr.span),
             ExprKind::Match { scrutinee, arms } => self.check_match(scrutinee, arms, &expr.span),
             ExprKind::Loop(b) => {
-                self.loop_kinds.push(true);
+                let break_ty = Ty::Var(self.infer.fresh(expr.span.clone()));
+                self.loop_kinds.push(Some(break_ty.clone()));
                 let r = self.check_block(b);
                 self.loop_kinds.pop();
                 r?;
-                Ok(Ty::Unit)
+                // A `loop` yields whatever its `break`s carry. One with no
+                // `break` at all never finishes normally; type it `()`.
+                let break_ty = self.infer.resolve(&break_ty);
+                if let Ty::Var(_) = break_ty {
+                    self.unify(&Ty::Unit, &break_ty, &expr.span)?;
+                    return Ok(Ty::Unit);
+                }
+                Ok(break_ty)
             }
-            ExprKind::While { cond, body } => {
+            ExprKind::While {
+                cond,
+                body,
+                else_block,
+            } => {
                 let c = self.check_expr(cond)?;
                 self.unify(&Ty::Bool, &c, &cond.span)?;
-                self.loop_kinds.push(false);
+                self.loop_kinds.push(None);
                 let r = self.check_block(body);
                 self.loop_kinds.pop();
                 r?;
+                // The `else` block runs outside the loop, so a `break` in it
+                // targets an enclosing loop, not this one.
+                if let Some(e) = else_block {
+                    self.check_block(e)?;
+                }
                 Ok(Ty::Unit)
             }
             ExprKind::For {
@@ -1127,7 +1237,7 @@ impl<'a, 'b> Checker<'a, 'b> {
                 // method resolution (used by the iterator-driven path).
                 self.record(&pat.span, elem.clone());
                 pattern::bind(pat, &elem, self.env, &mut self.locals)?;
-                self.loop_kinds.push(false);
+                self.loop_kinds.push(None);
                 let r = self.check_block(body);
                 self.loop_kinds.pop();
                 r?;
@@ -1183,10 +1293,18 @@ impl<'a, 'b> Checker<'a, 'b> {
             }
             self.type_of_binding(&binding, span, &explicit_args)
         } else {
-            Err(RavenError::ty(
+            let err = RavenError::ty(
                 TypeError::Custom(format!("identifier `{}` has no type binding", name)),
                 span.clone(),
-            ))
+            );
+            // `print x` is the statement form older Raven accepted. There is
+            // only the builtin function now, so point at the call syntax.
+            if name == "print" {
+                return Err(
+                    err.with_hint("`print` is a function, not a statement: write `print(value)`")
+                );
+            }
+            Err(err)
         }
     }
 
@@ -1478,6 +1596,10 @@ impl<'a, 'b> Checker<'a, 'b> {
                 self.unify(&Ty::Bool, &t, &operand.span)?;
                 Ok(Ty::Bool)
             }
+            UnaryOp::BitNot => {
+                self.unify(&Ty::Int, &t, &operand.span)?;
+                Ok(Ty::Int)
+            }
             // Raven has no reference or pointer type, so there is nothing for an
             // address-of to produce. Accepting it silently (returning the operand
             // unchanged) made `&x` a misleading no-op, so reject it instead.
@@ -1489,6 +1611,9 @@ impl<'a, 'b> Checker<'a, 'b> {
                 ),
                 operand.span.clone(),
             )),
+            // Any type has a name. The operand's type is already recorded at
+            // its span, which HIR lowering reads to build the name.
+            UnaryOp::TypeOf => Ok(Ty::Str),
         }
     }
 
@@ -1574,32 +1699,17 @@ impl<'a, 'b> Checker<'a, 'b> {
         );
         for (param_ty, arg) in params.iter().zip(args.iter()) {
             let a = self.check_expr(arg)?;
-            // An integer C FFI parameter (`CInt`, `CLong`, `CSize`)
-            // accepts a native `Int`, so an integer literal or expression
-            // can be passed to a C function (for example `abs(-7)`). The
-            // back end converts the i64 to the parameter's machine width
-            // at the call. A `c"..."` literal is already typed `CStr`, so
-            // it unifies directly with a `CStr` parameter. Any other
-            // mismatch falls through to the normal unify diagnostic.
-            let resolved_param = self.infer.resolve(param_ty);
-            let resolved_arg = self.infer.resolve(&a);
-            if is_int_ffi(resolved_param.strip_self())
-                && matches!(resolved_arg.strip_self(), Ty::Int)
-            {
-                continue;
-            }
-            // A `CDouble` parameter is C `double` (f64), the same
-            // representation a Raven `Float` uses, so a `Float` argument
-            // passes directly with no conversion at the call. A `CFloat`
-            // parameter is C `float` (f32); a `Float` argument is accepted
-            // and the back end narrows it to f32 at the call boundary.
-            if matches!(
-                resolved_param.strip_self(),
-                Ty::Ffi(FfiTy::CDouble) | Ty::Ffi(FfiTy::CFloat)
-            ) && matches!(resolved_arg.strip_self(), Ty::Float)
-            {
+            // A native `Int` or `Float` passes to a C scalar parameter, so
+            // an integer literal or expression can be passed to a C
+            // function (for example `abs(-7)`). A `c"..."` literal is
+            // already typed `CStr`, so it unifies directly with a `CStr`
+            // parameter. Any other mismatch falls through to the normal
+            // unify diagnostic.
+            if self.ffi_scalar_coerces(param_ty, &a) {
                 continue;
             }
+            let resolved_param = self.infer.resolve(param_ty);
+            let resolved_arg = self.infer.resolve(&a);
             // A `CFnPtr` parameter accepts a non-capturing top-level Raven
             // function whose parameters and return are all C-FFI types. The
             // function is passed as its C-ABI address; the signature match
@@ -1628,7 +1738,8 @@ impl<'a, 'b> Checker<'a, 'b> {
                 if callee_is_extern && !is_repr_c {
                     return Err(ty_custom(
                         &format!(
-                            "struct `{name}` is passed to a C function but is not `@repr(C)`; mark it `@repr(C)` to cross the FFI by value"
+                            "struct `{}` is passed to a C function but is not `@repr(C)`; mark it `@repr(C)` to cross the FFI by value",
+                            source_type_name(name)
                         ),
                         &arg.span,
                     ));
@@ -1639,6 +1750,24 @@ impl<'a, 'b> Checker<'a, 'b> {
         Ok(ret)
     }
 
+    /// Whether a native scalar argument of type `arg` passes to a C
+    /// parameter of type `param` without unifying. An integer C FFI
+    /// parameter (`CInt`, `CLong`, `CSize`) accepts a native `Int`, and the
+    /// back end converts the i64 to the parameter's machine width at the
+    /// call. A `CDouble` parameter is C `double` (f64), the same
+    /// representation a Raven `Float` uses, so a `Float` passes directly; a
+    /// `CFloat` parameter is C `float` (f32) and the back end narrows a
+    /// `Float` to f32 at the call boundary.
+    fn ffi_scalar_coerces(&mut self, param: &Ty, arg: &Ty) -> bool {
+        let param = self.infer.resolve(param);
+        let arg = self.infer.resolve(arg);
+        match (param.strip_self(), arg.strip_self()) {
+            (p, Ty::Int) => is_int_ffi(p),
+            (Ty::Ffi(FfiTy::CDouble) | Ty::Ffi(FfiTy::CFloat), Ty::Float) => true,
+            _ => false,
+        }
+    }
+
     /// Validate an argument passed where a `CFnPtr` is expected. The
     /// argument must be a bare name of a non-capturing top-level function
     /// (a resolver `Function` binding) whose parameters and return are all
@@ -1876,6 +2005,12 @@ impl<'a, 'b> Checker<'a, 'b> {
                 self.unify(&Ty::Str, &b, &args[1].span)?;
                 Ok(Ty::Str)
             }
+            "__char_from_code" => {
+                self.check_intrinsic_arity(name, args, 1, span)?;
+                let c = self.check_expr(&args[0])?;
+                self.unify(&Ty::Int, &c, &args[0].span)?;
+                Ok(Ty::Char)
+            }
             other => Err(RavenError::ty(
                 TypeError::Custom(format!("identifier `{}` has no type binding", other)),
                 span.clone(),
@@ -2303,6 +2438,8 @@ impl<'a, 'b> Checker<'a, 'b> {
         let impls_snapshot = self.env.impls.clone();
         let mut inherent_matches: Vec<(usize, FnSig, HashMap<ParamId, Ty>)> = Vec::new();
         let mut trait_matches: Vec<(usize, FnSig, HashMap<ParamId, Ty>, String)> = Vec::new();
+        let mut unmet_bound: Option<(String, String)> = None;
+        let mut narrower_self: Option<String> = None;
         for (idx, imp) in impls_snapshot.iter().enumerate() {
             // Skip impls without this method before allocating inference
             // variables or unifying. A rejected impl whose self type still
@@ -2324,7 +2461,23 @@ impl<'a, 'b> Checker<'a, 'b> {
             let impl_self = substitute(&imp.self_ty, &subst);
             // Try unifying receiver with this impl's self type.
             let probe = self.infer.unify(&impl_self, &recv_stripped, span);
-            if probe.is_err() {
+            if let Err(e) = probe {
+                // Remember an impl that has the method but whose bound the
+                // receiver misses (`sort` on a `List` of a type without
+                // `Ord`), so a "no method" error can name the bound.
+                if let RavenError::Type(te, _, _) = &e {
+                    if let TypeError::BoundNotSatisfied { ty, trait_name } = &**te {
+                        unmet_bound.get_or_insert((ty.clone(), trait_name.clone()));
+                    }
+                }
+                // Likewise an inherent impl on a narrower shape of the same
+                // type (`flatten` on `List<List<T>>`, called on `List<Int>`).
+                if imp.trait_name.is_none()
+                    && std::mem::discriminant(&imp.self_ty)
+                        == std::mem::discriminant(&recv_stripped)
+                {
+                    narrower_self.get_or_insert(format!("{}", imp.self_ty));
+                }
                 continue;
             }
             if imp.trait_name.is_some() {
@@ -2345,6 +2498,9 @@ impl<'a, 'b> Checker<'a, 'b> {
             // built in fast path methods (Option/Result/List/String).
             // These match directly against the resolved receiver shape;
             // their signatures already substitute the element type.
+            if name == "format" && recv_stripped == Ty::Str {
+                return self.check_string_format(receiver, args, span);
+            }
             if let Some((params, ret)) = builtin::lookup_method(&recv_stripped, name) {
                 if params.len() != args.len() {
                     return Err(RavenError::ty(
@@ -2362,13 +2518,52 @@ impl<'a, 'b> Checker<'a, 'b> {
                 }
                 return Ok(ret);
             }
-            return Err(RavenError::ty(
+            let err = RavenError::ty(
                 TypeError::UndefinedMethod {
                     receiver_ty: format!("{}", recv_stripped),
                     method: name.to_string(),
                 },
                 span.clone(),
-            ));
+            );
+            // `op.apply(x)` where `apply` is a field holding a function: a
+            // method call only looks in `impl`s, so point at the call form
+            // that reads the field first.
+            if let Ty::Struct { id, .. } = &recv_stripped {
+                let holds_fn =
+                    self.env.structs.get(id).is_some_and(|sig| {
+                        matches!(sig.field(name), Some((_, Ty::Function { .. })))
+                    });
+                if holds_fn {
+                    let recv_text = match &receiver.kind {
+                        ExprKind::Ident { name, .. } => name.as_str(),
+                        ExprKind::SelfLower => "self",
+                        _ => "value",
+                    };
+                    return Err(err.with_hint(format!(
+                        "`{name}` is a field holding a function; wrap the field access in parentheses to call it: `({recv_text}.{name})(...)`"
+                    )));
+                }
+            }
+            if let Some((ty, trait_name)) = unmet_bound {
+                return Err(err.with_hint(format!(
+                    "`{name}` is only available when `{ty}` implements `{trait_name}`"
+                )));
+            }
+            if let Some(self_ty) = narrower_self {
+                return Err(err.with_hint(format!("`{name}` is only defined on `{self_ty}`")));
+            }
+            // `s.trim()` without `import std/string`: the method is in a
+            // module the program has not imported.
+            if matches!(recv_stripped, Ty::Str | Ty::Int | Ty::Float) {
+                let self_ty = format!("{}", recv_stripped);
+                if let Some(module) = crate::resolve::stdlib::module_defining_method(&self_ty, name)
+                {
+                    return Err(err.with_hint(format!(
+                        "`{name}` is a `{self_ty}` method from std/{module}: add `import std/{module}`"
+                    )));
+                }
+            }
+            return Err(err);
         }
         // Resolve explicit method type arguments (`recv.method<T>(...)`) once,
         // to bind the method's own generic parameters at the call site.
@@ -2407,6 +2602,45 @@ impl<'a, 'b> Checker<'a, 'b> {
         ))
     }
 
+    /// Reject `alias.NAME` read as a value through a whole-module import
+    /// (`math.PI` after `import std/math`). Only calls are reached through
+    /// the alias, so point at the selector import that binds the name.
+    fn reject_module_qualified_value(
+        &self,
+        receiver: &Expr,
+        name: &str,
+        span: &Span,
+    ) -> Result<(), RavenError> {
+        let ExprKind::Ident {
+            name: alias,
+            generics,
+        } = &receiver.kind
+        else {
+            return Ok(());
+        };
+        if !generics.is_empty() {
+            return Ok(());
+        }
+        let Some(Binding::ImportAlias(import_id)) = self.resolved.map.lookup(&receiver.span) else {
+            return Ok(());
+        };
+        let Some(import) = self.resolved.map.imports.get(import_id.0) else {
+            return Ok(());
+        };
+        let source = if import.path.starts_with("std/") {
+            import.path.clone()
+        } else {
+            format!("\"{}\"", import.path)
+        };
+        Err(ty_custom(
+            &format!("`{alias}.{name}` is not supported; only a function call can go through a module name"),
+            span,
+        )
+        .with_hint(format!(
+            "import `{name}` by name: `import {source} {{ {name} }}`"
+        )))
+    }
+
     /// If `receiver` is a bare reference to a type name (a struct or enum
     /// binding, or a built-in type identifier), resolve it to that type.
     /// This marks the call as an associated function call. A value
@@ -2462,13 +2696,22 @@ impl<'a, 'b> Checker<'a, 'b> {
             .iter()
             .find(|(_, s)| s.name == mangled)
             .map(|(d, _)| *d);
-        let Some(decl) = decl else {
-            return Err(ty_custom(
-                &format!("module `{}` has no function `{}`", alias, name),
-                span,
-            ));
+        // An `extern "C"` function keeps its bare C name when its module is
+        // merged, so `math.sqrt(x)` reaches std/math's extern `sqrt` here.
+        let binding = match decl {
+            Some(decl) => Binding::Function(decl),
+            None => match self.resolved.module_extern(import, name) {
+                Some(binding) => binding,
+                None => {
+                    return Err(ty_custom(
+                        &format!("module `{}` has no function `{}`", alias, name),
+                        span,
+                    ));
+                }
+            },
         };
-        let fn_ty = self.type_of_binding(&Binding::Function(decl), span, &[])?;
+        let is_extern = matches!(binding, Binding::Extern { .. });
+        let fn_ty = self.type_of_binding(&binding, span, &[])?;
         let Ty::Function { params, ret } = fn_ty else {
             return Ok(None);
         };
@@ -2484,6 +2727,9 @@ impl<'a, 'b> Checker<'a, 'b> {
         }
         for (param_ty, arg) in params.iter().zip(args.iter()) {
             let a = self.check_expr(arg)?;
+            if is_extern && self.ffi_scalar_coerces(param_ty, &a) {
+                continue;
+            }
             self.unify(param_ty, &a, &arg.span)?;
         }
         Ok(Some(*ret))
@@ -2891,6 +3137,7 @@ impl<'a, 'b> Checker<'a, 'b> {
         if let Some(ty) = self.try_enum_variant_ctor(receiver, name, span)? {
             return Ok(ty);
         }
+        self.reject_module_qualified_value(receiver, name, span)?;
         let recv = self.check_expr(receiver)?;
         let recv_resolved = self.infer.resolve(&recv);
         let stripped = recv_resolved.strip_self().clone();
@@ -3128,55 +3375,102 @@ impl<'a, 'b> Checker<'a, 'b> {
         ret: Option<&crate::ast::Type>,
         body: &LambdaBody,
         params_inferred: bool,
-        _span: &Span,
+        span: &Span,
     ) -> Result<Ty, RavenError> {
-        // Lambdas require parameter type annotations in this release;
-        // shorthand `{ x, y -> body }` syntax is parsed but not typed.
-        if params_inferred {
-            return Err(RavenError::ty(
-                TypeError::Custom(
-                    "shorthand lambdas without parameter annotations require a context type; \
-                     full inference lands with issue #59"
-                        .into(),
-                ),
-                _span.clone(),
-            ));
-        }
+        // A shorthand `{ x, y -> body }` lambda annotates nothing, so it is
+        // typed only against a context type: the declared type of the `let`
+        // it initializes. Taking the hint here keeps it from reaching a
+        // lambda nested in the body.
+        let hint = self.lambda_hint.take();
+        let mut hinted_ret = None;
         let mut param_tys = Vec::with_capacity(params.len());
-        for p in params {
-            let t = match &p.ty {
-                Some(t) => self.resolve_ast_ty(t)?,
-                None => {
-                    return Err(RavenError::ty(
-                        TypeError::Custom(format!(
-                            "lambda parameter `{}` needs a type annotation",
-                            p.name
-                        )),
-                        p.span.clone(),
-                    ));
-                }
+        if params_inferred {
+            let Some(Ty::Function {
+                params: hint_params,
+                ret: hint_ret,
+            }) = hint
+            else {
+                return Err(RavenError::ty(
+                    TypeError::Custom(
+                        "shorthand lambdas without parameter annotations require a context type; \
+                         full inference lands with issue #59"
+                            .into(),
+                    ),
+                    span.clone(),
+                )
+                .with_hint(
+                    "annotate the binding, as in `let f: fun(Int) -> Int = { x -> x + 1 }`",
+                ));
             };
-            self.locals.insert(BindingKey::param(&p.span), t.clone());
-            param_tys.push(t);
+            if hint_params.len() != params.len() {
+                return Err(RavenError::ty(
+                    TypeError::Custom(format!(
+                        "this lambda takes {} parameter{} but `{}` takes {}",
+                        params.len(),
+                        if params.len() == 1 { "" } else { "s" },
+                        Ty::Function {
+                            params: hint_params.clone(),
+                            ret: hint_ret.clone(),
+                        },
+                        hint_params.len()
+                    )),
+                    span.clone(),
+                ));
+            }
+            for (p, t) in params.iter().zip(hint_params) {
+                self.locals.insert(BindingKey::param(&p.span), t.clone());
+                param_tys.push(t);
+            }
+            hinted_ret = Some(*hint_ret);
+        }
+        if !params_inferred {
+            for p in params {
+                let t = match &p.ty {
+                    Some(t) => self.resolve_ast_ty(t)?,
+                    None => {
+                        return Err(RavenError::ty(
+                            TypeError::Custom(format!(
+                                "lambda parameter `{}` needs a type annotation",
+                                p.name
+                            )),
+                            p.span.clone(),
+                        ));
+                    }
+                };
+                self.locals.insert(BindingKey::param(&p.span), t.clone());
+                param_tys.push(t);
+            }
         }
         let declared_ret = match ret {
             Some(t) => Some(self.resolve_ast_ty(t)?),
-            None => None,
+            None => hinted_ret,
         };
         // A lambda is its own function: an enclosing loop does not extend into
-        // it, so `break`/`continue` in the body are outside any loop. Check the
-        // body with a fresh loop stack, then restore the caller's.
+        // it, so `break`/`continue` in the body are outside any loop, and a
+        // `return` in the body returns from the lambda, not the enclosing
+        // function. Check the body with a fresh loop stack and the lambda's own
+        // return type (a fresh variable when undeclared), then restore the
+        // caller's.
+        let lambda_ret = match &declared_ret {
+            Some(d) => d.clone(),
+            None => Ty::Var(self.infer.fresh(span.clone())),
+        };
+        let saved_ret = std::mem::replace(&mut self.return_ty, lambda_ret.clone());
         let saved_loops = std::mem::take(&mut self.loop_kinds);
         let body_ty = match body {
             LambdaBody::Block(b) => self.check_block(b),
             LambdaBody::Expr(e) => self.check_expr(e),
         };
         self.loop_kinds = saved_loops;
+        self.return_ty = saved_ret;
         let body_ty = body_ty?;
+        // A block body with no trailing expression ends in its statements, so
+        // (as for a named function) its value is whatever `return` carries.
+        let no_tail = matches!(body, LambdaBody::Block(b) if b.trailing.is_none());
         let final_ret = match declared_ret {
             Some(d) => {
-                let discards_block_tail =
-                    matches!(body, LambdaBody::Block(_)) && matches!(d.strip_self(), Ty::Unit);
+                let discards_block_tail = matches!(body, LambdaBody::Block(_))
+                    && (matches!(d.strip_self(), Ty::Unit) || no_tail);
                 if !discards_block_tail {
                     self.unify(
                         &d,
@@ -3189,7 +3483,13 @@ impl<'a, 'b> Checker<'a, 'b> {
                 }
                 d
             }
-            None => body_ty,
+            None => {
+                let returned = !matches!(self.infer.resolve(&lambda_ret), Ty::Var(_));
+                if !(no_tail && returned) {
+                    self.unify(&lambda_ret, &body_ty, span)?;
+                }
+                self.infer.resolve(&lambda_ret)
+            }
         };
         Ok(Ty::Function {
             params: param_tys,
@@ -3208,7 +3508,10 @@ impl<'a, 'b> Checker<'a, 'b> {
         // the struct decl).
         let binding = self.resolved.map.lookup(span).ok_or_else(|| {
             RavenError::ty(
-                TypeError::Custom(format!("struct `{}` is not in scope", name)),
+                TypeError::Custom(format!(
+                    "struct `{}` is not in scope",
+                    source_type_name(name)
+                )),
                 span.clone(),
             )
         })?;
@@ -3292,7 +3595,7 @@ impl<'a, 'b> Checker<'a, 'b> {
             return Err(RavenError::ty(
                 TypeError::Custom(format!(
                     "struct `{}` is missing field(s): {}",
-                    sig.name,
+                    source_type_name(&sig.name),
                     missing.join(", ")
                 )),
                 span.clone(),
@@ -3321,6 +3624,44 @@ impl<'a, 'b> Checker<'a, 'b> {
         self.types.record_type_args(span, args);
     }
 
+    /// `"template {}".format(args)`: the template must be a string literal,
+    /// and the call checks as the interpolated string it spells, with each
+    /// `{}` replaced by the next argument and each `{:?}` by its `debug()`.
+    /// See `builtin::format_fragments`.
+    fn check_string_format(
+        &mut self,
+        receiver: &Expr,
+        args: &[Expr],
+        span: &Span,
+    ) -> Result<Ty, RavenError> {
+        let (ExprKind::Str(template) | ExprKind::BlockStr(template)) = &receiver.kind else {
+            for a in args {
+                self.check_expr_recover(a);
+            }
+            return Err(RavenError::ty(
+                TypeError::Custom(
+                    "`format` needs a string literal template; interpolate a computed string with `${...}` instead"
+                        .to_string(),
+                ),
+                receiver.span.clone(),
+            ));
+        };
+        let fragments = builtin::format_fragments(template, &receiver.span, args)
+            .map_err(|msg| RavenError::ty(TypeError::Custom(msg), span.clone()))?;
+        self.check_interpolated_string(&fragments).map_err(|e| {
+            // A `{:?}` slot's `debug()` call is spanned inside the template,
+            // so an error there is a missing `Debug` impl.
+            let at = e.span();
+            if at.start > receiver.span.start && at.end <= receiver.span.end {
+                e.with_hint(
+                    "`{:?}` renders through the `Debug` trait: add `import std/fmt`, and `@derive(Debug)` on your own types",
+                )
+            } else {
+                e
+            }
+        })
+    }
+
     /// Type an interpolated string literal. The whole literal has type
     /// `String`. Every embedded `${expr}` must resolve to a type that can
     /// be converted to a string. The built-in scalars (`String`, `Int`,
@@ -3355,6 +3696,12 @@ impl<'a, 'b> Checker<'a, 'b> {
             if is_int_ffi(stripped) || is_float_ffi(stripped) {
                 continue;
             }
+            // A list goes through the prelude's generic `ToString` impl and
+            // interpolates when its element type does.
+            if let Ty::List(_) = stripped {
+                self.require_to_string(stripped, &e.span)?;
+                continue;
+            }
             if let Ty::Param(p) = stripped {
                 let ok = self
                     .param_bounds
@@ -3435,14 +3782,25 @@ pub fn check_binary(l: &Ty, r: &Ty, op: BinaryOp, span: &Span) -> Result<Ty, Rav
         Add | Sub | Mul | Div | Mod => match (ls, rs) {
             (Ty::Int, Ty::Int) => Ok(Ty::Int),
             (Ty::Float, Ty::Float) => Ok(Ty::Float),
+            // `"ab" * 3` repeats the string; the count may sit on either side.
+            (Ty::Str, Ty::Int) | (Ty::Int, Ty::Str) if op == Mul => Ok(Ty::Str),
+            // `a + b` on two strings concatenates. Only `String + String`:
+            // any other operand needs an explicit conversion, or `${...}`.
+            (Ty::Str, Ty::Str) if op == Add => Ok(Ty::Str),
             // Arithmetic on two equal integer C FFI types stays in that
             // type (the back end emits the op at the type's machine
             // width). This lets an FFI callback such as a `qsort`
             // comparator compute `load<CInt>(a) - load<CInt>(b)` directly.
             (a, b) if is_int_ffi(a) && a == b => Ok(a.clone()),
+            // When both sides already agree, the type itself is wrong for
+            // the operator; asking for "`Bool and Bool`" would repeat it.
             _ => Err(RavenError::ty(
                 TypeError::TypeMismatch {
-                    expected: format!("{} and {}", ls, ls),
+                    expected: if ls == rs {
+                        "Int or Float".to_string()
+                    } else {
+                        format!("{} and {}", ls, ls)
+                    },
                     actual: format!("{} and {}", ls, rs),
                 },
                 span.clone(),
@@ -3466,6 +3824,11 @@ pub fn check_binary(l: &Ty, r: &Ty, op: BinaryOp, span: &Span) -> Result<Ty, Rav
             | (Ty::Float, Ty::Float)
             | (Ty::Char, Ty::Char)
             | (Ty::Str, Ty::Str) => Ok(Ty::Bool),
+            // Ordering an `Int` against a `Float` compares numerically: MIR
+            // widens the `Int` side to `Float`. This is the one place the
+            // two mix; arithmetic and `==` still need an explicit
+            // `to_float()`.
+            (Ty::Int, Ty::Float) | (Ty::Float, Ty::Int) => Ok(Ty::Bool),
             _ => Err(RavenError::ty(
                 TypeError::TypeMismatch {
                     expected: "orderable types".into(),
@@ -3474,6 +3837,16 @@ pub fn check_binary(l: &Ty, r: &Ty, op: BinaryOp, span: &Span) -> Result<Ty, Rav
                 span.clone(),
             )),
         },
+        EqIgnoreCase | NeIgnoreCase => match (ls, rs) {
+            (Ty::Str, Ty::Str) => Ok(Ty::Bool),
+            _ => Err(RavenError::ty(
+                TypeError::TypeMismatch {
+                    expected: "String and String".into(),
+                    actual: format!("{} and {}", ls, rs),
+                },
+                span.clone(),
+            )),
+        },
         And | Or => match (ls, rs) {
             (Ty::Bool, Ty::Bool) => Ok(Ty::Bool),
             _ => Err(RavenError::ty(
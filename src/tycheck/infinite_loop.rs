@@ -0,0 +1,246 @@
+//! Infinite `loop` detection.
+//!
+//! A `loop { ... }` only finishes through a `break` aimed at it, or by leaving
+//! the whole function with `return` or `?`. A `loop` whose body has none of
+//! these runs forever, which is almost always a forgotten exit. Each one
+//! produces a [`Warning::InfiniteLoop`] at its `loop` keyword.
+//!
+//! The check is syntactic and does not ask whether an exit is reachable: a
+//! `break` behind an `if` that can never be true still counts. A `break` inside
+//! a nested `while`, `for`, or `loop` leaves that inner loop, not this one, and
+//! a `return` inside a lambda returns from the lambda, so neither counts.
+//!
+//! Like dead code, only the entry file is linted, but impl methods are walked
+//! along with top level functions. A server or event loop that is meant to run
+//! forever is silenced with `@allow(infinite_loop)` on its function or method.
+
+use crate::ast::{
+    Block, DeclKind, ElseBranch, Expr, ExprKind, Function, FunctionBody, LambdaBody, Stmt, StmtKind,
+};
+use crate::error::Warning;
+use crate::resolve::ResolvedFile;
+use crate::span::Span;
+
+/// Report every `loop` in an entry file function or method that has no way
+/// out.
+pub fn check_infinite_loops(resolved: &ResolvedFile<'_>) -> Vec<Warning> {
+    let file = resolved.file;
+    let mut scan = Scan::default();
+    for decl in &file.items {
+        if decl.span.file != file.span.file {
+            continue;
+        }
+        match &decl.kind {
+            DeclKind::Function(f) => scan.function(f),
+            DeclKind::Impl(i) => i.items.iter().for_each(|m| scan.function(m)),
+            _ => {}
+        }
+    }
+    scan.warnings
+}
+
+/// Walk state: the loops enclosing the current point, innermost last.
+#[derive(Default)]
+struct Scan {
+    /// `Some(exits)` for a `loop` (whether an exit out of it has been seen
+    /// yet), `None` for a `while` or `for`, which end on their own.
+    open: Vec<Option<bool>>,
+    warnings: Vec<Warning>,
+}
+
+impl Scan {
+    fn function(&mut self, f: &Function) {
+        if f.allows.iter().any(|a| a == "infinite_loop") {
+            return;
+        }
+        match &f.body {
+            FunctionBody::Block(b) => self.block(b),
+            FunctionBody::Expr(e) => self.expr(e),
+            FunctionBody::None => {}
+        }
+    }
+
+    fn block(&mut self, b: &Block) {
+        for s in &b.stmts {
+            self.stmt(s);
+        }
+        if let Some(e) = &b.trailing {
+            self.expr(e);
+        }
+    }
+
+    /// Run `f` with `kind` pushed as the innermost loop, returning whether
+    /// an exit out of it was seen.
+    fn in_loop(&mut self, kind: Option<bool>, f: impl FnOnce(&mut Self)) -> bool {
+        self.open.push(kind);
+        f(self);
+        matches!(self.open.pop(), Some(Some(true)))
+    }
+
+    /// A `return` or `?` leaves the function, and with it every open loop.
+    fn leave_function(&mut self) {
+        for l in self.open.iter_mut().flatten() {
+            *l = true;
+        }
+    }
+
+    fn stmt(&mut self, s: &Stmt) {
+        match &s.kind {
+            StmtKind::Let { init, .. } => {
+                if let Some(e) = init {
+                    self.expr(e);
+                }
+            }
+            StmtKind::Fun { func, .. } => self.expr(func),
+            StmtKind::Return(e) => {
+                if let Some(e) = e {
+                    self.expr(e);
+                }
+                self.leave_function();
+            }
+            StmtKind::Break(e) => {
+                if let Some(e) = e {
+                    self.expr(e);
+                }
+                if let Some(Some(exits)) = self.open.last_mut() {
+                    *exits = true;
+                }
+            }
+            StmtKind::Continue | StmtKind::TypeDecl(_) => {}
+            StmtKind::Defer(e) | StmtKind::Spawn(e) | StmtKind::Expr(e) => self.expr(e),
+            StmtKind::Assign { target, value, .. } => {
+                self.expr(target);
+                self.expr(value);
+            }
+        }
+    }
+
+    fn expr(&mut self, e: &Expr) {
+        match &e.kind {
+            ExprKind::Loop(b) => {
+                if !self.in_loop(Some(false), |s| s.block(b)) {
+                    let sp = &e.span;
+                    let keyword =
+                        Span::new(sp.file.clone(), sp.start, sp.start + 4, sp.line, sp.col);
+                    self.warnings.push(Warning::InfiniteLoop { span: keyword });
+                }
+            }
+            ExprKind::While {
+                cond,
+                body,
+                else_block,
+            } => {
+                self.expr(cond);
+                self.in_loop(None, |s| s.block(body));
+                if let Some(b) = else_block {
+                    self.block(b);
+                }
+            }
+            ExprKind::For { iter, body, .. } => {
+                self.expr(iter);
+                self.in_loop(None, |s| s.block(body));
+            }
+            ExprKind::Try(inner) => {
+                self.expr(inner);
+                self.leave_function();
+            }
+            // A lambda is its own function: its `break`s and `return`s never
+            // leave a loop around it. Loops inside it are still checked.
+            ExprKind::Lambda { body, .. } => {
+                let outer = std::mem::take(&mut self.open);
+                match body {
+                    LambdaBody::Block(b) => self.block(b),
+                    LambdaBody::Expr(e) => self.expr(e),
+                }
+                self.open = outer;
+            }
+            ExprKind::Block(b) => self.block(b),
+            ExprKind::If {
+                cond,
+                then_branch,
+                else_branch,
+            } => {
+                self.expr(cond);
+                self.block(then_branch);
+                match else_branch.as_deref() {
+                    Some(ElseBranch::If(e)) => self.expr(e),
+                    Some(ElseBranch::Block(b)) => self.block(b),
+                    None => {}
+                }
+            }
+            ExprKind::Match { scrutinee, arms } => {
+                self.expr(scrutinee);
+                for arm in arms {
+                    if let Some(g) = &arm.guard {
+                        self.expr(g);
+                    }
+                    self.expr(&arm.body);
+                }
+            }
+            ExprKind::InterpolatedString(fragments) => {
+                for frag in fragments {
+                    if let crate::ast::StrFragment::Expr(e) = frag {
+                        self.expr(e);
+                    }
+                }
+            }
+            ExprKind::StructLit { fields, .. } => {
+                for f in fields {
+                    self.expr(&f.value);
+                }
+            }
+            ExprKind::Array(items) | ExprKind::Tuple(items) | ExprKind::SetLit(items) => {
+                for i in items {
+                    self.expr(i);
+                }
+            }
+            ExprKind::MapLit(pairs) => {
+                for (k, v) in pairs {
+                    self.expr(k);
+                    self.expr(v);
+                }
+            }
+            ExprKind::Call { callee, args } => {
+                self.expr(callee);
+                for a in args {
+                    self.expr(a);
+                }
+            }
+            ExprKind::MethodCall { receiver, args, .. } => {
+                self.expr(receiver);
+                for a in args {
+                    self.expr(a);
+                }
+            }
+            ExprKind::Binary { lhs, rhs, .. } => {
+                self.expr(lhs);
+                self.expr(rhs);
+            }
+            ExprKind::Range { start, end, .. } => {
+                self.expr(start);
+                self.expr(end);
+            }
+            ExprKind::Index { receiver, index } => {
+                self.expr(receiver);
+                self.expr(index);
+            }
+            ExprKind::Paren(inner)
+            | ExprKind::Unary { operand: inner, .. }
+            | ExprKind::Field {
+                receiver: inner, ..
+            } => self.expr(inner),
+            ExprKind::Int(_)
+            | ExprKind::Float(_)
+            | ExprKind::Bool(_)
+            | ExprKind::Str(_)
+            | ExprKind::BlockStr(_)
+            | ExprKind::Char(_)
+            | ExprKind::CStr(_)
+            | ExprKind::SelfLower
+            | ExprKind::SelfUpper
+            | ExprKind::Ident { .. }
+            | ExprKind::MacroCall(_)
+            | ExprKind::Unit => {}
+        }
+    }
+}
@@ -16,14 +16,18 @@
 //! * `expr` and `stmt` run the body checking pass.
 //! * `pattern` and `match_check` validate pattern matching and
 //!   exhaustiveness.
+//! * `dead_code` reports entry file functions that are never used.
+//! * `infinite_loop` reports `loop`s with no `break`, `return`, or `?`.
 //!
 //! See `docs/v2/specs/tycheck.md` for the design.
 
 pub mod builtin;
 pub mod collect;
+pub mod dead_code;
 pub mod env;
 pub mod expr;
 pub mod infer;
+pub mod infinite_loop;
 pub mod match_check;
 pub mod pattern;
 pub mod stmt;
@@ -37,7 +41,7 @@ mod tests;
 use std::collections::{HashMap, HashSet};
 
 use crate::ast::File;
-use crate::error::RavenError;
+use crate::error::{RavenError, Warning};
 use crate::resolve::{ResolvedFile, UseKey};
 use crate::span::Span;
 
@@ -189,6 +193,9 @@ pub struct TypedFile<'a> {
     pub resolved: &'a ResolvedFile<'a>,
     pub env: TypeEnv,
     pub types: TypeMap,
+    /// Non-fatal diagnostics (unused functions, endless `loop`s) for the
+    /// driver to report.
+    pub warnings: Vec<Warning>,
 }
 
 /// Run the type checker on `resolved` and return either a `TypedFile`
@@ -213,11 +220,14 @@ pub fn check_file_all<'a>(
     }
     let mut types = TypeMap::new();
     expr::check_bodies(resolved, &env, &mut types)?;
+    let mut warnings = dead_code::check_dead_code(resolved);
+    warnings.extend(infinite_loop::check_infinite_loops(resolved));
     Ok(TypedFile {
         file: resolved.file,
         resolved,
         env,
         types,
+        warnings,
     })
 }
 
@@ -6,7 +6,7 @@
 
 use std::collections::HashMap;
 
-use crate::ast::{LiteralPattern, Pattern, PatternKind};
+use crate::ast::{source_type_name, LiteralPattern, Pattern, PatternKind};
 use crate::error::{RavenError, TypeError};
 
 use super::env::{TypeEnv, VariantPayloadSig};
@@ -92,7 +92,8 @@ pub fn bind(
                     RavenError::ty(
                         TypeError::Custom(format!(
                             "enum `{}` has no variant `{}`",
-                            sig.name, variant
+                            source_type_name(&sig.name),
+                            variant
                         )),
                         pat.span.clone(),
                     )
@@ -167,8 +168,8 @@ pub fn bind(
                     if sig.name != *name {
                         return Err(RavenError::ty(
                             TypeError::TypeMismatch {
-                                expected: sig.name.clone(),
-                                actual: name.clone(),
+                                expected: source_type_name(&sig.name).to_string(),
+                                actual: source_type_name(name).to_string(),
                             },
                             pat.span.clone(),
                         ));
@@ -1,7 +1,7 @@
 //! Inline unit tests for the type checker.
 
 use super::{check_file, check_file_all, Ty};
-use crate::error::{RavenError, TypeError};
+use crate::error::{RavenError, TypeError, Warning};
 use crate::lexer::Lexer;
 use crate::parser::parse;
 use crate::resolve::{resolve_file, LoadedSource, SourceLoader};
@@ -15,13 +15,19 @@ impl SourceLoader for NoLoader {
 }
 
 fn check(src: &str) -> Result<(), RavenError> {
+    check_warnings(src).map(|_| ())
+}
+
+/// Type-check `src` and return the warnings of a clean check, for tests of
+/// the lints that ride along with type checking.
+fn check_warnings(src: &str) -> Result<Vec<Warning>, RavenError> {
     let tokens = Lexer::new(src.to_string(), PathBuf::from("t.rv"))
         .tokenize()
         .expect("lex");
     let file = parse(&tokens).expect("parse");
     let mut loader = NoLoader;
     let resolved = resolve_file(&file, &mut loader)?;
-    check_file(&resolved).map(|_| ())
+    check_file(&resolved).map(|typed| typed.warnings)
 }
 
 /// Type-check `src` and return every recovered diagnostic, for tests that
@@ -84,6 +90,80 @@ fn unit_lambda_discards_a_trailing_value() {
     .unwrap();
 }
 
+#[test]
+fn return_in_a_lambda_returns_from_the_lambda() {
+    check("fun main() {\n    let f = fun(x: Int) -> Int { return x * 2 }\n    let g = fun(x: Int) {\n        if x > 0 { return }\n    }\n}\n")
+        .expect("each return checks against its own lambda");
+    check("fun main() -> Bool {\n    let f = fun(x: Int) { return x }\n    let n: Int = f(1)\n    true\n}\n")
+        .expect("an undeclared lambda return type is inferred from `return`");
+    assert!(
+        check("fun main() {\n    let f = fun(x: Int) -> Int { return true }\n}\n").is_err(),
+        "a lambda's return still has to match its declared type"
+    );
+}
+
+#[test]
+fn shorthand_lambda_takes_types_from_the_let_annotation() {
+    check("fun main() {\n    let add: fun(Int, Int) -> Int = { a, b -> a + b }\n    let n: Int = add(1, 2)\n}\n")
+        .expect("the annotation types the shorthand lambda");
+    assert!(
+        check("fun main() {\n    let f: fun(Int) -> Bool = { x -> x + 1 }\n}\n").is_err(),
+        "the body still has to match the annotated return type"
+    );
+    let err = check("fun main() {\n    let f = { x -> x + 1 }\n}\n").unwrap_err();
+    assert!(
+        err.to_string().contains("require a context type"),
+        "got: {}",
+        err
+    );
+    // The hint belongs to the initializer itself, not a lambda inside it.
+    assert!(check(
+        "fun main() {\n    let f: fun(Int) -> Int = fun(x: Int) -> Int {\n        let g = { y -> y }\n        x\n    }\n}\n"
+    )
+    .is_err());
+}
+
+#[test]
+fn shorthand_lambda_arity_must_match_the_annotation() {
+    let err = check("fun main() {\n    let f: fun(Int) -> Int = { a, b -> a }\n}\n").unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("this lambda takes 2 parameters but `fun(Int) -> Int` takes 1"),
+        "got: {}",
+        err
+    );
+}
+
+#[test]
+fn local_function_binds_a_callable_constant() {
+    check("fun main() {\n    let k = 3\n    fun scale(x: Int) -> Int {\n        return x * k\n    }\n    let n: Int = scale(2)\n}\n")
+        .expect("local function");
+    assert!(
+        check("fun main() {\n    fun one() -> Int = 1\n    one = fun() -> Int = 2\n}\n").is_err(),
+        "a local function is immutable"
+    );
+}
+
+#[test]
+fn a_value_named_like_a_local_type_is_still_the_value() {
+    // The parameter `Node`, the field `Node`, and the local `Box` share a
+    // name with a local type, and each still reads the value.
+    check(
+        "fun f(Node: Int) -> Int {\n    struct Node { value: Int }\n    struct Wrap { Node: Int }\n    let n = Node { value: Node }\n    let w = Wrap { Node }\n    let Box = n.value + w.Node\n    struct Box { v: Int }\n    let b = Box { v: Box }\n    return Box + b.v\n}\n",
+    )
+    .expect("values shadow the local type names");
+}
+
+#[test]
+fn a_local_type_is_named_as_declared_in_errors() {
+    let err = check("fun f() {\n    struct P { x: Int }\n    let p = P {}\n}\n").unwrap_err();
+    let msg = err.to_string();
+    assert!(
+        msg.contains("struct `P` is missing field(s): x"),
+        "got: {msg}"
+    );
+}
+
 #[test]
 fn non_unit_function_still_requires_its_declared_return_type() {
     assert!(check("fun value() -> Int { true }").is_err());
@@ -131,6 +211,21 @@ fn unary_address_of_is_rejected() {
     }
 }
 
+#[test]
+fn bitwise_complement_takes_only_an_int() {
+    check("fun f(x: Int) -> Int = ~x\n").unwrap();
+    let err = check("fun f(x: Bool) -> Bool = ~x\n").unwrap_err();
+    assert!(
+        matches!(
+            &err,
+            RavenError::Type(b, _, _)
+                if matches!(**b, TypeError::TypeMismatch { ref expected, .. } if expected == "Int")
+        ),
+        "got: {:?}",
+        err
+    );
+}
+
 #[test]
 fn inferred_type_violating_a_bound_is_rejected() {
     // A call that infers a type argument violating the bound is rejected the
@@ -276,6 +371,37 @@ fn reassigning_a_let_local_is_allowed() {
     check("fun f() -> Int {\n    let m = 1\n    m = 2\n    return m\n}\n").unwrap();
 }
 
+#[test]
+fn chained_field_assignment_checks_the_field_and_the_value() {
+    let structs = "struct B { c: Float }\nstruct A { b: B }\n";
+    check(&format!(
+        "{structs}fun f(a: A) {{\n    a.b.c = 3.14\n    a.b = B {{ c: 0.0 }}\n}}\n"
+    ))
+    .unwrap();
+    let errors = check_all(&format!(
+        "{structs}fun f(a: A) {{\n    a.b.c = \"x\"\n    a.b.d = 1.0\n    a.b = 3.14\n}}\n"
+    ));
+    assert_eq!(errors.len(), 3, "{errors:?}");
+    assert!(
+        matches!(&errors[0], RavenError::Type(b, _, _) if matches!(**b, TypeError::TypeMismatch { .. }))
+    );
+    assert!(matches!(
+        &errors[1],
+        RavenError::Type(b, _, _) if matches!(**b, TypeError::UndefinedField { ref field, .. } if field == "d")
+    ));
+    assert!(
+        matches!(&errors[2], RavenError::Type(b, _, _) if matches!(**b, TypeError::TypeMismatch { .. }))
+    );
+}
+
+#[test]
+fn outer_binding_type_returns_after_a_shadowing_block() {
+    check(
+        "fun f(c: Bool) -> Int {\n    let x = 1\n    if c {\n        let x = \"s\"\n        let n: String = x\n    }\n    return x + 1\n}\n",
+    )
+    .unwrap();
+}
+
 #[test]
 fn mixed_int_float_arithmetic_is_rejected() {
     let err = check("fun f() -> Float = 1 + 2.0\n").unwrap_err();
@@ -290,11 +416,82 @@ fn bool_logical_ops_produce_bool() {
     check("fun f(a: Bool, b: Bool) -> Bool = a && b || !a\n").unwrap();
 }
 
+#[test]
+fn for_binding_and_unannotated_let_infer_from_their_initializers() {
+    // `i` takes the range's element type and `j` its initializer's, so both
+    // are `Int` without annotations.
+    check(
+        "fun f() -> Int {\n    let total = 0\n    for i in 0..10 {\n        let j = i + 1\n        total = total + j\n    }\n    return total\n}\n",
+    )
+    .unwrap();
+    let err = check("fun f() {\n    for i in 0..10 {\n        let s: String = i\n    }\n}\n")
+        .unwrap_err();
+    assert!(matches!(err, RavenError::Type(_, _, _)));
+}
+
+#[test]
+fn for_iterates_a_struct_with_an_inherent_next() {
+    // No `Iterator` impl: a `next(self) -> Option<T>` method is enough, and
+    // the loop binding takes the `Option`'s payload type.
+    let countdown = "struct Countdown {\n    left: Int,\n}\n\nimpl Countdown {\n    fun next(self) -> Option<Int> {\n        if self.left == 0 {\n            return None\n        }\n        self.left = self.left - 1\n        return Some(self.left)\n    }\n}\n\n";
+    check(&format!(
+        "{countdown}fun f(c: Countdown) -> Int {{\n    let total = 0\n    for n in c {{\n        total = total + n\n    }}\n    return total\n}}\n"
+    ))
+    .unwrap();
+    let err = check(&format!(
+        "{countdown}fun f(c: Countdown) {{\n    for n in c {{\n        let s: String = n\n    }}\n}}\n"
+    ))
+    .unwrap_err();
+    assert!(matches!(err, RavenError::Type(_, _, _)));
+}
+
+#[test]
+fn for_rejects_a_next_that_does_not_return_an_option() {
+    let err = check(
+        "struct Ticker {\n    n: Int,\n}\n\nimpl Ticker {\n    fun next(self) -> Int = self.n\n}\n\nfun f(t: Ticker) {\n    for x in t {\n    }\n}\n",
+    )
+    .unwrap_err();
+    assert!(
+        err.to_string().contains("cannot iterate over `Ticker`"),
+        "{err}"
+    );
+}
+
+#[test]
+fn indexing_a_string_yields_a_char() {
+    check("fun f(s: String) -> Char = s[0]\n").unwrap();
+}
+
+#[test]
+fn assigning_through_a_string_index_is_rejected() {
+    let err = check("fun f() {\n    let s = \"abc\"\n    s[0] = 'x'\n}\n").unwrap_err();
+    match err {
+        RavenError::Type(b, _, _) => assert!(
+            matches!(*b, TypeError::Custom(ref m) if m.contains("character of a `String`")),
+            "got: {:?}",
+            b
+        ),
+        other => panic!("expected a type error, got {:?}", other),
+    }
+}
+
 #[test]
 fn comparison_returns_bool() {
     check("fun f(a: Int, b: Int) -> Bool = a < b\n").unwrap();
 }
 
+#[test]
+fn equality_against_bare_none_takes_the_other_side_type() {
+    check("fun f(n: Option<Int>) -> Bool = n == None\n").unwrap();
+    check("fun f(n: Option<Int>) -> Bool = None != n\n").unwrap();
+}
+
+#[test]
+fn equality_against_none_still_rejects_a_non_option() {
+    let err = check("fun f(n: Int) -> Bool = n == None\n").unwrap_err();
+    assert!(matches!(err, RavenError::Type(_, _, _)));
+}
+
 #[test]
 fn comparison_requires_compatible_operands() {
     let err = check("fun f(a: Int, b: Bool) -> Bool = a < b\n").unwrap_err();
@@ -368,6 +565,25 @@ fn dyn_let_coercion_checks() {
     check_with_prelude(&src).unwrap();
 }
 
+#[test]
+fn a_list_of_printable_elements_prints() {
+    check_with_prelude("fun main() {\n    print([[1, 2], [3]])\n    print(\"${[true]}\")\n}\n")
+        .unwrap();
+}
+
+#[test]
+fn a_list_prints_only_when_its_element_type_does() {
+    let err = check_with_prelude("struct Q {}\nfun main() {\n    print([Q {}])\n}\n").unwrap_err();
+    match err {
+        RavenError::Type(b, _, _) => assert!(
+            matches!(&*b, TypeError::BoundNotSatisfied { ty, .. } if ty == "Q"),
+            "got {:?}",
+            b
+        ),
+        other => panic!("expected BoundNotSatisfied, got {:?}", other),
+    }
+}
+
 #[test]
 fn dyn_coercion_of_non_implementor_is_error() {
     let src = format!(
@@ -393,6 +609,66 @@ fn dyn_unknown_trait_method_is_error() {
     }
 }
 
+#[test]
+fn calling_a_function_field_as_a_method_points_at_parentheses() {
+    let err = check("struct Op { apply: fun(Int) -> Int }\nfun f(o: Op) -> Int = o.apply(1)\n")
+        .unwrap_err();
+    let RavenError::Type(b, _, Some(hint)) = &err else {
+        panic!("expected a type error with a hint, got {:?}", err);
+    };
+    assert!(matches!(**b, TypeError::UndefinedMethod { .. }));
+    assert_eq!(
+        hint,
+        "`apply` is a field holding a function; wrap the field access in parentheses to call it: `(o.apply)(...)`"
+    );
+    check("struct Op { apply: fun(Int) -> Int }\nfun f(o: Op) -> Int = (o.apply)(1)\n").unwrap();
+}
+
+#[test]
+fn method_behind_an_unmet_impl_bound_names_the_bound() {
+    let src = concat!(
+        "trait Weigh { fun weight(self) -> Int }\n",
+        "impl<T: Weigh> List<T> {\n    fun total(self) -> Int = 0\n}\n",
+        "struct Rock { kg: Int }\n",
+        "fun f(rs: List<Rock>) -> Int = rs.total()\n",
+    );
+    let err = check(src).unwrap_err();
+    let RavenError::Type(b, _, Some(hint)) = &err else {
+        panic!("expected a type error with a hint, got {:?}", err);
+    };
+    assert!(matches!(**b, TypeError::UndefinedMethod { .. }));
+    assert_eq!(
+        hint,
+        "`total` is only available when `Rock` implements `Weigh`"
+    );
+}
+
+#[test]
+fn method_on_a_narrower_self_type_names_that_type() {
+    let src = concat!(
+        "impl<T> List<List<T>> {\n    fun inner_count(self) -> Int = 0\n}\n",
+        "fun f(xs: List<Int>) -> Int = xs.inner_count()\n",
+    );
+    let err = check(src).unwrap_err();
+    let RavenError::Type(b, _, Some(hint)) = &err else {
+        panic!("expected a type error with a hint, got {:?}", err);
+    };
+    assert!(matches!(**b, TypeError::UndefinedMethod { .. }));
+    assert_eq!(hint, "`inner_count` is only defined on `List<List<T>>`");
+}
+
+#[test]
+fn string_method_without_its_import_names_the_module() {
+    let err = check("fun f(s: String) -> String = s.trim_start()\n").unwrap_err();
+    let RavenError::Type(_, _, Some(hint)) = &err else {
+        panic!("expected a type error with a hint, got {:?}", err);
+    };
+    assert_eq!(
+        hint,
+        "`trim_start` is a `String` method from std/string: add `import std/string`"
+    );
+}
+
 #[test]
 fn dyn_of_non_object_safe_generic_method_is_error() {
     // A generic method makes the trait non-object-safe.
@@ -702,6 +978,15 @@ fn try_operator_on_option_type_checks() {
         .expect("? on Option type-checks");
 }
 
+#[test]
+fn print_without_parentheses_points_at_the_call_form() {
+    let err = check("fun main() {\n    print \"hi\"\n}\n").unwrap_err();
+    match err {
+        RavenError::Type(_, _, Some(hint)) => assert!(hint.contains("print(value)"), "{hint}"),
+        other => panic!("expected a hinted TypeError, got {:?}", other),
+    }
+}
+
 #[test]
 fn try_operator_on_int_is_error() {
     let err = check("fun f() -> Int { let v = 1?; return v }\n").unwrap_err();
@@ -782,6 +1067,16 @@ fn break_and_continue_inside_a_loop_are_ok() {
         .expect("continue in a while");
 }
 
+#[test]
+fn break_in_a_while_else_block_targets_the_enclosing_loop() {
+    assert!(
+        check("fun f() { while false { } else { break } }\n").is_err(),
+        "the else block is outside the while loop"
+    );
+    check("fun f() { loop { while false { } else { break } } }\n")
+        .expect("break in a while-else inside a loop");
+}
+
 #[test]
 fn break_with_a_value_outside_a_value_loop_is_rejected() {
     assert!(
@@ -790,6 +1085,76 @@ fn break_with_a_value_outside_a_value_loop_is_rejected() {
     );
 }
 
+#[test]
+fn a_loop_takes_the_type_of_its_break_value() {
+    check("fun f() -> Int {\n    let n = loop {\n        break 42\n    }\n    n + 1\n}\n")
+        .expect("loop yields its break value");
+    check("fun f() {\n    loop {\n        break\n    }\n}\n").expect("bare break yields unit");
+}
+
+#[test]
+fn breaks_out_of_one_loop_must_agree() {
+    assert!(
+        check("fun f() {\n    let x = loop {\n        if true { break 1 }\n        break \"one\"\n    }\n}\n")
+            .is_err(),
+        "an Int and a String break out of the same loop"
+    );
+    assert!(
+        check(
+            "fun f() {\n    let x = loop {\n        if true { break }\n        break 1\n    }\n}\n"
+        )
+        .is_err(),
+        "a bare break and a valued break out of the same loop"
+    );
+}
+
+#[test]
+fn format_on_a_literal_checks_as_interpolation() {
+    check_with_prelude(
+        "fun f(line: Int, msg: String) -> String {\n    \"Error at line {}: {}\".format(line, [msg])\n}\n",
+    )
+    .expect("format fills each placeholder");
+    assert!(
+        check_with_prelude("struct S {}\nfun f() -> String {\n    \"{}\".format(S {})\n}\n")
+            .is_err(),
+        "an argument without ToString is rejected"
+    );
+}
+
+#[test]
+fn module_qualified_constant_points_at_a_selector_import() {
+    let err =
+        check_with_prelude("import std/math\nfun f() -> Float = math.PI * 2.0\n").unwrap_err();
+    match err {
+        RavenError::Type(_, _, Some(hint)) => {
+            assert_eq!(hint, "import `PI` by name: `import std/math { PI }`")
+        }
+        other => panic!("expected a hinted TypeError, got {:?}", other),
+    }
+    check_with_prelude("import std/math { PI }\nfun f() -> Float = PI * 2.0\n")
+        .expect("a selector import binds the constant");
+}
+
+#[test]
+fn debug_placeholder_needs_a_debug_impl() {
+    check_with_prelude(
+        "import std/fmt\nfun f(names: List<String>) -> String {\n    \"{} {:?}\".format(1, names)\n}\n",
+    )
+    .expect("`{:?}` renders through `Debug`");
+    let err = check_with_prelude("fun f() -> String {\n    \"{:?}\".format(3)\n}\n").unwrap_err();
+    match err {
+        RavenError::Type(_, _, Some(hint)) => assert!(hint.contains("import std/fmt"), "{hint}"),
+        other => panic!("expected a hinted TypeError, got {:?}", other),
+    }
+}
+
+#[test]
+fn format_needs_a_literal_template_with_matching_placeholders() {
+    assert!(check("fun f(t: String) -> String {\n    t.format(1)\n}\n").is_err());
+    assert!(check("fun f() -> String {\n    \"{} {}\".format(1)\n}\n").is_err());
+    assert!(check("fun f() -> String {\n    \"{\".format()\n}\n").is_err());
+}
+
 #[test]
 fn duplicate_enum_variant_is_rejected() {
     assert!(check("enum E { A, A }\nfun main() {}\n").is_err());
@@ -971,6 +1336,45 @@ fn passing_native_string_to_cstr_is_rejected() {
     assert!(matches!(err, RavenError::Type(_, _, _)));
 }
 
+#[test]
+fn ordering_may_mix_int_and_float() {
+    check("fun f() -> Bool = 5 < 3.14\n").expect("Int < Float");
+    check("fun f(x: Float, n: Int) -> Bool = x >= n\n").expect("Float >= Int");
+    // Only ordering mixes: arithmetic and equality still need a conversion.
+    assert!(check("fun f() -> Float = 1 + 2.0\n").is_err());
+    assert!(check("fun f() -> Bool = 1 == 1.0\n").is_err());
+}
+
+#[test]
+fn string_repeat_and_case_insensitive_equality() {
+    check("fun f() -> String = \"ab\" * 3\n").expect("String * Int");
+    check("fun f() -> String = 3 * \"ab\"\n").expect("Int * String");
+    check("fun f() -> Bool = \"A\" ==~ \"a\"\n").expect("String ==~ String");
+    check("fun f() -> Bool = \"A\" !=~ \"a\"\n").expect("String !=~ String");
+    assert!(check("fun f() -> String = \"ab\" * 1.5\n").is_err());
+    assert!(check("fun f() -> String = \"ab\" + 3\n").is_err());
+    assert!(check("fun f() -> Bool = 1 ==~ 1\n").is_err());
+}
+
+#[test]
+fn string_plus_string_concatenates() {
+    check("fun f(a: String) -> String = a + \"!\"\n").expect("String + String");
+    check("fun f() -> String {\n    let s = \"a\"\n    s += \"b\"\n    return s\n}\n")
+        .expect("String += String");
+    assert!(check("fun f() -> String = 1 + \"a\"\n").is_err());
+    assert!(check("fun f() -> String = \"a\" - \"b\"\n").is_err());
+    let err = check("fun f() -> Bool = true + false\n").unwrap_err();
+    assert!(
+        matches!(
+            &err,
+            RavenError::Type(b, _, _)
+                if matches!(**b, TypeError::TypeMismatch { ref expected, .. } if expected == "Int or Float")
+        ),
+        "got: {:?}",
+        err
+    );
+}
+
 #[test]
 fn ffi_type_mismatch_is_rejected() {
     // A `c"..."` (CStr) where a CInt is expected is rejected.
@@ -1133,6 +1537,22 @@ fn type_name_accepts_scalar_and_struct() {
     .unwrap();
 }
 
+#[test]
+fn typeof_yields_a_string_for_any_operand() {
+    check(
+        r#"
+        struct Point { x: Int, y: Int }
+        fun a(p: Point) -> String = typeof p
+        fun b() -> String = typeof [1, 2]
+        fun c<T>(v: T) -> String = typeof v
+        fun d(p: Point) -> Bool = typeof p.x == "Int"
+    "#,
+    )
+    .unwrap();
+    let err = check("fun a() -> Int = typeof 1\n").unwrap_err();
+    assert!(matches!(err, RavenError::Type(_, _, _)), "got: {}", err);
+}
+
 #[test]
 fn field_names_yields_list_of_string() {
     check(
@@ -1297,3 +1717,182 @@ fn duplicate_diagnostics_are_deduplicated() {
     let errs = check_all("fun f() -> Int = \"x\"\n");
     assert_eq!(errs.len(), 1, "got: {:?}", errs);
 }
+
+/// Type-check `src` and return the names of the functions it warns are dead.
+fn dead_functions(src: &str) -> Vec<String> {
+    check_warnings(src)
+        .expect("check")
+        .into_iter()
+        .filter_map(|w| match w {
+            Warning::DeadCode { name, .. } => Some(name),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn uncalled_function_is_dead_code() {
+    let src =
+        "fun unused() -> Int = 1\nfun used() -> Int = 2\nfun main() {\n    let x = used()\n}\n";
+    assert_eq!(dead_functions(src), vec!["unused"]);
+}
+
+#[test]
+fn self_recursion_does_not_keep_a_function_alive() {
+    let src = "fun fact(n: Int) -> Int {\n    if n <= 1 {\n        return 1\n    }\n    return n * fact(n - 1)\n}\nfun main() {}\n";
+    assert_eq!(dead_functions(src), vec!["fact"]);
+}
+
+#[test]
+fn functions_that_only_call_each_other_are_dead() {
+    let src = "fun a(n: Int) -> Int = b(n - 1)\nfun b(n: Int) -> Int = a(n)\nfun main() {}\n";
+    assert_eq!(dead_functions(src), vec!["a", "b"]);
+}
+
+#[test]
+fn a_function_only_a_dead_function_calls_is_dead() {
+    let src = "fun leaf() -> Int = 1\nfun unused() -> Int = leaf()\nfun main() {}\n";
+    assert_eq!(dead_functions(src), vec!["leaf", "unused"]);
+}
+
+#[test]
+fn a_function_an_allowed_function_calls_is_used() {
+    let src = "fun leaf() -> Int = 1\n@allow(dead_code)\nfun debug_dump() -> Int = leaf()\nfun main() {}\n";
+    assert!(dead_functions(src).is_empty());
+}
+
+#[test]
+fn function_passed_as_a_value_is_used() {
+    let src = "fun double(x: Int) -> Int = x * 2\nfun apply(f: fun(Int) -> Int) -> Int = f(1)\nfun main() {\n    let y = apply(double)\n}\n";
+    assert!(dead_functions(src).is_empty());
+}
+
+#[test]
+fn allow_dead_code_silences_the_warning() {
+    let src = "@allow(dead_code)\nfun debug_dump() {}\nfun main() {}\n";
+    assert!(dead_functions(src).is_empty());
+}
+
+#[test]
+fn a_library_without_main_has_no_dead_code() {
+    let src = "fun helper() -> Int = 1\nfun api() -> Int = 2\n";
+    assert!(dead_functions(src).is_empty());
+}
+
+/// Type-check `src` and return the `(line, col)` of each `loop` it warns
+/// never ends.
+fn endless_loops(src: &str) -> Vec<(u32, u32)> {
+    check_warnings(src)
+        .expect("check")
+        .iter()
+        .filter_map(|w| match w {
+            Warning::InfiniteLoop { span } => Some((span.line, span.col)),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn loop_without_an_exit_warns_at_the_keyword() {
+    let src = "fun main() {\n    let n = 0\n    loop {\n        n = n + 1\n    }\n}\n";
+    assert_eq!(endless_loops(src), vec![(3, 5)]);
+}
+
+#[test]
+fn loop_left_by_break_return_or_try_does_not_warn() {
+    let src = "fun a() {\n    loop {\n        break\n    }\n}\n\
+               fun b() {\n    loop {\n        return\n    }\n}\n\
+               fun c(r: Result<Int, String>) -> Result<Int, String> {\n    loop {\n        let x = r?\n    }\n    Ok(0)\n}\n\
+               fun main() {\n    a()\n    b()\n    let z = c(Ok(1))\n}\n";
+    assert!(endless_loops(src).is_empty());
+}
+
+#[test]
+fn break_in_an_inner_loop_does_not_leave_the_outer_loop() {
+    let src =
+        "fun main() {\n    loop {\n        while true {\n            break\n        }\n    }\n}\n";
+    assert_eq!(endless_loops(src), vec![(2, 5)]);
+}
+
+#[test]
+fn return_in_a_lambda_does_not_leave_the_enclosing_loop() {
+    let src = "fun main() {\n    loop {\n        let f = fun() -> Int {\n            return 1\n        }\n    }\n}\n";
+    assert_eq!(endless_loops(src), vec![(2, 5)]);
+}
+
+#[test]
+fn allow_infinite_loop_silences_the_warning() {
+    let src = "@allow(infinite_loop)\nfun serve() {\n    loop {\n        let n = 1\n    }\n}\nfun main() {\n    serve()\n}\n";
+    assert!(endless_loops(src).is_empty());
+}
+
+#[test]
+fn loop_in_an_impl_method_is_checked() {
+    let src = "struct S { x: Int }\nimpl S {\n    fun spin(self) {\n        loop {\n            let y = self.x\n        }\n    }\n\n    @allow(infinite_loop)\n    fun serve(self) {\n        loop {\n            let y = self.x\n        }\n    }\n}\nfun main() {\n    S { x: 1 }.spin()\n}\n";
+    assert_eq!(endless_loops(src), vec![(4, 9)]);
+}
+
+#[test]
+fn bare_return_exits_a_unit_function_early() {
+    check("fun f(x: Int) {\n    if x > 0 {\n        return\n    }\n    let y = x\n}\n").unwrap();
+}
+
+#[test]
+fn bare_return_in_a_typed_function_is_an_error() {
+    let err = check("fun f() -> Int {\n    return\n}\n").unwrap_err();
+    let RavenError::Type(te, _, hint) = &err else {
+        panic!("expected a type error, got {}", err)
+    };
+    assert!(
+        matches!(**te, TypeError::Custom(ref m) if m == "bare `return` in a function that returns `Int`"),
+        "got: {}",
+        err
+    );
+    assert!(hint.is_some());
+}
+
+#[test]
+fn unit_expression_has_type_unit() {
+    check("fun f(x: Int) {\n    if x > 0 {\n        return ()\n    }\n}\nfun g() -> Unit = ()\n")
+        .unwrap();
+    assert!(check("fun h() -> Int = ()\n").is_err());
+}
+
+#[test]
+fn module_alias_reaches_the_modules_extern_functions() {
+    // std/math declares `sqrt` in an `extern "C"` block, which keeps its
+    // bare C name when merged, rather than as a namespaced `fun`.
+    check_with_prelude(
+        "import std/math\nfun f() -> Float = math.sqrt(4.0) + math.pow_int(2, 3).to_float()\n",
+    )
+    .unwrap();
+    let err =
+        check_with_prelude("import std/math\nfun f() -> Float = math.sqrt(\"4\")\n").unwrap_err();
+    assert!(matches!(err, RavenError::Type(_, _, _)));
+    let err =
+        check_with_prelude("import std/math\nfun f() -> Float = math.sqrtf(4.0)\n").unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("module `math` has no function `sqrtf`"),
+        "{err}"
+    );
+}
+
+#[test]
+fn zip_longest_fills_must_match_their_lists() {
+    check_with_prelude(
+        "import std/list\nfun main() {\n    let _ = list.zip_longest([1, 2], [\"a\"], 0, \"?\")\n}\n",
+    )
+    .unwrap();
+    // `fill_a` pads the `Int` list, `fill_b` the `String` one.
+    for (fills, mismatch) in [
+        ("\"?\", \"?\"", "expected `Int`, found `String`"),
+        ("0, 1", "expected `String`, found `Int`"),
+    ] {
+        let src = format!(
+            "import std/list\nfun main() {{\n    let _ = list.zip_longest([1, 2], [\"a\"], {fills})\n}}\n"
+        );
+        let err = check_with_prelude(&src).unwrap_err();
+        assert!(err.to_string().contains(mismatch), "{err}");
+    }
+}
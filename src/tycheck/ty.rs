@@ -249,7 +249,7 @@ impl fmt::Display for Ty {
             Ty::Char => f.write_str("Char"),
             Ty::Str => f.write_str("String"),
             Ty::Struct { name, args, .. } | Ty::Enum { name, args, .. } => {
-                f.write_str(name)?;
+                f.write_str(crate::ast::source_type_name(name))?;
                 if !args.is_empty() {
                     f.write_str("<")?;
                     for (i, a) in args.iter().enumerate() {
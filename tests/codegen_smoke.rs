@@ -110,12 +110,17 @@ fn multifile_local_imports_compile_and_run() {
     let Some(runtime) = supported_runtime() else {
         return;
     };
-    // `main.rv` selectively imports a function and a struct from a sibling
-    // `helper.rv` (`./helper`). The expander merges the local module into
-    // the program, so the imported `greet` call, the imported `Counter`
-    // type, and its `bumped` method all compile and link. Prints the
-    // greeting then 42.
-    compile_link_run_and_check("multifile/main.rv", "hi raven\n42\n", &runtime);
+    // `main.rv` selectively imports a function, a struct, and an enum from
+    // a sibling `helper.rv` (`./helper`). The expander merges the local
+    // module into the program, so the imported `greet` call, the imported
+    // `Counter` type and its `bumped` method, and the `Level` variants and
+    // their `describe` method all compile and link. Prints the greeting,
+    // 42, then each level.
+    compile_link_run_and_check(
+        "multifile/main.rv",
+        "hi raven\n42\nhigh 41\nlow\n",
+        &runtime,
+    );
 }
 
 #[test]
@@ -340,6 +345,59 @@ fn deeply_nested_expression_does_not_overflow() {
     assert_eq!(stdout, "250\n", "unexpected stdout: {:?}", stdout);
 }
 
+#[test]
+fn profile_build_reports_calls_per_function() {
+    // `raven build --profile` instruments every function and prints a
+    // table of calls and times to stderr when `main` returns, leaving
+    // stdout alone. `fib(10)` makes 177 calls.
+    let Some(_runtime) = supported_runtime() else {
+        return;
+    };
+    let tmp = workdir();
+    let source_path = tmp.join("profiled.rv");
+    std::fs::write(
+        &source_path,
+        "fun fib(n: Int) -> Int {\n    if n < 2 {\n        return n\n    }\n    return fib(n - 1) + fib(n - 2)\n}\n\nfun main() {\n    print(fib(10))\n}\n",
+    )
+    .expect("write source");
+    let binary = tmp.join(if cfg!(windows) {
+        "profiled.exe"
+    } else {
+        "profiled"
+    });
+    let build = Command::new(env!("CARGO_BIN_EXE_raven"))
+        .arg("build")
+        .arg(&source_path)
+        .arg("--profile")
+        .arg("-o")
+        .arg(&binary)
+        .output()
+        .expect("run raven build");
+    assert!(
+        build.status.success(),
+        "raven build --profile failed: stderr={}",
+        String::from_utf8_lossy(&build.stderr)
+    );
+    let run = Command::new(&binary).output().expect("run profiled binary");
+    cleanup(&tmp);
+    let stdout = String::from_utf8_lossy(&run.stdout).into_owned();
+    let stderr = String::from_utf8_lossy(&run.stderr).into_owned();
+    assert!(run.status.success(), "profiled binary failed: {stderr}");
+    assert_eq!(stdout, "55\n");
+    let mut lines = stderr.lines();
+    let header = lines.next().unwrap_or_default();
+    assert!(
+        header.starts_with("function") && header.contains("calls"),
+        "{stderr}"
+    );
+    let fib: Vec<&str> = lines
+        .find(|l| l.starts_with("fib "))
+        .unwrap_or_else(|| panic!("no fib row in {stderr}"))
+        .split_whitespace()
+        .collect();
+    assert_eq!(fib[1], "177", "{stderr}");
+}
+
 #[test]
 fn closure_arg_program_compiles_and_runs() {
     let Some(runtime) = supported_runtime() else {
@@ -992,6 +1050,34 @@ fn env_program_compiles_and_runs() {
     );
 }
 
+#[test]
+fn env_args_reach_the_program() {
+    let Some(runtime) = supported_runtime() else {
+        return;
+    };
+    // Arguments after the program path reach std/env's args() in order, with
+    // the program path itself at index 0 (the example drops it).
+    let example = build_example_binary("env_args.rv", &runtime);
+    let output = Command::new(&example.binary)
+        .args(["hello", "world"])
+        .output()
+        .expect("run env_args binary");
+    let stdout = String::from_utf8_lossy(&output.stdout).into_owned();
+    let stderr = String::from_utf8_lossy(&output.stderr).into_owned();
+    cleanup(&example.tmp);
+    assert!(
+        output.status.success(),
+        "env_args exited non zero: status={:?} stderr={}",
+        output.status,
+        stderr
+    );
+    assert_eq!(
+        stdout, "[hello, world]\n",
+        "unexpected stdout for env_args: {:?}",
+        stdout
+    );
+}
+
 #[test]
 fn fs_program_compiles_and_runs() {
     let Some(runtime) = supported_runtime() else {
@@ -1084,6 +1170,66 @@ fn fs_binary_roundtrip_compiles_and_runs() {
     );
 }
 
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+#[test]
+fn unbounded_recursion_reports_stack_overflow() {
+    let Some(runtime) = supported_runtime() else {
+        return;
+    };
+    // Recursing with no base case overflows the main stack. The runtime's
+    // guard handler turns the fault into a panic message and status 101.
+    let example = build_example_binary("stack_overflow.rv", &runtime);
+    let output = Command::new(&example.binary)
+        .output()
+        .expect("run stack_overflow binary");
+    let stdout = String::from_utf8_lossy(&output.stdout).into_owned();
+    let stderr = String::from_utf8_lossy(&output.stderr).into_owned();
+    cleanup(&example.tmp);
+    assert_eq!(
+        output.status.code(),
+        Some(101),
+        "stack_overflow status={:?} stderr={}",
+        output.status,
+        stderr
+    );
+    assert_eq!(stdout, "start\n");
+    assert!(
+        stderr.contains("raven panic: stack overflow"),
+        "unexpected stderr: {:?}",
+        stderr
+    );
+}
+
+#[test]
+fn panic_reports_call_stack() {
+    let Some(runtime) = supported_runtime() else {
+        return;
+    };
+    // An out-of-bounds index several calls deep, through a lambda, a local
+    // function, and a recursive function. The panic line is followed by the call stack,
+    // outermost first, with the recursion collapsed.
+    let example = build_example_binary("panic_call_stack.rv", &runtime);
+    let output = Command::new(&example.binary)
+        .output()
+        .expect("run panic_call_stack binary");
+    let stdout = String::from_utf8_lossy(&output.stdout).into_owned();
+    let stderr = String::from_utf8_lossy(&output.stderr).into_owned();
+    cleanup(&example.tmp);
+    assert_eq!(
+        output.status.code(),
+        Some(101),
+        "panic_call_stack status={:?} stderr={}",
+        output.status,
+        stderr
+    );
+    assert_eq!(stdout, "3\n");
+    assert_eq!(
+        stderr,
+        "raven panic: list index out of bounds\n\
+         call stack: main -> process_data -> <closure> -> start -> validate (x3) -> Range.check\n"
+    );
+}
+
 #[test]
 fn net_program_compiles_and_runs() {
     let Some(runtime) = supported_runtime() else {
@@ -2169,6 +2315,75 @@ fn list_get_rejects_out_of_range_index() {
     );
 }
 
+#[test]
+fn list_index_assignment_rejects_out_of_range_index() {
+    let Some(runtime) = supported_runtime() else {
+        return;
+    };
+    // `xs[i] = v` writes an existing element; an index at or past the end
+    // aborts rather than growing the list or writing out of bounds.
+    let example = build_example_binary("list_set_index_oob.rv", &runtime);
+    let output = Command::new(&example.binary)
+        .output()
+        .expect("run list_set_index_oob binary");
+    let stdout = String::from_utf8_lossy(&output.stdout).into_owned();
+    let stderr = String::from_utf8_lossy(&output.stderr).into_owned();
+    cleanup(&example.tmp);
+    assert_eq!(output.status.code(), Some(101), "stderr={stderr}");
+    assert_eq!(stdout, "25\n");
+    assert!(
+        stderr.contains("list index out of bounds"),
+        "expected an out-of-bounds abort, got stderr: {:?}",
+        stderr
+    );
+}
+
+#[test]
+fn list_remove_rejects_out_of_range_index() {
+    let Some(runtime) = supported_runtime() else {
+        return;
+    };
+    // `remove` shifts elements in place; an index at or past the end aborts
+    // with the method name, the index, and the length instead of popping.
+    let example = build_example_binary("list_remove_oob.rv", &runtime);
+    let output = Command::new(&example.binary)
+        .output()
+        .expect("run list_remove_oob binary");
+    let stdout = String::from_utf8_lossy(&output.stdout).into_owned();
+    let stderr = String::from_utf8_lossy(&output.stderr).into_owned();
+    cleanup(&example.tmp);
+    assert_eq!(output.status.code(), Some(101), "stderr={stderr}");
+    assert_eq!(stdout, "20\n");
+    assert!(
+        stderr.contains("List.remove index 2 out of bounds for length 2"),
+        "expected an out-of-bounds abort, got stderr: {:?}",
+        stderr
+    );
+}
+
+#[test]
+fn string_repeat_rejects_overflowing_length() {
+    let Some(runtime) = supported_runtime() else {
+        return;
+    };
+    // A repeat past the string length limit aborts with the sizes involved
+    // rather than reading back as an empty string.
+    let example = build_example_binary("string_repeat_overflow.rv", &runtime);
+    let output = Command::new(&example.binary)
+        .output()
+        .expect("run string_repeat_overflow binary");
+    let stdout = String::from_utf8_lossy(&output.stdout).into_owned();
+    let stderr = String::from_utf8_lossy(&output.stderr).into_owned();
+    cleanup(&example.tmp);
+    assert_eq!(output.status.code(), Some(101), "stderr={stderr}");
+    assert_eq!(stdout, "abab\n");
+    assert!(
+        stderr.contains("string repeat too long: 2 bytes * 3000000000"),
+        "expected a repeat overflow abort, got stderr: {:?}",
+        stderr
+    );
+}
+
 #[test]
 fn read_line_preserves_non_utf8() {
     use std::io::Write;
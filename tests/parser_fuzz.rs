@@ -0,0 +1,443 @@
+//! Property tests for the parser over generated programs.
+//!
+//! A small seeded generator builds random expression trees, prints each
+//! with the fewest parentheses the precedence table allows, and checks
+//! that parsing the text rebuilds the same tree, both directly and after
+//! a trip through the formatter. A second pass feeds the parser mangled
+//! programs and random token soup and checks that it answers with an
+//! error value (whose diagnostic renders) instead of panicking.
+//!
+//! The stream is deterministic so a failure reproduces. Set
+//! `RAVEN_PARSER_FUZZ_SEED` to explore a different stream and
+//! `RAVEN_PARSER_FUZZ_CASES` to change how many programs each test tries.
+
+use std::panic::{self, AssertUnwindSafe};
+use std::path::PathBuf;
+
+use raven::ast::{BinaryOp, DeclKind, Expr, ExprKind, File, UnaryOp};
+use raven::format::format_source;
+use raven::lexer::Lexer;
+use raven::macros::MacroTable;
+use raven::parser::{parse, parse_with_macros_all};
+
+/// splitmix64: tiny, seedable, and good enough to pick tree shapes.
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n as u64) as usize
+    }
+
+    fn pick<T: Copy>(&mut self, xs: &[T]) -> T {
+        xs[self.below(xs.len())]
+    }
+}
+
+fn env_or(name: &str, default: u64) -> u64 {
+    std::env::var(name)
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(default)
+}
+
+fn seed() -> u64 {
+    env_or("RAVEN_PARSER_FUZZ_SEED", 0x5eed_2a7e)
+}
+
+fn cases() -> usize {
+    env_or("RAVEN_PARSER_FUZZ_CASES", 400) as usize
+}
+
+/// Binary operators with their binding power, loosest first. Mirrors the
+/// ladder in `src/parser/expr.rs`; comparisons do not chain.
+const BINARY: &[(&str, u8)] = &[
+    ("||", 1),
+    ("&&", 2),
+    ("==", 3),
+    ("!=", 3),
+    ("<", 3),
+    ("<=", 3),
+    (">", 3),
+    (">=", 3),
+    ("|", 4),
+    ("^", 5),
+    ("&", 6),
+    ("<<", 7),
+    (">>", 7),
+    ("+", 8),
+    ("-", 8),
+    ("*", 9),
+    ("/", 9),
+    ("%", 9),
+];
+
+const COMPARISON: u8 = 3;
+
+const NAMES: &[&str] = &["a", "b", "count", "xs", "total"];
+const CALLEES: &[&str] = &["f", "max", "step"];
+const MEMBERS: &[&str] = &["len", "get", "size", "first"];
+
+/// A generated expression. `Paren` is an explicit, redundant pair of
+/// parentheses: it changes the text but not the tree.
+enum Gen {
+    Int(u32),
+    Bool(bool),
+    Name(&'static str),
+    Paren(Box<Gen>),
+    Unary(&'static str, Box<Gen>),
+    Binary(&'static str, u8, Box<Gen>, Box<Gen>),
+    Call(&'static str, Vec<Gen>),
+    Method(Box<Gen>, &'static str, Vec<Gen>),
+    Field(Box<Gen>, &'static str),
+    Index(Box<Gen>, Box<Gen>),
+}
+
+fn gen_expr(rng: &mut Rng, depth: u32) -> Gen {
+    if depth == 0 || rng.below(5) == 0 {
+        return match rng.below(4) {
+            0 => Gen::Int(rng.below(1000) as u32),
+            1 => Gen::Bool(rng.below(2) == 0),
+            _ => Gen::Name(rng.pick(NAMES)),
+        };
+    }
+    let d = depth - 1;
+    match rng.below(20) {
+        0..=9 => {
+            let (op, prec) = rng.pick(BINARY);
+            Gen::Binary(
+                op,
+                prec,
+                Box::new(gen_expr(rng, d)),
+                Box::new(gen_expr(rng, d)),
+            )
+        }
+        10 | 11 => Gen::Unary(rng.pick(&["-", "!"]), Box::new(gen_expr(rng, d))),
+        12 | 13 => {
+            let args = (0..rng.below(3)).map(|_| gen_expr(rng, d)).collect();
+            Gen::Call(rng.pick(CALLEES), args)
+        }
+        14 | 15 => {
+            let args = (0..rng.below(3)).map(|_| gen_expr(rng, d)).collect();
+            Gen::Method(Box::new(gen_expr(rng, d)), rng.pick(MEMBERS), args)
+        }
+        16 => Gen::Field(Box::new(gen_expr(rng, d)), rng.pick(MEMBERS)),
+        17 => Gen::Index(Box::new(gen_expr(rng, d)), Box::new(gen_expr(rng, d))),
+        _ => Gen::Paren(Box::new(gen_expr(rng, d))),
+    }
+}
+
+/// Binding power of `g` as an operand; atoms and postfix forms bind
+/// tighter than every binary operator.
+fn power(g: &Gen) -> u8 {
+    match g {
+        Gen::Binary(_, prec, _, _) => *prec,
+        Gen::Unary(..) => 10,
+        _ => 11,
+    }
+}
+
+fn parens(text: String, needed: bool) -> String {
+    if needed {
+        format!("({text})")
+    } else {
+        text
+    }
+}
+
+/// Print `g` as source with only the parentheses the grammar requires
+/// (plus any explicit `Paren` nodes).
+fn render(g: &Gen) -> String {
+    match g {
+        Gen::Int(n) => n.to_string(),
+        Gen::Bool(b) => b.to_string(),
+        Gen::Name(n) => n.to_string(),
+        Gen::Paren(inner) => format!("({})", render(inner)),
+        Gen::Unary(op, operand) => {
+            format!("{op}{}", parens(render(operand), power(operand) < 10))
+        }
+        Gen::Binary(op, prec, lhs, rhs) => {
+            let lp = power(lhs);
+            let rp = power(rhs);
+            let left_needs = lp < *prec || (lp == *prec && *prec == COMPARISON);
+            format!(
+                "{} {op} {}",
+                parens(render(lhs), left_needs),
+                parens(render(rhs), rp <= *prec)
+            )
+        }
+        Gen::Call(callee, args) => format!("{callee}({})", render_args(args)),
+        Gen::Method(recv, name, args) => {
+            format!("{}.{name}({})", receiver(recv), render_args(args))
+        }
+        Gen::Field(recv, name) => format!("{}.{name}", receiver(recv)),
+        Gen::Index(recv, index) => format!("{}[{}]", receiver(recv), render(index)),
+    }
+}
+
+/// A postfix receiver. An integer is wrapped too, so `1.len()` is never
+/// read as the start of a float literal.
+fn receiver(g: &Gen) -> String {
+    parens(render(g), power(g) < 11 || matches!(g, Gen::Int(_)))
+}
+
+fn render_args(args: &[Gen]) -> String {
+    args.iter().map(render).collect::<Vec<_>>().join(", ")
+}
+
+/// The tree `g` stands for, as an s-expression. Parentheses vanish.
+fn shape(g: &Gen) -> String {
+    match g {
+        Gen::Int(n) => n.to_string(),
+        Gen::Bool(b) => b.to_string(),
+        Gen::Name(n) => n.to_string(),
+        Gen::Paren(inner) => shape(inner),
+        Gen::Unary(op, operand) => {
+            let name = if *op == "-" { "neg" } else { "not" };
+            format!("({name} {})", shape(operand))
+        }
+        Gen::Binary(op, _, lhs, rhs) => format!("({op} {} {})", shape(lhs), shape(rhs)),
+        Gen::Call(callee, args) => format!("(call {callee}{})", shape_args(args)),
+        Gen::Method(recv, name, args) => {
+            format!("(method {} {name}{})", shape(recv), shape_args(args))
+        }
+        Gen::Field(recv, name) => format!("(field {} {name})", shape(recv)),
+        Gen::Index(recv, index) => format!("(index {} {})", shape(recv), shape(index)),
+    }
+}
+
+fn shape_args(args: &[Gen]) -> String {
+    args.iter().map(|a| format!(" {}", shape(a))).collect()
+}
+
+fn binary_text(op: &BinaryOp) -> &'static str {
+    match op {
+        BinaryOp::Add => "+",
+        BinaryOp::Sub => "-",
+        BinaryOp::Mul => "*",
+        BinaryOp::Div => "/",
+        BinaryOp::Mod => "%",
+        BinaryOp::Eq => "==",
+        BinaryOp::Ne => "!=",
+        BinaryOp::EqIgnoreCase => "==~",
+        BinaryOp::NeIgnoreCase => "!=~",
+        BinaryOp::Lt => "<",
+        BinaryOp::Le => "<=",
+        BinaryOp::Gt => ">",
+        BinaryOp::Ge => ">=",
+        BinaryOp::And => "&&",
+        BinaryOp::Or => "||",
+        BinaryOp::BitAnd => "&",
+        BinaryOp::BitOr => "|",
+        BinaryOp::BitXor => "^",
+        BinaryOp::Shl => "<<",
+        BinaryOp::Shr => ">>",
+    }
+}
+
+/// The parsed tree in the same notation as [`shape`]. Anything the
+/// generator never produces prints as `?` so a mismatch stays readable.
+fn shape_of(e: &Expr) -> String {
+    match &e.kind {
+        ExprKind::Int(n) => n.to_string(),
+        ExprKind::Bool(b) => b.to_string(),
+        ExprKind::Ident { name, .. } => name.clone(),
+        ExprKind::Paren(inner) => shape_of(inner),
+        ExprKind::Unary { op, operand } => {
+            let name = match op {
+                UnaryOp::Neg => "neg",
+                UnaryOp::Not => "not",
+                _ => "?",
+            };
+            format!("({name} {})", shape_of(operand))
+        }
+        ExprKind::Binary { op, lhs, rhs } => {
+            format!("({} {} {})", binary_text(op), shape_of(lhs), shape_of(rhs))
+        }
+        ExprKind::Call { callee, args } => {
+            format!("(call {}{})", shape_of(callee), shape_of_args(args))
+        }
+        ExprKind::MethodCall {
+            receiver,
+            name,
+            args,
+            ..
+        } => format!(
+            "(method {} {name}{})",
+            shape_of(receiver),
+            shape_of_args(args)
+        ),
+        ExprKind::Field { receiver, name } => format!("(field {} {name})", shape_of(receiver)),
+        ExprKind::Index { receiver, index } => {
+            format!("(index {} {})", shape_of(receiver), shape_of(index))
+        }
+        _ => "?".to_string(),
+    }
+}
+
+fn shape_of_args(args: &[Expr]) -> String {
+    args.iter().map(|a| format!(" {}", shape_of(a))).collect()
+}
+
+fn parse_source(src: &str) -> Result<File, String> {
+    let tokens = Lexer::new(src, PathBuf::from("fuzz.rv"))
+        .tokenize()
+        .map_err(|e| e.display(src))?;
+    parse(&tokens).map_err(|e| e.display(src))
+}
+
+/// The shape of the initializer of `let v = ...`, the only item in `src`.
+fn parsed_shape(src: &str) -> Result<String, String> {
+    let file = parse_source(src)?;
+    match file.items.as_slice() {
+        [item] => match &item.kind {
+            DeclKind::Let(l) => l
+                .init
+                .as_ref()
+                .map(shape_of)
+                .ok_or_else(|| "let without an initializer".to_string()),
+            _ => Err("expected a single `let`".to_string()),
+        },
+        _ => Err(format!("expected one item, got {}", file.items.len())),
+    }
+}
+
+fn report(failures: &[String]) {
+    assert!(
+        failures.is_empty(),
+        "{} of {} cases failed (seed {:#x}); first failures:\n\n{}",
+        failures.len(),
+        cases(),
+        seed(),
+        failures
+            .iter()
+            .take(5)
+            .cloned()
+            .collect::<Vec<_>>()
+            .join("\n\n")
+    );
+}
+
+#[test]
+fn printed_expressions_parse_back_to_the_same_tree() {
+    let mut rng = Rng(seed());
+    let mut failures = Vec::new();
+    for _ in 0..cases() {
+        let g = gen_expr(&mut rng, 6);
+        let src = format!("let v = {}\n", render(&g));
+        let expected = shape(&g);
+        match parsed_shape(&src) {
+            Ok(got) if got == expected => {}
+            Ok(got) => failures.push(format!(
+                "source: {src}expected: {expected}\n     got: {got}"
+            )),
+            Err(e) => failures.push(format!("source: {src}parse failed: {e}")),
+        }
+    }
+    report(&failures);
+}
+
+#[test]
+fn formatting_preserves_the_parsed_tree() {
+    let mut rng = Rng(seed() ^ 0xf0f0);
+    let mut failures = Vec::new();
+    for _ in 0..cases() {
+        let g = gen_expr(&mut rng, 6);
+        let src = format!("let v = {}\n", render(&g));
+        let expected = shape(&g);
+        let formatted = match format_source(&src) {
+            Ok(f) => f,
+            Err(e) => {
+                failures.push(format!("source: {src}format failed: {e}"));
+                continue;
+            }
+        };
+        match parsed_shape(&formatted) {
+            Ok(got) if got == expected => {}
+            Ok(got) => failures.push(format!(
+                "source: {src}formatted: {formatted}expected: {expected}\n     got: {got}"
+            )),
+            Err(e) => failures.push(format!("formatted: {formatted}reparse failed: {e}")),
+        }
+    }
+    report(&failures);
+}
+
+/// Token spellings for soup: enough punctuation and keywords to reach
+/// most parser entry points, including the ones that used to panic.
+const SOUP: &[&str] = &[
+    "fun", "let", "const", "struct", "enum", "impl", "trait", "for", "in", "while", "if", "else",
+    "match", "return", "import", "extern", "macro", "self", "Self", "typeof", "a", "Point", "0",
+    "1.5", "\"s\"", "'c'", "true", "(", ")", "{", "}", "[", "]", "<", ">", ",", ".", "..", "..=",
+    ":", "::", ";", "=", "==", "=>", "->", "+", "-", "*", "/", "!", "&", "|", "?", "@", "$", "\n",
+];
+
+/// Parse `src` every way the compiler and formatter do and insist that no
+/// path panics. Returns the panic message when one does.
+fn parse_without_panicking(src: &str) -> Option<String> {
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        let Ok(tokens) = Lexer::new(src, PathBuf::from("fuzz.rv")).tokenize() else {
+            return;
+        };
+        if let Err(e) = parse(&tokens) {
+            let _ = e.display(src);
+        }
+        if let Err(errors) = parse_with_macros_all(&tokens, MacroTable::default()) {
+            assert!(!errors.is_empty(), "recovery returned an empty error list");
+            for e in errors {
+                let _ = e.display(src);
+            }
+        }
+        let _ = format_source(src);
+    }));
+    result.err().map(|payload| {
+        payload
+            .downcast_ref::<String>()
+            .cloned()
+            .or_else(|| payload.downcast_ref::<&str>().map(|s| s.to_string()))
+            .unwrap_or_else(|| "non-string panic".to_string())
+    })
+}
+
+#[test]
+fn invalid_programs_report_errors_instead_of_panicking() {
+    let mut rng = Rng(seed() ^ 0xbad);
+    let mut failures = Vec::new();
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+    for case in 0..cases() {
+        let src = if case % 2 == 0 {
+            // Token soup, sometimes inside a function body.
+            let soup: Vec<&str> = (0..1 + rng.below(30)).map(|_| rng.pick(SOUP)).collect();
+            let soup = soup.join(" ");
+            if rng.below(2) == 0 {
+                soup
+            } else {
+                format!("fun main() {{\n    {soup}\n}}\n")
+            }
+        } else {
+            // A valid program with one cut or one stray token spliced in.
+            let g = gen_expr(&mut rng, 5);
+            let mut src = format!("fun main() {{\n    let v = {}\n}}\n", render(&g));
+            let at = rng.below(src.len());
+            if rng.below(2) == 0 {
+                src.replace_range(at..at + 1 + rng.below((src.len() - at).min(8)), "");
+            } else {
+                src.insert_str(at, rng.pick(SOUP));
+            }
+            src
+        };
+        if let Some(message) = parse_without_panicking(&src) {
+            failures.push(format!("source: {src:?}\npanicked: {message}"));
+        }
+    }
+    panic::set_hook(default_hook);
+    report(&failures);
+}
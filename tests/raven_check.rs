@@ -0,0 +1,98 @@
+//! End to end tests for `raven check`.
+//!
+//! `check` stops after type checking, so these need no runtime staticlib.
+//! They pin the exit status a CI step relies on: 0 for a clean program or
+//! one with only warnings, and 1 for any error.
+
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+fn workdir() -> PathBuf {
+    use std::sync::atomic::{AtomicU64, Ordering};
+    static SEQ: AtomicU64 = AtomicU64::new(0);
+    let mut p = std::env::temp_dir();
+    let seq = SEQ.fetch_add(1, Ordering::Relaxed);
+    p.push(format!("raven-check-{}-{}", std::process::id(), seq));
+    std::fs::create_dir_all(&p).expect("create tempdir");
+    p
+}
+
+fn raven_check(source: &str) -> std::process::Output {
+    let dir = workdir();
+    let path = dir.join("main.rv");
+    std::fs::write(&path, source).expect("write source");
+    let out = Command::new(env!("CARGO_BIN_EXE_raven"))
+        .arg("check")
+        .arg(&path)
+        .output()
+        .expect("run raven check");
+    std::fs::remove_dir_all(&dir).ok();
+    out
+}
+
+#[test]
+fn a_clean_program_exits_zero() {
+    let out = raven_check("fun main() {\n    print(1 + 2)\n}\n");
+    assert!(
+        out.status.success(),
+        "stderr: {}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    assert!(out.stdout.is_empty());
+}
+
+#[test]
+fn a_type_error_exits_one_with_the_diagnostic() {
+    let out = raven_check("fun main() {\n    let x: Int = \"a\"\n}\n");
+    assert_eq!(out.status.code(), Some(1));
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(stderr.contains("error"), "stderr: {}", stderr);
+}
+
+#[test]
+fn a_parse_error_exits_one() {
+    let out = raven_check("fun main( {\n");
+    assert_eq!(out.status.code(), Some(1));
+}
+
+#[test]
+fn an_unused_function_warns_but_exits_zero() {
+    let out = raven_check("fun helper() {}\n\nfun main() {\n    print(1)\n}\n");
+    assert!(out.status.success());
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(
+        stderr.contains("warning: function `helper` is never used"),
+        "stderr: {}",
+        stderr
+    );
+}
+
+#[test]
+fn stdin_source_is_checked_under_its_filename() {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_raven"))
+        .args(["check", "-", "--filename", "piped.rv"])
+        .stdin(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("run raven check");
+    child
+        .stdin
+        .take()
+        .expect("stdin")
+        .write_all(b"fun main() {\n    let x: Int = true\n}\n")
+        .expect("write stdin");
+    let out = child.wait_with_output().expect("wait");
+    assert_eq!(out.status.code(), Some(1));
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(stderr.contains("piped.rv"), "stderr: {}", stderr);
+}
+
+#[test]
+fn an_error_inside_an_interpolation_points_at_the_string() {
+    let out = raven_check("fun main() {\n    let n = 1\n    print(\"n = ${n.nope(\", \")}\")\n}\n");
+    assert_eq!(out.status.code(), Some(1));
+    let stderr = String::from_utf8_lossy(&out.stderr);
+    assert!(stderr.contains("main.rv:3:11"), "stderr: {}", stderr);
+    assert!(!stderr.contains("<interp:"), "stderr: {}", stderr);
+}
@@ -0,0 +1,43 @@
+//! End to end tests for `raven --version`.
+//!
+//! The plain form is a single line that scripts parse; the verbose form adds
+//! the build facts a bug report needs.
+
+use std::process::Command;
+
+fn raven(args: &[&str]) -> std::process::Output {
+    Command::new(env!("CARGO_BIN_EXE_raven"))
+        .args(args)
+        .output()
+        .expect("run raven")
+}
+
+#[test]
+fn plain_version_is_one_line() {
+    let out = raven(&["--version"]);
+    assert!(out.status.success());
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert_eq!(stdout, format!("raven {}\n", env!("CARGO_PKG_VERSION")));
+}
+
+#[test]
+fn verbose_version_reports_the_build() {
+    for args in [&["--version", "--verbose"][..], &["-vV"][..]] {
+        let out = raven(args);
+        assert!(out.status.success(), "{:?}", args);
+        let stdout = String::from_utf8_lossy(&out.stdout);
+        let keys: Vec<&str> = stdout
+            .lines()
+            .skip(1)
+            .filter_map(|l| l.split_once(": ").map(|(k, _)| k))
+            .collect();
+        assert_eq!(keys, ["commit", "rustc", "target", "host"], "{}", stdout);
+        assert!(stdout.contains(std::env::consts::OS), "{}", stdout);
+    }
+}
+
+#[test]
+fn unknown_version_argument_is_rejected() {
+    let out = raven(&["--version", "--bogus"]);
+    assert_eq!(out.status.code(), Some(2));
+}